@@ -1,8 +1,29 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}, time::Duration};
 use anyhow::Result;
+use atrium_api::{
+    agent::Session,
+    app::bsky::{
+        actor::{defs::ProfileViewDetailed, get_profile},
+        feed::{get_author_feed, get_post_thread},
+        notification::list_notifications,
+    },
+    types::{string::{Did, Handle}, LimitedU16},
+};
 use bsky_sdk::agent::{config::{Config, FileStore}, BskyAgent};
 use secrecy::{ExposeSecret, SecretString};
 
+use super::{bluesky_client::{BlueskyClient, TrendingTopic}, handle_cache::{HandleCache, HANDLE_CACHE_PATH}, offline_cache::{actor_key, OfflineCache, OFFLINE_CACHE_PATH}, rate_limit::{RateLimitStatus, RateLimitedClient}};
+
 const CONFIG_PATH: &str = "config.json";
+const DEFAULT_ENDPOINT: &str = "https://bsky.social";
+// Serves app.bsky reads without a session. Used for endpoints newer than the
+// generated atrium-api bindings
+const PUBLIC_APPVIEW: &str = "https://public.api.bsky.app";
+
+// app.bsky.feed.getPosts takes at most this many URIs per call
+const MAX_GET_POSTS_URIS: usize = 25;
+// Reads that land this close together share one write of the offline cache
+const OFFLINE_SAVE_DELAY: Duration = Duration::from_secs(10);
 
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -25,29 +46,232 @@ pub enum ApiError {
     Unknown(String),
 }
 
+// True when a request never reached the server, as opposed to the server
+// turning it down. Some errors only survive as text once they've been wrapped
+pub fn is_connectivity_error(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect() || e.is_timeout())
+            || cause.to_string().contains("error sending request")
+    })
+}
+
 #[derive(Clone)]
 pub struct API {
-    pub agent: BskyAgent,
+    pub agent: BskyAgent<RateLimitedClient>,
+    client: RateLimitedClient,
+    pub handle_cache: Arc<Mutex<HandleCache>>,
+    offline_cache: Arc<Mutex<OfflineCache>>,
+    // Set while reads are being served from the offline cache
+    offline: Arc<AtomicBool>,
+    // Set while a write of the offline cache is waiting to happen
+    offline_save_pending: Arc<AtomicBool>,
 }
 
 impl API {
     pub async fn new() -> Result<Self> {
-        let agent_builder = BskyAgent::builder();
-        if let Ok(config) = Config::load(&FileStore::new(CONFIG_PATH)).await {
-            if let Ok(agent) = agent_builder.config(config).build().await {
-                return Ok(Self { agent } );
-            } else {
-                let agent_builder = BskyAgent::builder();
-                let agent = agent_builder.build().await?;
-                return Ok(Self { agent } );
+        let handle_cache = Arc::new(Mutex::new(HandleCache::load(HANDLE_CACHE_PATH)));
+        let offline_cache = OfflineCache::load(OFFLINE_CACHE_PATH);
+        let client = RateLimitedClient::new(DEFAULT_ENDPOINT);
+        let agent_builder = BskyAgent::builder().client(client.clone());
+        let (agent, offline) = match Config::load(&FileStore::new(CONFIG_PATH)).await {
+            Ok(config) => {
+                let had_session = config.session.is_some();
+                match agent_builder.config(config).build().await {
+                    Ok(agent) => (agent, false),
+                    Err(e) => {
+                        // Resuming a saved session needs the network. Without it,
+                        // read what we saved last time instead of asking for a login
+                        log::info!("Could not resume session: {}", e);
+                        let agent = BskyAgent::builder().client(client.clone()).build().await?;
+                        (agent, had_session && !offline_cache.is_empty())
+                    }
+                }
+            }
+            Err(_) => (agent_builder.build().await?, false),
+        };
+        Ok(Self {
+            agent,
+            client,
+            handle_cache,
+            offline_cache: Arc::new(Mutex::new(offline_cache)),
+            offline: Arc::new(AtomicBool::new(offline)),
+            offline_save_pending: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    // Successful reads are saved for offline use. When a read fails because
+    // we can't reach the server and we have a saved copy, that's returned
+    // instead and we go offline until the next read succeeds
+    fn through_offline_cache<T>(
+        &self,
+        result: Result<T>,
+        save: impl FnOnce(&mut OfflineCache, &T),
+        load: impl FnOnce(&OfflineCache) -> Option<T>,
+    ) -> Result<T> {
+        match result {
+            Ok(value) => {
+                self.offline.store(false, Ordering::Relaxed);
+                save(&mut self.offline_cache.lock().unwrap(), &value);
+                self.schedule_offline_save();
+                Ok(value)
+            }
+            Err(e) if is_connectivity_error(&e) => match load(&self.offline_cache.lock().unwrap()) {
+                Some(value) => {
+                    log::info!("Showing saved copy, request failed: {}", e);
+                    self.offline.store(true, Ordering::Relaxed);
+                    Ok(value)
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    // Write the offline cache out a little later on its own task, taking in
+    // any other reads that come in meanwhile
+    fn schedule_offline_save(&self) {
+        if self.offline_save_pending.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let cache = self.offline_cache.clone();
+        let pending = self.offline_save_pending.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(OFFLINE_SAVE_DELAY).await;
+            pending.store(false, Ordering::Relaxed);
+            let serialized = cache.lock().unwrap().serialize();
+            let result = match serialized {
+                Ok((path, contents)) => tokio::fs::write(path, contents).await.map_err(anyhow::Error::from),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to save offline cache: {}", e);
+            }
+        });
+    }
+
+    // Find @handle mentions in post text and turn them into mention facets.
+    // Handles that fail to resolve are left as plain text.
+    async fn build_mention_facets(&self, text: &str) -> Vec<atrium_api::app::bsky::richtext::facet::Main> {
+        let mut facets = Vec::new();
+
+        for (byte_start, byte_end) in Self::find_mentions(text) {
+            let handle = &text[byte_start + 1..byte_end];
+            match self.resolve_handle(handle).await {
+                Ok(did) => facets.push(atrium_api::app::bsky::richtext::facet::MainData {
+                    features: vec![atrium_api::types::Union::Refs(
+                        atrium_api::app::bsky::richtext::facet::MainFeaturesItem::Mention(Box::new(
+                            atrium_api::app::bsky::richtext::facet::MentionData { did }.into(),
+                        )),
+                    )],
+                    index: atrium_api::app::bsky::richtext::facet::ByteSliceData {
+                        byte_end,
+                        byte_start,
+                    }.into(),
+                }.into()),
+                Err(e) => log::info!("Could not resolve mention @{}: {}", handle, e),
+            }
+        }
+
+        facets
+    }
+
+    // Byte ranges (including the '@') of things that look like handle mentions
+    fn find_mentions(text: &str) -> Vec<(usize, usize)> {
+        let mut mentions = Vec::new();
+        let mut previous: Option<char> = None;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((start, c)) = chars.next() {
+            let at_word_start = previous.is_none_or(|p| p.is_whitespace() || p == '(');
+            previous = Some(c);
+            if c != '@' || !at_word_start {
+                continue;
+            }
+
+            let mut end = start + 1;
+            while let Some(&(i, next)) = chars.peek() {
+                if next.is_ascii_alphanumeric() || next == '.' || next == '-' {
+                    end = i + next.len_utf8();
+                    previous = Some(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            let handle = text[start + 1..end].trim_end_matches('.');
+            if handle.contains('.') {
+                mentions.push((start, start + 1 + handle.len()));
             }
+        }
+
+        mentions
+    }
+}
+
+impl BlueskyClient for API {
+    // Quota from the most recent response, shared by every clone of the API
+    fn rate_limit(&self) -> Option<RateLimitStatus> {
+        self.client.status()
+    }
+
+    async fn resolve_handle(&self, handle: &str) -> Result<Did> {
+        let handle = handle.trim_start_matches('@').to_lowercase();
+        let cached = self.handle_cache.lock().unwrap().get_did(&handle);
+        if let Some(did) = cached {
+            return Did::new(did).map_err(|e| anyhow::anyhow!(e));
+        }
+
+        let params = atrium_api::com::atproto::identity::resolve_handle::ParametersData {
+            handle: Handle::new(handle.clone()).map_err(|e| anyhow::anyhow!(e))?,
+        };
+        let output = self.agent.api.com.atproto.identity.resolve_handle(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+
+        let mut cache = self.handle_cache.lock().unwrap();
+        cache.insert(&handle, output.did.as_str());
+        if let Err(e) = cache.save() {
+            log::warn!("Failed to save handle cache: {}", e);
+        }
+        Ok(output.did.clone())
+    }
+
+    async fn resolve_did_handle(&self, did: &str) -> Result<String> {
+        let cached = self.handle_cache.lock().unwrap().get_handle(did);
+        if let Some(handle) = cached {
+            return Ok(handle);
+        }
+
+        let params = atrium_api::app::bsky::actor::get_profile::ParametersData {
+            actor: atrium_api::types::string::AtIdentifier::Did(
+                Did::new(did.to_string()).map_err(|e| anyhow::anyhow!(e))?
+            ),
+        };
+        let profile = self.agent.api.app.bsky.actor.get_profile(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        let handle = profile.handle.to_string();
+
+        let mut cache = self.handle_cache.lock().unwrap();
+        cache.insert(&handle, did);
+        if let Err(e) = cache.save() {
+            log::warn!("Failed to save handle cache: {}", e);
+        }
+        Ok(handle)
+    }
+
+    async fn resolve_actor(&self, actor: &str) -> Result<Did> {
+        if actor.starts_with("did:") {
+            Did::new(actor.to_string()).map_err(|e| anyhow::anyhow!(e))
         } else {
-            let agent = agent_builder.build().await?;
-            return Ok(Self { agent } );
+            self.resolve_handle(actor).await
         }
     }
 
-    pub async fn login(&mut self, identifier: String, password: SecretString) -> Result<()> {
+    async fn session(&self) -> Option<Session> {
+        self.agent.get_session().await
+    }
+
+    async fn login(&mut self, identifier: String, password: SecretString) -> Result<()> {
         match self.agent.login(&identifier, password.expose_secret()).await {
             Ok(_) => {
                 self.agent.to_config().await.save(&FileStore::new(CONFIG_PATH))
@@ -62,19 +286,48 @@ impl API {
             },
         }
     }
-    
-    pub async fn logout(&mut self) -> Result<()> {
+
+    async fn logout(&mut self) -> Result<()> {
         // Clear the stored session file
         tokio::fs::remove_file(CONFIG_PATH).await.ok(); // Use ok() to ignore if file doesn't exist
         
         // Create a fresh agent
-        let agent_builder = BskyAgent::builder();
+        let agent_builder = BskyAgent::builder().client(self.client.clone());
         self.agent = agent_builder.build().await?;
+
+        if let Err(e) = self.offline_cache.lock().unwrap().clear() {
+            log::warn!("Failed to clear offline cache: {}", e);
+        }
+        self.offline.store(false, Ordering::Relaxed);
         
         Ok(())
     }
 
-    pub async fn get_timeline(
+    fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::Relaxed)
+    }
+
+    async fn flush_offline_cache(&self) -> Result<()> {
+        // Nothing read since the last save
+        if !self.offline_save_pending.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        let (path, contents) = self.offline_cache.lock().unwrap().serialize()?;
+        tokio::fs::write(path, contents).await?;
+        Ok(())
+    }
+
+    async fn get_profile(&self, params: get_profile::ParametersData) -> Result<ProfileViewDetailed> {
+        let key = actor_key(&params.actor);
+        let result = self.agent.api.app.bsky.actor.get_profile(params.into()).await.map_err(anyhow::Error::from);
+        self.through_offline_cache(
+            result,
+            |cache, profile| cache.set_profile(&key, profile),
+            |cache| cache.profile(&key),
+        )
+    }
+
+    async fn get_timeline(
         &self,
         cursor: Option<String>,
     ) -> Result<(Vec<atrium_api::app::bsky::feed::defs::FeedViewPost>, Option<String>)> {
@@ -83,8 +336,60 @@ impl API {
             cursor,
             limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
         };
+        let first_page = params.cursor.is_none();
     
-        match self.agent.api.app.bsky.feed.get_timeline(params.into()).await {
+        let result = match self.agent.api.app.bsky.feed.get_timeline(params.into()).await {
+            Ok(response) => Ok((response.feed.clone(), response.cursor.clone())),
+            Err(e) => match e {
+                _ if e.to_string().contains("rate limit") => Err(ApiError::RateLimited.into()),
+                _ if e.to_string().contains("unauthorized") => Err(ApiError::SessionExpired.into()),
+                _ => Err(ApiError::NetworkError(e.to_string()).into()),
+            },
+        };
+        // Only the newest page is kept; there's no cursor to page on from offline
+        if !first_page {
+            return result;
+        }
+        self.through_offline_cache(
+            result,
+            |cache, (posts, _)| cache.set_timeline(posts),
+            |cache| cache.timeline().map(|posts| (posts, None)),
+        )
+    }
+
+    async fn get_actor_likes(
+        &self,
+        actor: atrium_api::types::string::AtIdentifier,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::feed::defs::FeedViewPost>, Option<String>)> {
+        let params = atrium_api::app::bsky::feed::get_actor_likes::ParametersData {
+            actor,
+            cursor,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+        };
+
+        match self.agent.api.app.bsky.feed.get_actor_likes(params.into()).await {
+            Ok(response) => Ok((response.feed.clone(), response.cursor.clone())),
+            Err(e) => match e {
+                _ if e.to_string().contains("rate limit") => Err(ApiError::RateLimited.into()),
+                _ if e.to_string().contains("unauthorized") => Err(ApiError::SessionExpired.into()),
+                _ => Err(ApiError::NetworkError(e.to_string()).into()),
+            },
+        }
+    }
+
+    async fn get_feed(
+        &self,
+        feed: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::feed::defs::FeedViewPost>, Option<String>)> {
+        let params = atrium_api::app::bsky::feed::get_feed::ParametersData {
+            cursor,
+            feed: feed.to_string(),
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+        };
+
+        match self.agent.api.app.bsky.feed.get_feed(params.into()).await {
             Ok(response) => Ok((response.feed.clone(), response.cursor.clone())),
             Err(e) => match e {
                 _ if e.to_string().contains("rate limit") => Err(ApiError::RateLimited.into()),
@@ -94,7 +399,186 @@ impl API {
         }
     }
 
-    pub async fn like_post(&self, uri: &str, cid: &atrium_api::types::string::Cid) -> Result<()> {
+    async fn get_saved_feeds(&self) -> Result<Vec<(String, String)>> {
+        use atrium_api::app::bsky::actor::{defs::PreferencesItem, get_preferences};
+        use atrium_api::app::bsky::feed::get_feed_generators;
+        use atrium_api::types::Union;
+
+        let preferences = self.agent.api.app.bsky.actor
+            .get_preferences(get_preferences::ParametersData {}.into())
+            .await?;
+        // Saved lists and the built-in timeline share this preference; only generators can be opened here
+        let uris: Vec<String> = preferences.preferences.iter()
+            .filter_map(|preference| match preference {
+                Union::Refs(PreferencesItem::SavedFeedsPrefV2(saved)) => Some(saved.items.clone()),
+                _ => None,
+            })
+            .flatten()
+            .filter(|item| item.r#type == "feed")
+            .map(|item| item.value.clone())
+            .collect();
+        if uris.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let generators = self.agent.api.app.bsky.feed
+            .get_feed_generators(get_feed_generators::ParametersData { feeds: uris.clone() }.into())
+            .await?;
+        // The lookup doesn't promise to keep our order
+        Ok(uris.iter()
+            .filter_map(|uri| generators.feeds.iter().find(|generator| &generator.uri == uri))
+            .map(|generator| (generator.display_name.clone(), generator.uri.clone()))
+            .collect())
+    }
+
+    async fn search_posts(
+        &self,
+        query: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::feed::defs::FeedViewPost>, Option<String>)> {
+        let params = atrium_api::app::bsky::feed::search_posts::ParametersData {
+            author: None,
+            cursor,
+            domain: None,
+            lang: None,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+            mentions: None,
+            q: query.to_string(),
+            since: None,
+            sort: Some("latest".to_string()),
+            tag: None,
+            until: None,
+            url: None,
+        };
+
+        match self.agent.api.app.bsky.feed.search_posts(params.into()).await {
+            Ok(response) => {
+                let posts = response.posts.iter()
+                    .map(|post| atrium_api::app::bsky::feed::defs::FeedViewPostData {
+                        feed_context: None,
+                        post: post.clone(),
+                        reason: None,
+                        reply: None,
+                    }.into())
+                    .collect();
+                Ok((posts, response.cursor.clone()))
+            }
+            Err(e) => match e {
+                _ if e.to_string().contains("rate limit") => Err(ApiError::RateLimited.into()),
+                _ if e.to_string().contains("unauthorized") => Err(ApiError::SessionExpired.into()),
+                _ => Err(ApiError::NetworkError(e.to_string()).into()),
+            },
+        }
+    }
+
+    async fn get_trending_topics(&self) -> Result<Vec<TrendingTopic>> {
+        #[derive(serde::Deserialize)]
+        struct Output {
+            topics: Vec<TrendingTopic>,
+        }
+
+        let url = format!("{}/xrpc/app.bsky.unspecced.getTrendingTopics?limit=25", PUBLIC_APPVIEW);
+        let response = reqwest::Client::new()
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        let body = response.text().await.map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        let output: Output = serde_json::from_str(&body)?;
+        Ok(output.topics)
+    }
+
+    async fn get_author_feed(&self, params: get_author_feed::ParametersData) -> Result<get_author_feed::Output> {
+        // Only the first page of a profile as it's shown, not filtered polls
+        let key = (params.cursor.is_none() && params.filter.is_none()).then(|| actor_key(&params.actor));
+        let result = self.agent.api.app.bsky.feed.get_author_feed(params.into()).await.map_err(anyhow::Error::from);
+        match key {
+            Some(key) => self.through_offline_cache(
+                result,
+                |cache, feed| cache.set_author_feed(&key, feed),
+                |cache| cache.author_feed(&key),
+            ),
+            None => result,
+        }
+    }
+
+    async fn get_post_thread(&self, params: get_post_thread::ParametersData) -> Result<get_post_thread::Output> {
+        // Partial fetches for load more rows would overwrite the whole thread
+        let whole_thread = params.depth == Some(LimitedU16::MAX) && params.parent_height == Some(LimitedU16::MAX);
+        let key = params.uri.clone();
+        let result = self.agent.api.app.bsky.feed.get_post_thread(params.into()).await.map_err(anyhow::Error::from);
+        if !whole_thread {
+            return result;
+        }
+        self.through_offline_cache(
+            result,
+            |cache, thread| cache.set_thread(&key, thread),
+            |cache| cache.thread(&key),
+        )
+    }
+
+    async fn list_notifications(&self, params: list_notifications::ParametersData) -> Result<list_notifications::Output> {
+        Ok(self.agent.api.app.bsky.notification.list_notifications(params.into()).await?)
+    }
+
+    async fn get_follows(
+        &self,
+        actor: atrium_api::types::string::AtIdentifier,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::actor::defs::ProfileView>, Option<String>)> {
+        let params = atrium_api::app::bsky::graph::get_follows::ParametersData {
+            actor,
+            cursor,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+        };
+
+        match self.agent.api.app.bsky.graph.get_follows(params.into()).await {
+            Ok(response) => Ok((response.follows.clone(), response.cursor.clone())),
+            Err(e) => match e {
+                _ if e.to_string().contains("rate limit") => Err(ApiError::RateLimited.into()),
+                _ if e.to_string().contains("unauthorized") => Err(ApiError::SessionExpired.into()),
+                _ => Err(ApiError::NetworkError(e.to_string()).into()),
+            },
+        }
+    }
+
+    async fn get_likers(
+        &self,
+        uri: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::actor::defs::ProfileView>, Option<String>)> {
+        let params = atrium_api::app::bsky::feed::get_likes::ParametersData {
+            cid: None,
+            cursor,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+            uri: uri.to_string(),
+        };
+
+        let response = self.agent.api.app.bsky.feed.get_likes(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        let likers = response.likes.iter().map(|like| like.actor.clone()).collect();
+        Ok((likers, response.cursor.clone()))
+    }
+
+    async fn get_reposters(
+        &self,
+        uri: &str,
+        cursor: Option<String>,
+    ) -> Result<(Vec<atrium_api::app::bsky::actor::defs::ProfileView>, Option<String>)> {
+        let params = atrium_api::app::bsky::feed::get_reposted_by::ParametersData {
+            cid: None,
+            cursor,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+            uri: uri.to_string(),
+        };
+
+        let response = self.agent.api.app.bsky.feed.get_reposted_by(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        Ok((response.reposted_by.clone(), response.cursor.clone()))
+    }
+
+    async fn like_post(&self, uri: &str, cid: &atrium_api::types::string::Cid) -> Result<String> {
         let record_data = atrium_api::app::bsky::feed::like::RecordData {
             created_at: atrium_api::types::string::Datetime::now(),
             subject: atrium_api::com::atproto::repo::strong_ref::MainData{
@@ -103,11 +587,11 @@ impl API {
             }.into(),
         };
     
-        self.agent.create_record(record_data).await?;
-        Ok(())
+        let output = self.agent.create_record(record_data).await?;
+        Ok(output.uri.clone())
     }
 
-    pub async fn unlike_post(&self, post: &atrium_api::app::bsky::feed::defs::PostViewData) -> Result<()> {
+    async fn unlike_post(&self, post: &atrium_api::app::bsky::feed::defs::PostViewData) -> Result<()> {
         if let Some(viewer) = &post.viewer {
             if let Some(like) = &viewer.like {
                 self.agent.delete_record(like).await?;
@@ -116,7 +600,7 @@ impl API {
         return Ok(());
     }
 
-    pub async fn repost(&self, uri: &str, cid: &atrium_api::types::string::Cid) -> Result<()> {
+    async fn repost(&self, uri: &str, cid: &atrium_api::types::string::Cid) -> Result<String> {
         let record_data = atrium_api::app::bsky::feed::repost::RecordData {
             created_at: atrium_api::types::string::Datetime::now(),
             subject: atrium_api::com::atproto::repo::strong_ref::MainData {
@@ -125,13 +609,15 @@ impl API {
             }.into(),
         };
         match self.agent.create_record(record_data).await {
-            Ok(_) => {},
-            Err(e) => {log::info!("error reposting: {:?}", e)}
+            Ok(output) => Ok(output.uri.clone()),
+            Err(e) => {
+                log::info!("error reposting: {:?}", e);
+                Err(e.into())
+            }
         }
-        Ok(())
     }
 
-    pub async fn unrepost(&self, post: &atrium_api::app::bsky::feed::defs::PostViewData) -> Result<()> {
+    async fn unrepost(&self, post: &atrium_api::app::bsky::feed::defs::PostViewData) -> Result<()> {
         if let Some(viewer) = &post.viewer {
             if let Some(repost) = &viewer.repost {
                 self.agent.delete_record(repost).await?;
@@ -140,20 +626,29 @@ impl API {
         return Ok(());
     }
 
-    pub async fn get_post(&self, uri: &str) -> Result<atrium_api::types::Object<atrium_api::app::bsky::feed::defs::PostViewData>> {
-        let get_posts_result = self.agent.api.app.bsky.feed.get_posts(
-            atrium_api::app::bsky::feed::get_posts::ParametersData {
-                uris: vec![uri.to_string()],
-            }.into()
-        ).await;
-        if let Ok(post_data) = get_posts_result {
-            return Ok(post_data.data.posts[0].clone());
-        } else {
-            return Err(anyhow::anyhow!("Failed to get post"));
+    async fn get_post(&self, uri: &str) -> Result<atrium_api::types::Object<atrium_api::app::bsky::feed::defs::PostViewData>> {
+        self.get_posts(&[uri.to_string()])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Failed to get post"))
+    }
+
+    // getPosts takes at most 25 URIs, so split bigger batches
+    async fn get_posts(&self, uris: &[String]) -> Result<Vec<atrium_api::app::bsky::feed::defs::PostView>> {
+        let mut posts = Vec::with_capacity(uris.len());
+        for chunk in uris.chunks(MAX_GET_POSTS_URIS) {
+            let response = self.agent.api.app.bsky.feed.get_posts(
+                atrium_api::app::bsky::feed::get_posts::ParametersData {
+                    uris: chunk.to_vec(),
+                }.into()
+            ).await.map_err(|e| ApiError::NetworkError(e.to_string()))?;
+            posts.extend(response.data.posts);
         }
+        Ok(posts)
     }
 
-    pub async fn refresh_session(&mut self) -> Result<()> {
+    async fn refresh_session(&mut self) -> Result<()> {
         if let Some(session) = self.agent.get_session().await {
             self.agent.resume_session(session).await?;
         } else {
@@ -162,38 +657,168 @@ impl API {
         Ok(())
     }
 
-    pub async fn follow_actor(&mut self, did: atrium_api::types::string::Did) -> Result<()> {
+    async fn follow_actor(&mut self, did: atrium_api::types::string::Did) -> Result<String> {
         let record_data = atrium_api::app::bsky::graph::follow::RecordData {
             created_at: atrium_api::types::string::Datetime::now(),
             subject: did.clone(),
         };
         match self.agent.create_record(record_data).await {
-            Ok(_) => {log::info!("Followed did: {:?}", did); Ok(())},
+            Ok(output) => {log::info!("Followed did: {:?}", did); Ok(output.uri.clone())},
             Err(e) => {log::error!("Failed to follow did: {:?} with error: {}", did, e); Err(e.into())},
         }
     }
 
-    pub async fn unfollow_actor(&mut self, did: &atrium_api::types::string::Did) -> Result<()> {
-        // First get the profile to find the follow record URI
-        let params = atrium_api::app::bsky::actor::get_profile::ParametersData {
-            actor: atrium_api::types::string::AtIdentifier::Did(did.clone())
-        }.into();
-        
-        if let Ok(profile) = self.agent.api.app.bsky.actor.get_profile(params).await {
-            if let Some(viewer) = &profile.viewer {
-                if let Some(follow) = &viewer.following {
-                    // If we have the follow record URI, delete it
-                    self.agent.delete_record(&follow).await?;
-                    log::info!("Unfollowed did: {:?}", did);
-                    return Ok(());
-                }
+    async fn unfollow_actor(&mut self, did: &atrium_api::types::string::Did, record: &str) -> Result<()> {
+        self.agent.delete_record(record).await?;
+        log::info!("Unfollowed did: {:?}", did);
+        Ok(())
+    }
+
+    async fn get_list(&self, uri: &str) -> Result<atrium_api::app::bsky::graph::defs::ListView> {
+        // Only the list itself is wanted, not its members
+        let params = atrium_api::app::bsky::graph::get_list::ParametersData {
+            cursor: None,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::try_from(1).map_err(|e| anyhow::anyhow!(e))?),
+            list: uri.to_string(),
+        };
+        let response = self.agent.api.app.bsky.graph.get_list(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        Ok(response.data.list)
+    }
+
+    async fn get_list_mutes(&self) -> Result<Vec<atrium_api::app::bsky::graph::defs::ListView>> {
+        let params = atrium_api::app::bsky::graph::get_list_mutes::ParametersData {
+            cursor: None,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+        };
+        let response = self.agent.api.app.bsky.graph.get_list_mutes(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        Ok(response.data.lists)
+    }
+
+    async fn get_list_blocks(&self) -> Result<Vec<atrium_api::app::bsky::graph::defs::ListView>> {
+        let params = atrium_api::app::bsky::graph::get_list_blocks::ParametersData {
+            cursor: None,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+        };
+        let response = self.agent.api.app.bsky.graph.get_list_blocks(params.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        Ok(response.data.lists)
+    }
+
+    async fn mute_list(&self, uri: &str) -> Result<()> {
+        let input = atrium_api::app::bsky::graph::mute_actor_list::InputData {
+            list: uri.to_string(),
+        };
+        self.agent.api.app.bsky.graph.mute_actor_list(input.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        log::info!("Muted list: {}", uri);
+        Ok(())
+    }
+
+    async fn unmute_list(&self, uri: &str) -> Result<()> {
+        let input = atrium_api::app::bsky::graph::unmute_actor_list::InputData {
+            list: uri.to_string(),
+        };
+        self.agent.api.app.bsky.graph.unmute_actor_list(input.into()).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+        log::info!("Unmuted list: {}", uri);
+        Ok(())
+    }
+
+    async fn block_list(&self, uri: &str) -> Result<String> {
+        let record_data = atrium_api::app::bsky::graph::listblock::RecordData {
+            created_at: atrium_api::types::string::Datetime::now(),
+            subject: uri.to_string(),
+        };
+        let output = self.agent.create_record(record_data).await?;
+        log::info!("Blocked list: {}", uri);
+        Ok(output.uri.clone())
+    }
+
+    async fn unblock_list(&self, record: &str) -> Result<()> {
+        self.agent.delete_record(record).await?;
+        log::info!("Deleted listblock: {}", record);
+        Ok(())
+    }
+
+    async fn get_lists(&self, actor: atrium_api::types::string::AtIdentifier) -> Result<Vec<atrium_api::app::bsky::graph::defs::ListView>> {
+        let mut lists = Vec::new();
+        let mut cursor = None;
+        loop {
+            let params = atrium_api::app::bsky::graph::get_lists::ParametersData {
+                actor: actor.clone(),
+                cursor,
+                limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+            };
+            let response = self.agent.api.app.bsky.graph.get_lists(params.into()).await
+                .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+            lists.extend(response.data.lists);
+            cursor = response.data.cursor;
+            if cursor.is_none() {
+                return Ok(lists);
             }
         }
-        
-        Err(anyhow::anyhow!("Could not find follow record to delete"))
     }
 
-    pub async fn create_post(&self, text: String, reply_to: Option<String>) -> Result<()> {
+    async fn create_list(&self, name: &str, purpose: &str) -> Result<String> {
+        let record_data = atrium_api::app::bsky::graph::list::RecordData {
+            avatar: None,
+            created_at: atrium_api::types::string::Datetime::now(),
+            description: None,
+            description_facets: None,
+            labels: None,
+            name: name.to_string(),
+            purpose: purpose.to_string(),
+        };
+        let output = self.agent.create_record(record_data).await?;
+        log::info!("Created list: {}", output.uri);
+        Ok(output.uri.clone())
+    }
+
+    async fn add_to_list(&self, list: &str, did: &atrium_api::types::string::Did) -> Result<String> {
+        let record_data = atrium_api::app::bsky::graph::listitem::RecordData {
+            created_at: atrium_api::types::string::Datetime::now(),
+            list: list.to_string(),
+            subject: did.clone(),
+        };
+        let output = self.agent.create_record(record_data).await?;
+        log::info!("Added {:?} to list {}", did, list);
+        Ok(output.uri.clone())
+    }
+
+    // There's no lookup by member, so this pages through the whole list
+    async fn find_list_item(&self, list: &str, did: &atrium_api::types::string::Did) -> Result<Option<String>> {
+        let mut cursor = None;
+        loop {
+            let params = atrium_api::app::bsky::graph::get_list::ParametersData {
+                cursor,
+                limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
+                list: list.to_string(),
+            };
+            let response = self.agent.api.app.bsky.graph.get_list(params.into()).await
+                .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+            if let Some(item) = response.data.items.iter().find(|item| item.subject.did == *did) {
+                return Ok(Some(item.uri.clone()));
+            }
+            cursor = response.data.cursor.clone();
+            if cursor.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    async fn remove_list_item(&self, record: &str) -> Result<()> {
+        self.agent.delete_record(record).await?;
+        log::info!("Deleted listitem: {}", record);
+        Ok(())
+    }
+
+    async fn create_post(&self, text: String, reply_to: Option<String>, rkey: &str) -> Result<()> {
+        use atrium_api::types::{Collection, TryIntoUnknown};
+
+        let session = self.agent.get_session().await.ok_or(ApiError::NotAuthenticated)?;
+        let facets = self.build_mention_facets(&text).await;
         let mut record = atrium_api::app::bsky::feed::post::RecordData {
             text,
             created_at: atrium_api::types::string::Datetime::now(),
@@ -202,7 +827,7 @@ impl API {
             langs: None,
             labels: None,
             tags: None,
-            facets: None,
+            facets: if facets.is_empty() { None } else { Some(facets) },
             entities: None,
         };
 
@@ -223,12 +848,43 @@ impl API {
             }.into());
         }
 
-        match self.agent.create_record(record).await {
+        let input = atrium_api::com::atproto::repo::create_record::InputData {
+            collection: atrium_api::app::bsky::feed::Post::nsid(),
+            record: record.try_into_unknown()?,
+            repo: atrium_api::types::string::AtIdentifier::Did(session.did.clone()),
+            rkey: Some(rkey.to_string()),
+            swap_commit: None,
+            validate: None,
+        };
+        match self.agent.api.com.atproto.repo.create_record(input.into()).await {
             Ok(_) => Ok(()),
-            Err(e) => Err(anyhow::anyhow!("Failed to create post: {}", e))
+            // Kept as the source so is_connectivity_error can see what went wrong
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn post_exists(&self, rkey: &str) -> Result<bool> {
+        use atrium_api::types::Collection;
+
+        let session = self.agent.get_session().await.ok_or(ApiError::NotAuthenticated)?;
+        let result = self.agent.api.com.atproto.repo.get_record(
+            atrium_api::com::atproto::repo::get_record::ParametersData {
+                cid: None,
+                collection: atrium_api::app::bsky::feed::Post::nsid(),
+                repo: atrium_api::types::string::AtIdentifier::Did(session.did.clone()),
+                rkey: rkey.parse().map_err(anyhow::Error::msg)?,
+            }.into()
+        ).await;
+        match result {
+            Ok(_) => Ok(true),
+            Err(e) => {
+                let e = anyhow::Error::from(e);
+                if is_connectivity_error(&e) { Err(e) } else { Ok(false) }
+            }
         }
     }
-    pub async fn delete_post(&self, uri: &str) -> Result<()> {
+
+    async fn delete_post(&self, uri: &str) -> Result<()> {
         let repo_uri: String = uri.try_into()?;
         
         match self.agent.delete_record(&repo_uri).await {
@@ -242,4 +898,59 @@ impl API {
             }
         }
     }
+
+    async fn update_avatar(&self, image: Vec<u8>) -> Result<()> {
+        use atrium_api::types::{Collection, TryFromUnknown, TryIntoUnknown};
+        use atrium_api::app::bsky::actor::profile;
+
+        let session = self.agent.get_session().await.ok_or(ApiError::NotAuthenticated)?;
+        let repo = atrium_api::types::string::AtIdentifier::Did(session.did.clone());
+        let rkey = "self".parse().map_err(anyhow::Error::msg)?;
+
+        let blob = self.agent.api.com.atproto.repo.upload_blob(image).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?
+            .data
+            .blob;
+
+        // Accounts that never edited their profile have no record yet
+        let existing = self.agent.api.com.atproto.repo.get_record(
+            atrium_api::com::atproto::repo::get_record::ParametersData {
+                cid: None,
+                collection: atrium_api::app::bsky::actor::Profile::nsid(),
+                repo: repo.clone(),
+                rkey: "self".parse().map_err(anyhow::Error::msg)?,
+            }.into()
+        ).await.ok();
+
+        let (mut record, swap_record) = match existing {
+            Some(output) => (profile::RecordData::try_from_unknown(output.data.value)?, output.data.cid),
+            None => (profile::RecordData {
+                avatar: None,
+                banner: None,
+                created_at: Some(atrium_api::types::string::Datetime::now()),
+                description: None,
+                display_name: None,
+                joined_via_starter_pack: None,
+                labels: None,
+                pinned_post: None,
+            }, None),
+        };
+        record.avatar = Some(blob);
+
+        self.agent.api.com.atproto.repo.put_record(
+            atrium_api::com::atproto::repo::put_record::InputData {
+                collection: atrium_api::app::bsky::actor::Profile::nsid(),
+                record: record.try_into_unknown()?,
+                repo,
+                rkey,
+                swap_commit: None,
+                swap_record,
+                validate: None,
+            }.into()
+        ).await
+            .map_err(|e| ApiError::NetworkError(e.to_string()))?;
+
+        log::info!("Updated avatar for {}", session.handle.as_str());
+        Ok(())
+    }
 }
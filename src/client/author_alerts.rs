@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use anyhow::Result;
+use atrium_api::{
+    app::bsky::{actor::defs::ProfileViewData, feed::defs::FeedViewPost, notification::list_notifications::NotificationData},
+    types::string::{AtIdentifier, Did},
+};
+use serde::{Deserialize, Serialize};
+
+use super::bluesky_client::BlueskyClient;
+
+pub const AUTHOR_ALERTS_PATH: &str = "author_alerts.json";
+
+// Reason used for the pseudo-notifications we create for new posts
+pub const ALERT_REASON: &str = "post";
+
+// How many of an author's latest posts to look at per poll
+const POLL_LIMIT: u8 = 10;
+
+// Authors marked "notify on post". Bluesky's own bell is server-side and not
+// exposed to third party clients, so we poll their feeds instead.
+#[derive(Default, Serialize, Deserialize)]
+pub struct AuthorAlerts {
+    // DID -> handle, for display
+    authors: HashMap<String, String>,
+    // DID -> URI of the newest post we've already alerted on
+    latest_seen: HashMap<String, String>,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+impl AuthorAlerts {
+    pub fn load(file_path: impl Into<PathBuf>) -> Self {
+        let file_path = file_path.into();
+        let mut alerts: AuthorAlerts = std::fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        alerts.file_path = file_path;
+        alerts
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.file_path, contents)?;
+        Ok(())
+    }
+
+    // Returns whether alerts are now on for the author
+    pub fn toggle(&mut self, did: &str, handle: &str) -> bool {
+        if self.authors.remove(did).is_some() {
+            self.latest_seen.remove(did);
+            false
+        } else {
+            self.authors.insert(did.to_string(), handle.to_string());
+            true
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty()
+    }
+
+    pub fn handles(&self) -> Vec<String> {
+        let mut handles: Vec<String> = self.authors.values().cloned().collect();
+        handles.sort();
+        handles
+    }
+
+    // (did, newest post seen) pairs to hand to a background poll
+    pub fn poll_targets(&self) -> Vec<(String, Option<String>)> {
+        self.authors
+            .keys()
+            .map(|did| (did.clone(), self.latest_seen.get(did).cloned()))
+            .collect()
+    }
+
+    pub fn mark_seen(&mut self, did: &str, uri: String) {
+        // The author may have been removed while the poll was running
+        if self.authors.contains_key(did) {
+            self.latest_seen.insert(did.to_string(), uri);
+        }
+    }
+
+    // Fetch the author's posts newer than `latest_seen`. The first poll for an
+    // author only records a baseline so we don't alert on their whole history.
+    pub async fn fetch_new_posts(
+        api: &impl BlueskyClient,
+        did: &str,
+        latest_seen: Option<&str>,
+    ) -> Result<(Option<String>, Vec<NotificationData>)> {
+        let params = atrium_api::app::bsky::feed::get_author_feed::ParametersData {
+            actor: AtIdentifier::Did(Did::new(did.to_string()).map_err(|e| anyhow::anyhow!(e))?),
+            cursor: None,
+            filter: Some("posts_no_replies".to_string()),
+            include_pins: None,
+            limit: Some(atrium_api::types::LimitedNonZeroU8::try_from(POLL_LIMIT).map_err(|e| anyhow::anyhow!(e))?),
+        };
+        let response = api.get_author_feed(params).await?;
+
+        // Reposts aren't the author's own posts
+        let own_posts: Vec<&FeedViewPost> = response.feed
+            .iter()
+            .filter(|feed_post| feed_post.reason.is_none())
+            .collect();
+        let newest = own_posts.first().map(|feed_post| feed_post.post.uri.clone());
+
+        let Some(latest_seen) = latest_seen else {
+            return Ok((newest, Vec::new()));
+        };
+
+        let alerts = own_posts
+            .iter()
+            .take_while(|feed_post| feed_post.post.uri != latest_seen)
+            .map(|feed_post| Self::to_notification(feed_post))
+            .collect();
+        Ok((newest, alerts))
+    }
+
+    // Shape a new post like a notification so it can sit in the notifications view
+    fn to_notification(feed_post: &FeedViewPost) -> NotificationData {
+        let post = &feed_post.post;
+        let author = &post.author;
+        NotificationData {
+            author: ProfileViewData {
+                associated: author.associated.clone(),
+                avatar: author.avatar.clone(),
+                created_at: author.created_at.clone(),
+                description: None,
+                did: author.did.clone(),
+                display_name: author.display_name.clone(),
+                handle: author.handle.clone(),
+                indexed_at: None,
+                labels: author.labels.clone(),
+                viewer: author.viewer.clone(),
+            }.into(),
+            cid: post.cid.clone(),
+            indexed_at: post.indexed_at.clone(),
+            is_read: false,
+            labels: post.labels.clone(),
+            reason: ALERT_REASON.to_string(),
+            reason_subject: None,
+            record: post.record.clone(),
+            uri: post.uri.clone(),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::future::Future;
+use anyhow::Result;
+use atrium_api::{
+    agent::Session,
+    app::bsky::{
+        actor::{defs::{ProfileView, ProfileViewDetailed}, get_profile},
+        feed::{defs::{FeedViewPost, PostView, PostViewData}, get_author_feed, get_post_thread},
+        graph::defs::ListView,
+        notification::list_notifications,
+    },
+    types::string::{AtIdentifier, Cid, Did},
+};
+use secrecy::SecretString;
+use serde::Deserialize;
+
+use super::rate_limit::RateLimitStatus;
+
+// A list's purpose when it's for muting or blocking, rather than curating
+pub const MODERATION_LIST: &str = "app.bsky.graph.defs#modlist";
+// One made to gather accounts, e.g. for a feed, which is what :newlist makes
+pub const CURATED_LIST: &str = "app.bsky.graph.defs#curatelist";
+
+// One entry of app.bsky.unspecced.getTrendingTopics
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendingTopic {
+    pub topic: String,
+    pub display_name: Option<String>,
+    pub description: Option<String>,
+    // Path on bsky.app, e.g. /profile/<actor>/feed/<rkey> or /search?q=...
+    pub link: String,
+}
+
+// What opening a trending topic shows
+pub enum TrendTarget {
+    Hashtag(String),
+    Feed { actor: String, rkey: String },
+    Search(String),
+}
+
+impl TrendingTopic {
+    pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or(&self.topic)
+    }
+
+    pub fn target(&self) -> TrendTarget {
+        let parts: Vec<&str> = self.link.trim_matches('/').split('/').collect();
+        if let ["profile", actor, "feed", rkey] = parts.as_slice() {
+            return TrendTarget::Feed { actor: actor.to_string(), rkey: rkey.to_string() };
+        }
+        match self.topic.strip_prefix('#') {
+            Some(tag) => TrendTarget::Hashtag(tag.to_string()),
+            None => TrendTarget::Search(self.topic.clone()),
+        }
+    }
+}
+
+// Everything the UI asks of Bluesky. `API` talks to a real PDS through
+// BskyAgent; views only see this trait, so an offline or mock backend can
+// stand in for it. Futures are Send so calls can run on spawned tasks
+pub trait BlueskyClient: Clone + Send + Sync + 'static {
+    fn rate_limit(&self) -> Option<RateLimitStatus>;
+
+    // True while reads come from the last saved copy because the network failed
+    fn is_offline(&self) -> bool;
+
+    // Write out any offline cache save still waiting on its delay, e.g. on quit
+    fn flush_offline_cache(&self) -> impl Future<Output = Result<()>> + Send;
+
+    fn session(&self) -> impl Future<Output = Option<Session>> + Send;
+
+    fn login(&mut self, identifier: String, password: SecretString) -> impl Future<Output = Result<()>> + Send;
+
+    fn logout(&mut self) -> impl Future<Output = Result<()>> + Send;
+
+    fn refresh_session(&mut self) -> impl Future<Output = Result<()>> + Send;
+
+    fn resolve_handle(&self, handle: &str) -> impl Future<Output = Result<Did>> + Send;
+
+    fn resolve_did_handle(&self, did: &str) -> impl Future<Output = Result<String>> + Send;
+
+    // Accepts either a DID or a handle
+    fn resolve_actor(&self, actor: &str) -> impl Future<Output = Result<Did>> + Send;
+
+    fn get_profile(&self, params: get_profile::ParametersData) -> impl Future<Output = Result<ProfileViewDetailed>> + Send;
+
+    fn get_timeline(&self, cursor: Option<String>) -> impl Future<Output = Result<(Vec<FeedViewPost>, Option<String>)>> + Send;
+
+    fn get_actor_likes(
+        &self,
+        actor: AtIdentifier,
+        cursor: Option<String>,
+    ) -> impl Future<Output = Result<(Vec<FeedViewPost>, Option<String>)>> + Send;
+
+    // A page of a feed generator, e.g. Discover
+    fn get_feed(&self, feed: &str, cursor: Option<String>) -> impl Future<Output = Result<(Vec<FeedViewPost>, Option<String>)>> + Send;
+
+    // (display name, generator URI) of each feed the user saved, in their order
+    fn get_saved_feeds(&self) -> impl Future<Output = Result<Vec<(String, String)>>> + Send;
+
+    // Newest posts matching `query` first. Results come back as feed items with
+    // no repost or reply context so they can fill a Feed
+    fn search_posts(&self, query: &str, cursor: Option<String>) -> impl Future<Output = Result<(Vec<FeedViewPost>, Option<String>)>> + Send;
+
+    fn get_trending_topics(&self) -> impl Future<Output = Result<Vec<TrendingTopic>>> + Send;
+
+    fn get_author_feed(&self, params: get_author_feed::ParametersData) -> impl Future<Output = Result<get_author_feed::Output>> + Send;
+
+    fn get_post_thread(&self, params: get_post_thread::ParametersData) -> impl Future<Output = Result<get_post_thread::Output>> + Send;
+
+    fn list_notifications(
+        &self,
+        params: list_notifications::ParametersData,
+    ) -> impl Future<Output = Result<list_notifications::Output>> + Send;
+
+    // Accounts `actor` follows, a page at a time
+    fn get_follows(&self, actor: AtIdentifier, cursor: Option<String>) -> impl Future<Output = Result<(Vec<ProfileView>, Option<String>)>> + Send;
+
+    fn get_likers(&self, uri: &str, cursor: Option<String>) -> impl Future<Output = Result<(Vec<ProfileView>, Option<String>)>> + Send;
+
+    fn get_reposters(&self, uri: &str, cursor: Option<String>) -> impl Future<Output = Result<(Vec<ProfileView>, Option<String>)>> + Send;
+
+    fn get_post(&self, uri: &str) -> impl Future<Output = Result<PostView>> + Send;
+
+    // Fetch many posts with as few calls as the backend allows. Deleted or
+    // hidden posts are simply missing from the result
+    fn get_posts(&self, uris: &[String]) -> impl Future<Output = Result<Vec<PostView>>> + Send;
+
+    // Returns the URI of the new like record
+    fn like_post(&self, uri: &str, cid: &Cid) -> impl Future<Output = Result<String>> + Send;
+
+    fn unlike_post(&self, post: &PostViewData) -> impl Future<Output = Result<()>> + Send;
+
+    // Returns the URI of the new repost record
+    fn repost(&self, uri: &str, cid: &Cid) -> impl Future<Output = Result<String>> + Send;
+
+    fn unrepost(&self, post: &PostViewData) -> impl Future<Output = Result<()>> + Send;
+
+    // Returns the URI of the new follow record
+    fn follow_actor(&mut self, did: Did) -> impl Future<Output = Result<String>> + Send;
+
+    // Deletes `record`, our follow of `did`
+    fn unfollow_actor(&mut self, did: &Did, record: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // A list's details, with its viewer state saying whether we mute or block it
+    fn get_list(&self, uri: &str) -> impl Future<Output = Result<ListView>> + Send;
+
+    // Moderation lists we've subscribed to as mutes, and as blocks
+    fn get_list_mutes(&self) -> impl Future<Output = Result<Vec<ListView>>> + Send;
+
+    fn get_list_blocks(&self) -> impl Future<Output = Result<Vec<ListView>>> + Send;
+
+    // Mute everyone on a list. Mutes are private, so there's no record
+    fn mute_list(&self, uri: &str) -> impl Future<Output = Result<()>> + Send;
+
+    fn unmute_list(&self, uri: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // Block everyone on a list. Returns the URI of the new listblock record
+    fn block_list(&self, uri: &str) -> impl Future<Output = Result<String>> + Send;
+
+    // Deletes `record`, our listblock
+    fn unblock_list(&self, record: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // Lists `actor` has made, moderation and curated alike
+    fn get_lists(&self, actor: AtIdentifier) -> impl Future<Output = Result<Vec<ListView>>> + Send;
+
+    // Returns the URI of the new list. `purpose` is one of the
+    // app.bsky.graph.defs list purposes
+    fn create_list(&self, name: &str, purpose: &str) -> impl Future<Output = Result<String>> + Send;
+
+    // Returns the URI of the new listitem record
+    fn add_to_list(&self, list: &str, did: &Did) -> impl Future<Output = Result<String>> + Send;
+
+    // The listitem record putting `did` on `list`, if they're on it
+    fn find_list_item(&self, list: &str, did: &Did) -> impl Future<Output = Result<Option<String>>> + Send;
+
+    // Deletes `record`, a listitem
+    fn remove_list_item(&self, record: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // Posts under `rkey`, so sending the same post again can't make a second one
+    fn create_post(&self, text: String, reply_to: Option<String>, rkey: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // Whether we have a post under `rkey`
+    fn post_exists(&self, rkey: &str) -> impl Future<Output = Result<bool>> + Send;
+
+    fn delete_post(&self, uri: &str) -> impl Future<Output = Result<()>> + Send;
+
+    // Upload `image` as a blob and point our profile record's avatar at it,
+    // keeping every other profile field as it was
+    fn update_avatar(&self, image: Vec<u8>) -> impl Future<Output = Result<()>> + Send;
+}
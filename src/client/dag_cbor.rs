@@ -0,0 +1,352 @@
+use std::collections::{BTreeMap, HashMap};
+use anyhow::{anyhow, bail, Result};
+use ipld_core::{cid::Cid, ipld::Ipld};
+
+// Just enough DAG-CBOR and CAR to read repo event streams. DAG-CBOR is the
+// strict subset of CBOR atproto uses: definite lengths only, string map keys,
+// and CIDs as tag 42
+
+// CBOR tag for a CID link
+const CID_TAG: u64 = 42;
+// Deeper than any record should go; stops a hostile frame blowing the stack
+const MAX_DEPTH: usize = 64;
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow!("CBOR item runs past the end of the data"))?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    // The number that follows an item's initial byte: a length, a value or a tag
+    fn argument(&mut self, info: u8) -> Result<u64> {
+        Ok(match info {
+            0..=23 => info as u64,
+            24 => self.byte()? as u64,
+            25 => u16::from_be_bytes(self.take(2)?.try_into()?) as u64,
+            26 => u32::from_be_bytes(self.take(4)?.try_into()?) as u64,
+            27 => u64::from_be_bytes(self.take(8)?.try_into()?),
+            _ => bail!("Indefinite lengths aren't allowed in DAG-CBOR"),
+        })
+    }
+
+    fn length(&mut self, info: u8) -> Result<usize> {
+        let length = self.argument(info)?;
+        // Every item is at least a byte, so anything longer than what's left is bogus
+        usize::try_from(length).ok()
+            .filter(|&length| length <= self.bytes.len() - self.pos)
+            .ok_or_else(|| anyhow!("CBOR length {} is longer than the data", length))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Ipld> {
+        if depth > MAX_DEPTH {
+            bail!("CBOR nested too deeply");
+        }
+        let initial = self.byte()?;
+        let info = initial & 0x1f;
+        Ok(match initial >> 5 {
+            0 => Ipld::Integer(self.argument(info)? as i128),
+            1 => Ipld::Integer(-1 - self.argument(info)? as i128),
+            2 => {
+                let length = self.length(info)?;
+                Ipld::Bytes(self.take(length)?.to_vec())
+            }
+            3 => {
+                let length = self.length(info)?;
+                Ipld::String(std::str::from_utf8(self.take(length)?)?.to_string())
+            }
+            4 => {
+                let length = self.length(info)?;
+                let mut items = Vec::with_capacity(length);
+                for _ in 0..length {
+                    items.push(self.value(depth + 1)?);
+                }
+                Ipld::List(items)
+            }
+            5 => {
+                let length = self.length(info)?;
+                let mut map = BTreeMap::new();
+                for _ in 0..length {
+                    let Ipld::String(key) = self.value(depth + 1)? else {
+                        bail!("DAG-CBOR map keys must be strings");
+                    };
+                    map.insert(key, self.value(depth + 1)?);
+                }
+                Ipld::Map(map)
+            }
+            6 => {
+                let tag = self.argument(info)?;
+                if tag != CID_TAG {
+                    bail!("Unexpected CBOR tag {}", tag);
+                }
+                let Ipld::Bytes(bytes) = self.value(depth + 1)? else {
+                    bail!("CID link isn't a byte string");
+                };
+                // Links carry a leading zero, the multibase prefix for raw binary
+                match bytes.split_first() {
+                    Some((0, cid)) => Ipld::Link(Cid::try_from(cid)?),
+                    _ => bail!("CID link is missing its multibase prefix"),
+                }
+            }
+            _ => match info {
+                20 => Ipld::Bool(false),
+                21 => Ipld::Bool(true),
+                22 => Ipld::Null,
+                26 => Ipld::Float(f32::from_be_bytes(self.take(4)?.try_into()?) as f64),
+                27 => Ipld::Float(f64::from_be_bytes(self.take(8)?.try_into()?)),
+                _ => bail!("Unsupported CBOR simple value {}", info),
+            },
+        })
+    }
+}
+
+// Decode the first item in `bytes`, returning it and how many bytes it took.
+// Stream frames are two items back to back: a header and a body
+pub fn decode_prefix(bytes: &[u8]) -> Result<(Ipld, usize)> {
+    let mut reader = Reader { bytes, pos: 0 };
+    let value = reader.value(0)?;
+    Ok((value, reader.pos))
+}
+
+pub fn decode(bytes: &[u8]) -> Result<Ipld> {
+    let (value, used) = decode_prefix(bytes)?;
+    if used != bytes.len() {
+        bail!("{} bytes left over after the CBOR item", bytes.len() - used);
+    }
+    Ok(value)
+}
+
+// LEB128, as used for CAR section lengths
+fn read_varint(bytes: &[u8]) -> Result<(usize, usize)> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = usize::try_from(value)?;
+            return Ok((value, i + 1));
+        }
+    }
+    bail!("Bad varint in CAR data")
+}
+
+// The blocks of a CAR v1 file, by CID. The header (version and roots) isn't needed
+pub fn read_car(bytes: &[u8]) -> Result<HashMap<Cid, Vec<u8>>> {
+    let mut blocks = HashMap::new();
+    let (header_len, used) = read_varint(bytes)?;
+    let mut pos = used.checked_add(header_len).ok_or_else(|| anyhow!("Bad CAR header length"))?;
+
+    while pos < bytes.len() {
+        let (section_len, used) = read_varint(&bytes[pos..])?;
+        pos += used;
+        let section = bytes.get(pos..pos.saturating_add(section_len))
+            .ok_or_else(|| anyhow!("CAR block runs past the end of the data"))?;
+        let mut cursor = std::io::Cursor::new(section);
+        let cid = Cid::read_bytes(&mut cursor)?;
+        blocks.insert(cid, section[cursor.position() as usize..].to_vec());
+        pos += section_len;
+    }
+    Ok(blocks)
+}
+
+// Follow a path of map keys, e.g. ["reply", "parent", "uri"]
+pub fn lookup<'a>(value: &'a Ipld, path: &[&str]) -> Option<&'a Ipld> {
+    path.iter().try_fold(value, |value, key| match value {
+        Ipld::Map(map) => map.get(*key),
+        _ => None,
+    })
+}
+
+pub fn lookup_str<'a>(value: &'a Ipld, path: &[&str]) -> Option<&'a str> {
+    match lookup(value, path)? {
+        Ipld::String(text) => Some(text),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ipld_core::cid::multihash::Multihash;
+
+    // A minimal encoder, enough to build frames like the relay sends
+    fn head(major: u8, n: u64) -> Vec<u8> {
+        let major = major << 5;
+        match n {
+            0..=23 => vec![major | n as u8],
+            24..=0xff => vec![major | 24, n as u8],
+            0x100..=0xffff => [vec![major | 25], (n as u16).to_be_bytes().to_vec()].concat(),
+            _ => [vec![major | 26], (n as u32).to_be_bytes().to_vec()].concat(),
+        }
+    }
+
+    fn text(s: &str) -> Vec<u8> {
+        [head(3, s.len() as u64), s.as_bytes().to_vec()].concat()
+    }
+
+    fn bytes(b: &[u8]) -> Vec<u8> {
+        [head(2, b.len() as u64), b.to_vec()].concat()
+    }
+
+    fn map(entries: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut out = head(5, entries.len() as u64);
+        for (key, value) in entries {
+            out.extend(text(key));
+            out.extend(value);
+        }
+        out
+    }
+
+    fn link(cid: &Cid) -> Vec<u8> {
+        [head(6, CID_TAG), bytes(&[vec![0], cid.to_bytes()].concat())].concat()
+    }
+
+    fn varint(mut n: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn record_cid() -> Cid {
+        // dag-cbor, sha2-256
+        Cid::new_v1(0x71, Multihash::wrap(0x12, &[7; 32]).unwrap())
+    }
+
+    fn record() -> Vec<u8> {
+        map(&[("$type", text("app.bsky.feed.post")), ("text", text("hello"))])
+    }
+
+    fn car(cid: &Cid, block: &[u8]) -> Vec<u8> {
+        let header = map(&[("roots", [head(4, 1), link(cid)].concat()), ("version", head(0, 1))]);
+        let section = [cid.to_bytes(), block.to_vec()].concat();
+        [varint(header.len()), header, varint(section.len()), section].concat()
+    }
+
+    fn commit_frame() -> (Vec<u8>, Vec<u8>) {
+        let cid = record_cid();
+        let header = map(&[("op", head(0, 1)), ("t", text("#commit"))]);
+        let op = map(&[
+            ("action", text("create")),
+            ("cid", link(&cid)),
+            ("path", text("app.bsky.feed.post/3kabc")),
+        ]);
+        let body = map(&[
+            ("blocks", bytes(&car(&cid, &record()))),
+            ("ops", [head(4, 1), op].concat()),
+            ("repo", text("did:plc:alice")),
+            ("seq", head(0, 1000)),
+            ("tooBig", [0xf4].to_vec()),
+        ]);
+        (header, body)
+    }
+
+    #[test]
+    fn reads_a_commit_frame() {
+        let (header, body) = commit_frame();
+        let frame = [header.clone(), body].concat();
+
+        let (decoded_header, used) = decode_prefix(&frame).unwrap();
+        assert_eq!(used, header.len());
+        assert_eq!(lookup_str(&decoded_header, &["t"]), Some("#commit"));
+        assert_eq!(lookup(&decoded_header, &["op"]), Some(&Ipld::Integer(1)));
+
+        let body = decode(&frame[used..]).unwrap();
+        assert_eq!(lookup_str(&body, &["repo"]), Some("did:plc:alice"));
+        assert_eq!(lookup(&body, &["seq"]), Some(&Ipld::Integer(1000)));
+        assert_eq!(lookup(&body, &["tooBig"]), Some(&Ipld::Bool(false)));
+        let Some(Ipld::List(ops)) = lookup(&body, &["ops"]) else {
+            panic!("ops isn't a list");
+        };
+        assert_eq!(lookup(&ops[0], &["cid"]), Some(&Ipld::Link(record_cid())));
+
+        let Some(Ipld::Bytes(blocks)) = lookup(&body, &["blocks"]) else {
+            panic!("blocks isn't a byte string");
+        };
+        let blocks = read_car(blocks).unwrap();
+        let record = decode(&blocks[&record_cid()]).unwrap();
+        assert_eq!(lookup_str(&record, &["text"]), Some("hello"));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let (header, body) = commit_frame();
+        for end in 0..header.len() {
+            assert!(decode_prefix(&header[..end]).is_err(), "header cut at {}", end);
+        }
+        for end in 0..body.len() {
+            assert!(decode(&body[..end]).is_err(), "body cut at {}", end);
+        }
+        let car = car(&record_cid(), &record());
+        assert!(read_car(&car[..car.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        assert!(decode(&[0x01, 0x02]).is_err());
+        assert_eq!(decode_prefix(&[0x01, 0x02]).unwrap(), (Ipld::Integer(1), 1));
+    }
+
+    #[test]
+    fn rejects_lengths_past_the_end() {
+        // A byte string, a text string, a list and a map each claiming far more
+        // than is there
+        for major in [2, 3, 4, 5] {
+            let item = [head(major, u32::MAX as u64), vec![0; 4]].concat();
+            let error = decode(&item).unwrap_err().to_string();
+            assert!(error.contains("longer than the data"), "{}", error);
+        }
+        let huge = [vec![0x9b], u64::MAX.to_be_bytes().to_vec()].concat();
+        assert!(decode(&huge).is_err());
+
+        let header = map(&[("version", head(0, 1))]);
+        let car = [varint(header.len()), header, varint(1000), vec![0; 10]].concat();
+        let error = read_car(&car).unwrap_err().to_string();
+        assert!(error.contains("past the end"), "{}", error);
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth: usize| [vec![0x81; depth], vec![0x00]].concat();
+        assert!(decode(&nested(MAX_DEPTH)).is_ok());
+        let error = decode(&nested(MAX_DEPTH + 1)).unwrap_err().to_string();
+        assert!(error.contains("nested too deeply"), "{}", error);
+    }
+
+    #[test]
+    fn only_accepts_cid_tags() {
+        let error = decode(&[head(6, 1), head(0, 0)].concat()).unwrap_err().to_string();
+        assert!(error.contains("Unexpected CBOR tag 1"), "{}", error);
+        // A tag 42 whose bytes lack the multibase prefix
+        let unprefixed = [head(6, CID_TAG), bytes(&record_cid().to_bytes())].concat();
+        assert!(decode(&unprefixed).is_err());
+        // Or hold something other than bytes
+        assert!(decode(&[head(6, CID_TAG), text("cid")].concat()).is_err());
+    }
+
+    #[test]
+    fn reads_varints() {
+        assert_eq!(read_varint(&[0x01]).unwrap(), (1, 1));
+        assert_eq!(read_varint(&[0xac, 0x02, 0xff]).unwrap(), (300, 2));
+        assert_eq!(read_varint(&varint(1 << 40)).unwrap(), (1 << 40, 6));
+        assert!(read_varint(&[]).is_err());
+        assert!(read_varint(&[0x80, 0x80]).is_err());
+        assert!(read_varint(&[0xff; 10]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+pub const HANDLE_CACHE_PATH: &str = "handle_cache.json";
+
+// Handles rarely change, but they can, so resolutions expire after a day
+const HANDLE_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CachedResolution {
+    did: String,
+    resolved_at: u64,
+}
+
+// Persistent handle -> DID resolution cache shared by everything that needs
+// to turn a handle into a DID (mentions, :profile, links) or back again
+#[derive(Default, Serialize, Deserialize)]
+pub struct HandleCache {
+    entries: HashMap<String, CachedResolution>,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl HandleCache {
+    pub fn load(file_path: impl Into<PathBuf>) -> Self {
+        let file_path = file_path.into();
+        let mut cache: HandleCache = std::fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        cache.file_path = file_path;
+        cache.evict_expired();
+        cache
+    }
+
+    fn normalize(handle: &str) -> String {
+        handle.trim_start_matches('@').to_lowercase()
+    }
+
+    fn is_fresh(entry: &CachedResolution) -> bool {
+        now_secs().saturating_sub(entry.resolved_at) < HANDLE_CACHE_TTL.as_secs()
+    }
+
+    pub fn get_did(&self, handle: &str) -> Option<String> {
+        self.entries
+            .get(&Self::normalize(handle))
+            .filter(|entry| Self::is_fresh(entry))
+            .map(|entry| entry.did.clone())
+    }
+
+    // Reverse lookup, used to label replies whose parent we only know by DID
+    pub fn get_handle(&self, did: &str) -> Option<String> {
+        self.entries
+            .iter()
+            .find(|(_, entry)| entry.did == did && Self::is_fresh(entry))
+            .map(|(handle, _)| handle.clone())
+    }
+
+    pub fn insert(&mut self, handle: &str, did: &str) {
+        self.entries.insert(
+            Self::normalize(handle),
+            CachedResolution { did: did.to_string(), resolved_at: now_secs() },
+        );
+    }
+
+    fn evict_expired(&mut self) {
+        self.entries.retain(|_, entry| Self::is_fresh(entry));
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string(self)?;
+        std::fs::write(&self.file_path, contents)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+// Parsing for links to Bluesky content, either bsky.app web URLs or at:// URIs
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BskyLink {
+    Post { actor: String, rkey: String },
+    Profile { actor: String },
+    List { actor: String, rkey: String },
+}
+
+pub fn list_uri(did: &str, rkey: &str) -> String {
+    format!("at://{}/app.bsky.graph.list/{}", did, rkey)
+}
+
+impl BskyLink {
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Some(rest) = input.strip_prefix("at://") {
+            return Self::parse_at_uri(rest);
+        }
+
+        let url = url::Url::parse(input).ok()?;
+        let host = url.host_str()?;
+        if host != "bsky.app" && !host.ends_with(".bsky.app") {
+            return None;
+        }
+
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            ["profile", actor] => Some(BskyLink::Profile { actor: actor.to_string() }),
+            ["profile", actor, "post", rkey] => Some(BskyLink::Post {
+                actor: actor.to_string(),
+                rkey: rkey.to_string(),
+            }),
+            ["profile", actor, "lists", rkey] => Some(BskyLink::List {
+                actor: actor.to_string(),
+                rkey: rkey.to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn parse_at_uri(rest: &str) -> Option<Self> {
+        let segments: Vec<&str> = rest.split('/').filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            [actor] => Some(BskyLink::Profile { actor: actor.to_string() }),
+            [actor, "app.bsky.actor.profile", ..] => Some(BskyLink::Profile { actor: actor.to_string() }),
+            [actor, "app.bsky.feed.post", rkey] => Some(BskyLink::Post {
+                actor: actor.to_string(),
+                rkey: rkey.to_string(),
+            }),
+            [actor, "app.bsky.graph.list", rkey] => Some(BskyLink::List {
+                actor: actor.to_string(),
+                rkey: rkey.to_string(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(actor: &str, rkey: &str) -> Option<BskyLink> {
+        Some(BskyLink::Post { actor: actor.to_string(), rkey: rkey.to_string() })
+    }
+
+    #[test]
+    fn parses_web_urls() {
+        assert_eq!(
+            BskyLink::parse("https://bsky.app/profile/alice.bsky.social"),
+            Some(BskyLink::Profile { actor: "alice.bsky.social".to_string() }),
+        );
+        assert_eq!(
+            BskyLink::parse("https://bsky.app/profile/alice.bsky.social/post/3kabc123"),
+            post("alice.bsky.social", "3kabc123"),
+        );
+        assert_eq!(
+            BskyLink::parse("https://bsky.app/profile/did:plc:alice/lists/3kxyz789"),
+            Some(BskyLink::List { actor: "did:plc:alice".to_string(), rkey: "3kxyz789".to_string() }),
+        );
+        // Surrounding whitespace, a trailing slash and a query don't matter
+        assert_eq!(
+            BskyLink::parse("  https://bsky.app/profile/alice.bsky.social/post/3kabc123/?ref=x \n"),
+            post("alice.bsky.social", "3kabc123"),
+        );
+        assert_eq!(
+            BskyLink::parse("https://staging.bsky.app/profile/alice.bsky.social"),
+            Some(BskyLink::Profile { actor: "alice.bsky.social".to_string() }),
+        );
+    }
+
+    #[test]
+    fn parses_at_uris() {
+        assert_eq!(
+            BskyLink::parse("at://did:plc:alice"),
+            Some(BskyLink::Profile { actor: "did:plc:alice".to_string() }),
+        );
+        assert_eq!(
+            BskyLink::parse("at://did:plc:alice/app.bsky.actor.profile/self"),
+            Some(BskyLink::Profile { actor: "did:plc:alice".to_string() }),
+        );
+        assert_eq!(
+            BskyLink::parse("at://did:plc:alice/app.bsky.feed.post/3kabc123"),
+            post("did:plc:alice", "3kabc123"),
+        );
+        assert_eq!(
+            BskyLink::parse(&list_uri("did:plc:alice", "3kxyz789")),
+            Some(BskyLink::List { actor: "did:plc:alice".to_string(), rkey: "3kxyz789".to_string() }),
+        );
+    }
+
+    #[test]
+    fn rejects_everything_else() {
+        for input in [
+            "",
+            "alice.bsky.social",
+            "not a url",
+            "https://example.com/profile/alice.bsky.social",
+            "https://notbsky.app/profile/alice.bsky.social",
+            "https://bsky.app/",
+            "https://bsky.app/search?q=rust",
+            "https://bsky.app/profile/alice.bsky.social/post",
+            "https://bsky.app/profile/alice.bsky.social/feed/whats-hot",
+            "at://",
+            "at://did:plc:alice/app.bsky.feed.like/3kabc123",
+            "at://did:plc:alice/app.bsky.feed.post",
+        ] {
+            assert_eq!(BskyLink::parse(input), None, "{:?}", input);
+        }
+    }
+}
@@ -1,3 +1,12 @@
 pub mod api;
+pub mod bluesky_client;
 pub mod auth;
 pub mod update;
+pub mod dag_cbor;
+pub mod handle_cache;
+pub mod offline_cache;
+pub mod links;
+pub mod author_alerts;
+pub mod rate_limit;
+pub mod task_queue;
+pub mod outbox;
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+use anyhow::Result;
+use atrium_api::{
+    app::bsky::{
+        actor::defs::ProfileViewDetailed,
+        feed::{defs::FeedViewPost, get_author_feed, get_post_thread},
+    },
+    types::string::AtIdentifier,
+};
+use serde::{Deserialize, Serialize};
+
+pub const OFFLINE_CACHE_PATH: &str = "offline_cache.json";
+
+// Enough to read through without the file growing without bound
+const MAX_TIMELINE_POSTS: usize = 100;
+const MAX_THREADS: usize = 20;
+const MAX_PROFILES: usize = 20;
+
+// The last copy of what we fetched, so a launch without network still has
+// something to show. Entries are kept most recently used last
+#[derive(Default, Serialize, Deserialize)]
+pub struct OfflineCache {
+    timeline: Vec<FeedViewPost>,
+    threads: Vec<(String, get_post_thread::Output)>,
+    author_feeds: Vec<(String, get_author_feed::Output)>,
+    profiles: Vec<(String, ProfileViewDetailed)>,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+// Keep `entries` to `limit`, replacing any older entry under the same key
+fn remember<T: Clone>(entries: &mut Vec<(String, T)>, key: &str, value: &T, limit: usize) {
+    entries.retain(|(existing, _)| existing != key);
+    entries.push((key.to_string(), value.clone()));
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+}
+
+fn recall<T: Clone>(entries: &[(String, T)], key: &str) -> Option<T> {
+    entries.iter().find(|(existing, _)| existing == key).map(|(_, value)| value.clone())
+}
+
+pub fn actor_key(actor: &AtIdentifier) -> String {
+    match actor {
+        AtIdentifier::Did(did) => did.as_str().to_string(),
+        AtIdentifier::Handle(handle) => handle.as_str().to_string(),
+    }
+}
+
+impl OfflineCache {
+    pub fn load(file_path: impl Into<PathBuf>) -> Self {
+        let file_path = file_path.into();
+        let mut cache: OfflineCache = std::fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        cache.file_path = file_path;
+        cache
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+            && self.threads.is_empty()
+            && self.author_feeds.is_empty()
+            && self.profiles.is_empty()
+    }
+
+    pub fn timeline(&self) -> Option<Vec<FeedViewPost>> {
+        Some(self.timeline.clone()).filter(|posts| !posts.is_empty())
+    }
+
+    pub fn set_timeline(&mut self, posts: &[FeedViewPost]) {
+        self.timeline = posts.iter().take(MAX_TIMELINE_POSTS).cloned().collect();
+    }
+
+    pub fn thread(&self, uri: &str) -> Option<get_post_thread::Output> {
+        recall(&self.threads, uri)
+    }
+
+    pub fn set_thread(&mut self, uri: &str, thread: &get_post_thread::Output) {
+        remember(&mut self.threads, uri, thread, MAX_THREADS);
+    }
+
+    pub fn author_feed(&self, actor: &str) -> Option<get_author_feed::Output> {
+        recall(&self.author_feeds, actor)
+    }
+
+    pub fn set_author_feed(&mut self, actor: &str, feed: &get_author_feed::Output) {
+        remember(&mut self.author_feeds, actor, feed, MAX_PROFILES);
+    }
+
+    pub fn profile(&self, actor: &str) -> Option<ProfileViewDetailed> {
+        recall(&self.profiles, actor)
+    }
+
+    pub fn set_profile(&mut self, actor: &str, profile: &ProfileViewDetailed) {
+        remember(&mut self.profiles, actor, profile, MAX_PROFILES);
+    }
+
+    // The file and what to write to it, so the write itself can happen
+    // without holding the cache
+    pub fn serialize(&self) -> Result<(PathBuf, String)> {
+        Ok((self.file_path.clone(), serde_json::to_string(self)?))
+    }
+
+    // Someone else logging in shouldn't see this account's posts
+    pub fn clear(&mut self) -> Result<()> {
+        *self = Self { file_path: std::mem::take(&mut self.file_path), ..Self::default() };
+        if self.file_path.exists() {
+            std::fs::remove_file(&self.file_path)?;
+        }
+        Ok(())
+    }
+}
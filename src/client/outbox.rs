@@ -0,0 +1,188 @@
+use std::{path::PathBuf, sync::atomic::{AtomicU64, Ordering}};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::{api::is_connectivity_error, bluesky_client::BlueskyClient};
+
+pub const OUTBOX_PATH: &str = "outbox.json";
+
+pub type OutboxId = u64;
+
+const TID_ALPHABET: &[u8] = b"234567abcdefghijklmnopqrstuvwxyz";
+
+// A record key for a new post: microseconds since the epoch over a 10 bit
+// clock id, in sortable base32. Never hands out the same one twice
+pub fn new_tid() -> String {
+    static LAST: AtomicU64 = AtomicU64::new(0);
+    let now = chrono::Utc::now().timestamp_micros() as u64;
+    let micros = LAST
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1)))
+        .map_or(now, |last| now.max(last + 1));
+    let value = (micros << 10 | (std::process::id() as u64 & 0x3ff)) & (u64::MAX >> 1);
+    (0..13)
+        .rev()
+        .map(|i| TID_ALPHABET[(value >> (i * 5) & 0x1f) as usize] as char)
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: OutboxId,
+    pub text: String,
+    pub reply_to: Option<String>,
+    // Picked when the post was first sent and kept for every retry, so one
+    // that got through before its response was lost isn't posted twice
+    #[serde(default)]
+    pub rkey: String,
+    // Unix seconds
+    pub queued_at: i64,
+    // Set when the server turned the post down. It won't be retried until edited
+    pub last_error: Option<String>,
+}
+
+pub enum SendOutcome {
+    Sent,
+    // Still can't reach the server, try again later
+    Offline,
+    Rejected(String),
+}
+
+impl OutboxEntry {
+    pub fn is_waiting(&self) -> bool {
+        self.last_error.is_none()
+    }
+
+    pub async fn send<C: BlueskyClient>(&self, api: &C) -> SendOutcome {
+        match api.create_post(self.text.clone(), self.reply_to.clone(), &self.rkey).await {
+            Ok(()) => SendOutcome::Sent,
+            Err(e) if is_connectivity_error(&e) => SendOutcome::Offline,
+            // An earlier attempt that timed out may have made it after all
+            Err(e) => match api.post_exists(&self.rkey).await {
+                Ok(true) => SendOutcome::Sent,
+                Err(e) if is_connectivity_error(&e) => SendOutcome::Offline,
+                _ => SendOutcome::Rejected(e.to_string()),
+            },
+        }
+    }
+}
+
+// Posts written while we couldn't reach the PDS, oldest first. Kept on disk so
+// they survive a restart before the connection comes back
+#[derive(Default, Serialize, Deserialize)]
+pub struct Outbox {
+    entries: Vec<OutboxEntry>,
+    next_id: OutboxId,
+    #[serde(skip)]
+    file_path: PathBuf,
+}
+
+impl Outbox {
+    pub fn load(file_path: impl Into<PathBuf>) -> Self {
+        let file_path = file_path.into();
+        let mut outbox: Outbox = std::fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        // Entries queued before they carried a record key
+        for entry in outbox.entries.iter_mut().filter(|entry| entry.rkey.is_empty()) {
+            entry.rkey = new_tid();
+        }
+        outbox.file_path = file_path;
+        outbox
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.file_path, contents)?;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) -> Result<()> {
+        *self = Self { file_path: std::mem::take(&mut self.file_path), ..Self::default() };
+        if self.file_path.exists() {
+            std::fs::remove_file(&self.file_path)?;
+        }
+        Ok(())
+    }
+
+    pub fn push(&mut self, text: String, reply_to: Option<String>, rkey: String) -> OutboxId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(OutboxEntry {
+            id,
+            text,
+            reply_to,
+            rkey,
+            queued_at: chrono::Utc::now().timestamp(),
+            last_error: None,
+        });
+        id
+    }
+
+    pub fn entries(&self) -> &[OutboxEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, id: OutboxId) -> Option<&OutboxEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    // Entries are numbered from 1 in the :outbox view
+    pub fn get_numbered(&self, number: usize) -> Option<&OutboxEntry> {
+        number.checked_sub(1).and_then(|index| self.entries.get(index))
+    }
+
+    pub fn remove(&mut self, id: OutboxId) -> Option<OutboxEntry> {
+        let index = self.entries.iter().position(|entry| entry.id == id)?;
+        Some(self.entries.remove(index))
+    }
+
+    // Replace the text of an edited post and give it another chance to send
+    pub fn update(&mut self, id: OutboxId, text: String) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                entry.text = text;
+                entry.last_error = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_error(&mut self, id: OutboxId, error: String) {
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.last_error = Some(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tids_are_sortable_base32() {
+        let tid = new_tid();
+        assert_eq!(tid.len(), 13);
+        assert!(tid.bytes().all(|c| TID_ALPHABET.contains(&c)), "{}", tid);
+        // The top bit is always zero, which keeps the first character low
+        assert!(b"234567abcdefghij".contains(&tid.as_bytes()[0]), "{}", tid);
+    }
+
+    #[test]
+    fn tids_increase_across_rapid_calls() {
+        // Far more calls than there are microseconds between them
+        let tids: Vec<String> = (0..10_000).map(|_| new_tid()).collect();
+        for pair in tids.windows(2) {
+            assert!(pair[0] < pair[1], "{} then {}", pair[0], pair[1]);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use atrium_api::xrpc::{HttpClient, XrpcClient};
+use atrium_xrpc_client::reqwest::ReqwestClient;
+use http::{HeaderMap, Request, Response, StatusCode};
+
+use super::api::ApiError;
+
+// Attempts after the first before a 429 is given up on
+const MAX_RETRIES: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+// Longer than this and we'd rather report the limit than sit frozen
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+// What the PDS last told us about our request quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    pub limit: u64,
+    pub remaining: u64,
+    // Unix seconds when the window resets
+    pub reset: u64,
+    // Set while a request is waiting out a 429
+    pub retrying: bool,
+}
+
+impl RateLimitStatus {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse::<u64>().ok();
+        Some(Self {
+            limit: header("ratelimit-limit")?,
+            remaining: header("ratelimit-remaining")?,
+            reset: header("ratelimit-reset").unwrap_or(0),
+            retrying: false,
+        })
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.remaining < self.limit / 10
+    }
+
+    fn until_reset(&self) -> Option<Duration> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        self.reset.checked_sub(now).map(Duration::from_secs)
+    }
+}
+
+// Sits between the agent and reqwest: records the rate limit headers on every
+// response and retries a 429 after the reset time, or with exponential backoff
+// when the server doesn't say
+#[derive(Clone)]
+pub struct RateLimitedClient {
+    inner: Arc<ReqwestClient>,
+    status: Arc<Mutex<Option<RateLimitStatus>>>,
+}
+
+impl RateLimitedClient {
+    pub fn new(base_uri: impl AsRef<str>) -> Self {
+        Self {
+            inner: Arc::new(ReqwestClient::new(base_uri)),
+            status: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn status(&self) -> Option<RateLimitStatus> {
+        *self.status.lock().unwrap()
+    }
+
+    fn record(&self, headers: &HeaderMap, retrying: bool) {
+        let mut status = self.status.lock().unwrap();
+        if let Some(latest) = RateLimitStatus::from_headers(headers) {
+            *status = Some(RateLimitStatus { retrying, ..latest });
+        } else if let Some(known) = status.as_mut() {
+            known.retrying = retrying;
+        }
+    }
+
+    // Requests aren't Clone, so rebuild one for each attempt
+    fn copy_request(request: &Request<Vec<u8>>) -> Request<Vec<u8>> {
+        let mut copy = Request::new(request.body().clone());
+        *copy.method_mut() = request.method().clone();
+        *copy.uri_mut() = request.uri().clone();
+        *copy.version_mut() = request.version();
+        *copy.headers_mut() = request.headers().clone();
+        copy
+    }
+}
+
+impl HttpClient for RateLimitedClient {
+    async fn send_http(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Response<Vec<u8>>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 0..=MAX_RETRIES {
+            let response = self.inner.send_http(Self::copy_request(&request)).await?;
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                self.record(response.headers(), false);
+                return Ok(response);
+            }
+
+            self.record(response.headers(), true);
+            let wait = self.status()
+                .and_then(|status| status.until_reset())
+                .filter(|wait| !wait.is_zero())
+                .unwrap_or(backoff);
+            if attempt == MAX_RETRIES || wait > MAX_WAIT {
+                break;
+            }
+            log::info!("Rate limited, retrying in {:?}", wait);
+            tokio::time::sleep(wait).await;
+            backoff *= 2;
+        }
+
+        self.record(&HeaderMap::new(), false);
+        Err(Box::new(ApiError::RateLimited))
+    }
+}
+
+impl XrpcClient for RateLimitedClient {
+    fn base_uri(&self) -> String {
+        self.inner.base_uri()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn status(limit: u64, remaining: u64) -> RateLimitStatus {
+        RateLimitStatus { limit, remaining, reset: 0, retrying: false }
+    }
+
+    #[test]
+    fn reads_the_rate_limit_headers() {
+        let parsed = RateLimitStatus::from_headers(&headers(&[
+            ("ratelimit-limit", "3000"),
+            ("ratelimit-remaining", "2990"),
+            ("ratelimit-reset", "1700000000"),
+            ("ratelimit-policy", "3000;w=300"),
+        ]));
+        assert_eq!(parsed, Some(RateLimitStatus { limit: 3000, remaining: 2990, reset: 1700000000, retrying: false }));
+
+        // The reset time is optional
+        let parsed = RateLimitStatus::from_headers(&headers(&[("ratelimit-limit", "100"), ("ratelimit-remaining", "5")]));
+        assert_eq!(parsed, Some(RateLimitStatus { limit: 100, remaining: 5, reset: 0, retrying: false }));
+    }
+
+    #[test]
+    fn needs_both_limit_and_remaining() {
+        assert_eq!(RateLimitStatus::from_headers(&HeaderMap::new()), None);
+        assert_eq!(RateLimitStatus::from_headers(&headers(&[("ratelimit-limit", "100")])), None);
+        assert_eq!(RateLimitStatus::from_headers(&headers(&[("ratelimit-remaining", "5")])), None);
+        assert_eq!(
+            RateLimitStatus::from_headers(&headers(&[("ratelimit-limit", "lots"), ("ratelimit-remaining", "5")])),
+            None,
+        );
+    }
+
+    #[test]
+    fn low_under_a_tenth_remaining() {
+        assert!(!status(3000, 3000).is_low());
+        assert!(!status(3000, 300).is_low());
+        assert!(status(3000, 299).is_low());
+        assert!(status(3000, 0).is_low());
+        // A limit too small to have a tenth is never low
+        assert!(!status(5, 0).is_low());
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+use atrium_api::{app::bsky::feed::defs::PostViewData, types::string::{Cid, Did}};
+use tokio::sync::mpsc;
+
+use super::bluesky_client::BlueskyClient;
+
+pub type TaskId = u64;
+
+// A write to the user's repo, run off the input loop
+#[derive(Debug, Clone)]
+pub enum Mutation {
+    Like { uri: String, cid: Cid },
+    Unlike { post: PostViewData },
+    Repost { uri: String, cid: Cid },
+    Unrepost { post: PostViewData },
+    Follow { did: Did },
+    // `record` is the follow record to delete
+    Unfollow { did: Did, record: String },
+}
+
+impl Mutation {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Mutation::Like { .. } => "like",
+            Mutation::Unlike { .. } => "unlike",
+            Mutation::Repost { .. } => "repost",
+            Mutation::Unrepost { .. } => "undo repost",
+            Mutation::Follow { .. } => "follow",
+            Mutation::Unfollow { .. } => "unfollow",
+        }
+    }
+
+    // URI of the record created, None when one was deleted
+    async fn run<C: BlueskyClient>(self, api: &mut C) -> anyhow::Result<Option<String>> {
+        match self {
+            Mutation::Like { uri, cid } => api.like_post(&uri, &cid).await.map(Some),
+            Mutation::Unlike { post } => api.unlike_post(&post).await.map(|_| None),
+            Mutation::Repost { uri, cid } => api.repost(&uri, &cid).await.map(Some),
+            Mutation::Unrepost { post } => api.unrepost(&post).await.map(|_| None),
+            Mutation::Follow { did } => api.follow_actor(did).await.map(Some),
+            Mutation::Unfollow { did, record } => api.unfollow_actor(&did, &record).await.map(|_| None),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TaskOutcome {
+    Done(Option<String>),
+    Failed(String),
+    Cancelled,
+}
+
+#[derive(Debug)]
+pub struct TaskEvent {
+    pub id: TaskId,
+    pub description: &'static str,
+    pub outcome: TaskOutcome,
+}
+
+// Mutations go to a single worker and run one at a time, in the order they
+// were made, so a like followed by an unlike can't land the wrong way round.
+// Anything that hasn't started yet can be cancelled
+pub struct TaskQueue {
+    sender: mpsc::UnboundedSender<(TaskId, Mutation)>,
+    events: mpsc::UnboundedReceiver<TaskEvent>,
+    cancelled: Arc<Mutex<HashSet<TaskId>>>,
+    running: Arc<Mutex<Option<TaskId>>>,
+    // Queued or running tasks
+    pending: HashSet<TaskId>,
+    next_id: TaskId,
+}
+
+impl TaskQueue {
+    pub fn new<C: BlueskyClient>(api: C) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<(TaskId, Mutation)>();
+        let (event_sender, events) = mpsc::unbounded_channel();
+        let cancelled = Arc::new(Mutex::new(HashSet::new()));
+        let running = Arc::new(Mutex::new(None));
+
+        let worker_cancelled = Arc::clone(&cancelled);
+        let worker_running = Arc::clone(&running);
+        tokio::spawn(async move {
+            let mut api = api;
+            while let Some((id, mutation)) = receiver.recv().await {
+                let description = mutation.describe();
+                // Checked under the same lock cancel_pending takes, so a task is
+                // either cancelled or started, never both
+                let was_cancelled = {
+                    let mut cancelled = worker_cancelled.lock().unwrap();
+                    let was_cancelled = cancelled.remove(&id);
+                    if !was_cancelled {
+                        *worker_running.lock().unwrap() = Some(id);
+                    }
+                    was_cancelled
+                };
+                let outcome = if was_cancelled {
+                    TaskOutcome::Cancelled
+                } else {
+                    let outcome = match mutation.run(&mut api).await {
+                        Ok(record) => TaskOutcome::Done(record),
+                        Err(e) => TaskOutcome::Failed(e.to_string()),
+                    };
+                    *worker_running.lock().unwrap() = None;
+                    outcome
+                };
+                if event_sender.send(TaskEvent { id, description, outcome }).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            sender,
+            events,
+            cancelled,
+            running,
+            pending: HashSet::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn enqueue(&mut self, mutation: Mutation) -> TaskId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id);
+        if self.sender.send((id, mutation)).is_err() {
+            log::error!("Task worker has stopped, dropping task {}", id);
+        }
+        id
+    }
+
+    // Cancel everything still waiting. The one already running, if any,
+    // finishes, and its result comes back as usual
+    pub fn cancel_pending(&mut self) -> usize {
+        let mut cancelled = self.cancelled.lock().unwrap();
+        let running = *self.running.lock().unwrap();
+        let before = cancelled.len();
+        cancelled.extend(self.pending.iter().copied().filter(|id| Some(*id) != running));
+        cancelled.len() - before
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn try_recv(&mut self) -> Option<TaskEvent> {
+        let event = self.events.try_recv().ok()?;
+        self.pending.remove(&event.id);
+        Some(event)
+    }
+}
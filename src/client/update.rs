@@ -1,110 +1,103 @@
-use std::time::Duration;
-use anyhow::Result;
-use atrium_api::app::bsky::notification::list_notifications::NotificationData;
-use futures_util::StreamExt;
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+use anyhow::{bail, Result};
+use futures_util::{SinkExt, StreamExt};
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_tungstenite::{connect_async, tungstenite::{handshake::client::generate_key, Message}};
-use serde::Deserialize;
 use log::error;
-use ipld_core::ipld::Ipld;
-
-#[derive(Debug, Deserialize)]
-#[serde(tag = "t")] 
-#[allow(dead_code)]
-enum SubscriptionMessage {
-    #[serde(rename = "commit")]
-    Commit(RepoCommit),
-    #[serde(rename = "handle")]
-    Handle(HandleChange),
-    #[serde(rename = "tombstone")] 
-    Tombstone(RecordDelete),
-    #[serde(rename = "migrate")]
-    Migrate(Migration),
-}
-
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct RepoCommit {
-    #[serde(rename = "#c")]
-    collection: String,
-    commit: CommitInfo,
-    repo: String,
-    time: String,
-    blocks: Vec<Block>
-}
-
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct HandleChange {
-    did: String,
-    handle: String,
-    time: String,
-}
-
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct RecordDelete {
-    uri: String,
-    time: String,
-}
+use ipld_core::{cid::Cid, ipld::Ipld};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct Migration {
-    did: String,
-    migrated_to: String,
-    time: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct Block {
-    cid: String,
-    #[serde(rename = "val")]
-    value: Ipld,
-}
+use super::dag_cbor::{self, lookup, lookup_str};
 
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct CommitInfo {
-    #[serde(rename = "seq")]
-    sequence: i64,
-    #[serde(rename = "rebase")]
-    is_rebase: bool,
-    #[serde(rename = "tooBig")]
-    too_big: bool,
-    ops: Vec<Operation>,
-}
-
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct Operation {
-    action: String,  // "create", "update", "delete"
-    path: String,
-    #[serde(rename = "cid")]
-    content_id: String,
-}
 // Represents different types of real-time updates
 #[derive(Debug, Clone)]
 pub enum UpdateEvent {
+    // Someone else's record that will show up in our notifications: a like,
+    // repost, follow, reply, mention or quote. `uri` is that record
     Notification {
         uri: String,
     },
+    // A post or repost by someone we follow, which should reach our timeline
+    FollowedPost {
+        uri: String,
+    },
+    // A post was deleted, so anywhere it's shown should drop it
+    PostDeleted {
+        uri: String,
+    },
     ConnectionStatus(ConnectionStatus),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConnectionStatus {
     Connected,
     Disconnected,
-    Reconnecting,
+    // How many tries there have been since the connection was last up
+    Reconnecting { attempt: u32 },
+}
+
+// Where realtime updates come from, picked with the `realtime` setting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RealtimeSource {
+    #[default]
+    Off,
+    // The relay's full subscribeRepos stream: every commit on the network, as DAG-CBOR
+    Firehose,
+    // Bluesky's Jetstream: the same events as JSON, filtered by collection server-side
+    Jetstream,
+}
+
+const FIREHOSE_URL: &str = "wss://bsky.network/xrpc/com.atproto.sync.subscribeRepos";
+const JETSTREAM_URL: &str = "wss://jetstream2.us-east.bsky.network/subscribe";
+// Reconnect delays double from the first to the last, then stay there
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+// Jetstream won't take a longer wantedDids list
+pub const MAX_WANTED_DIDS: usize = 10_000;
+
+// Collections whose records can notify someone
+const NOTIFYING_COLLECTIONS: [&str; 4] = [
+    "app.bsky.feed.post",
+    "app.bsky.feed.like",
+    "app.bsky.feed.repost",
+    "app.bsky.graph.follow",
+];
+
+#[derive(Deserialize)]
+struct JetstreamEvent {
+    did: String,
+    kind: String,
+    commit: Option<JetstreamCommit>,
+}
+
+#[derive(Deserialize)]
+struct JetstreamCommit {
+    operation: String,
+    collection: String,
+    rkey: String,
+    record: Option<Ipld>,
+}
+
+// A record created in a commit, decoded from the commit's CAR blocks
+struct CreatedRecord {
+    repo: String,
+    // at://<repo>/<collection>/<rkey>
+    uri: String,
+    collection: String,
+    value: Ipld,
 }
 
 pub struct UpdateManager {
     sender: mpsc::Sender<UpdateEvent>,
     receiver: mpsc::Receiver<UpdateEvent>,
     ws_task: Option<JoinHandle<()>>,
-    reconnect_interval: Duration,
-    service_url: String,
 }
 
 impl UpdateManager {
@@ -114,19 +107,39 @@ impl UpdateManager {
             sender,
             receiver,
             ws_task: None,
-            reconnect_interval: Duration::from_secs(5),
-            service_url: "wss://bsky.network/xrpc/com.atproto.sync.subscribeRepos".to_string(),
         }
     }
 
-    pub async fn start(&mut self, auth_jwt: String) -> Result<()> {
+    // Watch `source` for records that concern `did`, the logged-in account,
+    // and for posts from the repos in `wanted_dids`. Jetstream is told to send
+    // only those repos, so there likes and replies from anyone else are left
+    // to the notification poll. Both streams are public, so no session is needed
+    pub async fn start(&mut self, did: String, source: RealtimeSource, wanted_dids: Vec<String>) -> Result<()> {
+        let service_url = match source {
+            RealtimeSource::Off => return Ok(()),
+            RealtimeSource::Firehose => FIREHOSE_URL.to_string(),
+            RealtimeSource::Jetstream => {
+                let mut url = url::Url::parse(JETSTREAM_URL)?;
+                for collection in NOTIFYING_COLLECTIONS {
+                    url.query_pairs_mut().append_pair("wantedCollections", collection);
+                }
+                // Too many DIDs for a URL, so they go in an options message
+                // once connected. Until then Jetstream holds off sending
+                if !wanted_dids.is_empty() {
+                    url.query_pairs_mut().append_pair("requireHello", "true");
+                }
+                url.to_string()
+            }
+        };
+        self.stop().await;
         let sender = self.sender.clone();
-        let service_url = self.service_url.clone();
-        let reconnect_interval = self.reconnect_interval;
+        let wanted: Arc<HashSet<String>> = Arc::new(wanted_dids.into_iter().take(MAX_WANTED_DIDS).collect());
 
         let task = tokio::spawn(async move {
+            let mut attempt = 0;
             loop {
-                match Self::run_subscription(&service_url, &auth_jwt, &sender).await {
+                let mut connected = false;
+                match Self::run_subscription(&service_url, source, &did, &wanted, &sender, &mut connected).await {
                     Ok(_) => {
                         error!("WebSocket connection closed normally");
                     }
@@ -134,15 +147,21 @@ impl UpdateManager {
                         error!("WebSocket error: {:?}", e);
                     }
                 }
+                if connected {
+                    attempt = 0;
+                }
 
                 // Notify about disconnection
                 let _ = sender.send(UpdateEvent::ConnectionStatus(ConnectionStatus::Disconnected)).await;
-                
+
                 // Wait before reconnecting
-                tokio::time::sleep(reconnect_interval).await;
-                
+                let wait = Self::backoff(attempt);
+                attempt += 1;
+                log::info!("Reconnecting to {} in {:?} (attempt {})", service_url, wait, attempt);
+                tokio::time::sleep(wait).await;
+
                 // Notify about reconnection attempt
-                let _ = sender.send(UpdateEvent::ConnectionStatus(ConnectionStatus::Reconnecting)).await;
+                let _ = sender.send(UpdateEvent::ConnectionStatus(ConnectionStatus::Reconnecting { attempt })).await;
             }
         });
 
@@ -150,106 +169,253 @@ impl UpdateManager {
         Ok(())
     }
 
+    // Somewhere between half and all of the doubled delay, so clients that
+    // dropped together don't all come back at the same moment
+    fn backoff(attempt: u32) -> Duration {
+        let full = INITIAL_BACKOFF
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        // A fresh RandomState is randomly keyed, which is all the randomness needed here
+        let random = RandomState::new().build_hasher().finish();
+        full / 2 + full.mul_f64((random % 1000) as f64 / 2000.0)
+    }
+
     async fn run_subscription(
         service_url: &str,
-        auth_jwt: &str,
+        source: RealtimeSource,
+        did: &str,
+        wanted: &HashSet<String>,
         sender: &mpsc::Sender<UpdateEvent>,
+        // Set once the socket is open, so the caller knows to reset its attempt count
+        connected: &mut bool,
     ) -> Result<()> {
         // Parse URL to get host
         let url = url::Url::parse(service_url)?;
         let host = url.host_str().ok_or_else(|| anyhow::anyhow!("Missing host in URL"))?;
-    
+
         // Create request with all required headers
         let request = http::Request::builder()
             .uri(service_url)
             .header("Host", host)
-            .header("Authorization", format!("Bearer {}", auth_jwt))
             .header("Upgrade", "websocket")
             .header("Connection", "Upgrade")
             .header("Sec-WebSocket-Version", "13")
             .header("Sec-WebSocket-Key", generate_key())
             .body(())?;
-    
+
         // Connect to WebSocket
         let (ws_stream, _) = connect_async(request).await?;
-        let (_, mut read) = ws_stream.split();
+        *connected = true;
+        let (mut write, mut read) = ws_stream.split();
+
+        if source == RealtimeSource::Jetstream && !wanted.is_empty() {
+            let options = serde_json::json!({
+                "type": "options_update",
+                "payload": {
+                    "wantedCollections": NOTIFYING_COLLECTIONS,
+                    "wantedDids": wanted,
+                },
+            });
+            write.send(Message::Text(options.to_string())).await?;
+        }
 
         // Send successful connection event
         sender.send(UpdateEvent::ConnectionStatus(ConnectionStatus::Connected)).await?;
 
         // Handle incoming messages
         while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    match Self::parse_update(&text) {
-                        Ok(Some(event)) => {
-                            if let Err(e) = sender.send(event).await {
-                                log::error!("Failed to send update event: {:?}", e);
-                                break;
-                            }
-                        }
-                        Ok(None) => continue,
-                        Err(e) => {
-                            log::error!("Failed to parse update: {:?}", e);
-                            continue;
-                        }
-                    }
-                }
-                Ok(Message::Close(_)) => {
+            let parsed = match (source, msg) {
+                // Firehose frames are binary DAG-CBOR
+                (RealtimeSource::Firehose, Ok(Message::Binary(frame))) => Self::parse_frame(&frame, did, wanted),
+                (RealtimeSource::Jetstream, Ok(Message::Text(text))) => Self::parse_jetstream(&text, did, wanted),
+                (_, Ok(Message::Close(_))) => {
                     log::info!("WebSocket connection closed by server");
                     break;
                 }
-                Err(e) => {
+                (_, Err(e)) => {
                     log::error!("WebSocket error: {:?}", e);
                     break;
                 }
-                _ => {} // Ignore other message types
+                _ => continue, // Ignore other message types
+            };
+            let events = match parsed {
+                Ok(events) => events,
+                Err(e) => {
+                    log::error!("Failed to parse update: {:?}", e);
+                    continue;
+                }
+            };
+            for event in events {
+                if let Err(e) = sender.send(event).await {
+                    log::error!("Failed to send update event: {:?}", e);
+                    return Ok(());
+                }
             }
         }
 
         Ok(())
     }
-    
-    fn parse_update(text: &str) -> Result<Option<UpdateEvent>> {
-        let message: SubscriptionMessage = serde_json::from_str(text)?;
-
-        match message {
-            SubscriptionMessage::Commit(commit) => {
-                // Only care about notification collection
-                if !commit.collection.starts_with("app.bsky.notification") {
-                    return Ok(None);
-                }
 
-                // Process each operation in the commit
-                for op in commit.commit.ops {
-                    // Find the corresponding block for this operation
-                    if let Some(block) = commit.blocks.iter().find(|b| b.cid == op.content_id) {
-                        // Try to parse notification data from the block
-                        if let Ok(_notification) = serde_json::from_value::<NotificationData>(
-                            serde_json::to_value(&block.value)?
-                        ) {
-                            return Ok(Some(UpdateEvent::Notification {
-                                uri: format!("at://{}/app.bsky.notification/{}", 
-                                    commit.repo,
-                                    op.path.split('/').last().unwrap_or_default()
-                                ),
-                            }));
-                        }
-                    }
-                }
+    // A frame is a header ({op, t}) followed by the message body
+    fn parse_frame(frame: &[u8], did: &str, wanted: &HashSet<String>) -> Result<Vec<UpdateEvent>> {
+        let (header, used) = dag_cbor::decode_prefix(frame)?;
+        let body = dag_cbor::decode(&frame[used..])?;
+
+        if matches!(lookup(&header, &["op"]), Some(Ipld::Integer(-1))) {
+            bail!(
+                "Relay sent an error: {} {}",
+                lookup_str(&body, &["error"]).unwrap_or("unknown"),
+                lookup_str(&body, &["message"]).unwrap_or(""),
+            );
+        }
+        // #identity, #account and the like don't concern us
+        if lookup_str(&header, &["t"]) != Some("#commit") {
+            return Ok(Vec::new());
+        }
+        let mut events: Vec<UpdateEvent> = Self::deleted_posts(&body)
+            .into_iter()
+            .map(|uri| UpdateEvent::PostDeleted { uri })
+            .collect();
+        events.extend(Self::created_records(&body)?
+            .into_iter()
+            .filter_map(|record| Self::event_for(record, did, wanted)));
+        Ok(events)
+    }
+
+    // Jetstream sends one JSON object per event, the record already decoded
+    fn parse_jetstream(text: &str, did: &str, wanted: &HashSet<String>) -> Result<Vec<UpdateEvent>> {
+        let event: JetstreamEvent = serde_json::from_str(text)?;
+        if event.kind != "commit" {
+            return Ok(Vec::new());
+        }
+        let Some(JetstreamCommit { operation, collection, rkey, record }) = event.commit else {
+            return Ok(Vec::new());
+        };
+        let uri = format!("at://{}/{}/{}", event.did, collection, rkey);
+        let value = match (operation.as_str(), record) {
+            ("delete", _) if collection == "app.bsky.feed.post" => {
+                return Ok(vec![UpdateEvent::PostDeleted { uri }]);
             }
-            SubscriptionMessage::Handle(_) => {
-                // Could track handle changes if needed
+            ("create", Some(value)) => value,
+            _ => return Ok(Vec::new()),
+        };
+
+        let record = CreatedRecord {
+            uri,
+            repo: event.did,
+            collection,
+            value,
+        };
+        Ok(Self::event_for(record, did, wanted).into_iter().collect())
+    }
+
+    fn event_for(record: CreatedRecord, did: &str, wanted: &HashSet<String>) -> Option<UpdateEvent> {
+        if Self::concerns(&record, did) {
+            return Some(UpdateEvent::Notification { uri: record.uri });
+        }
+        // Unfiltered, everything on the network would count as followed
+        let followed = !wanted.is_empty() && wanted.contains(&record.repo);
+        match record.collection.as_str() {
+            "app.bsky.feed.post" | "app.bsky.feed.repost" if followed => {
+                Some(UpdateEvent::FollowedPost { uri: record.uri })
             }
-            SubscriptionMessage::Tombstone(_delete) => {
-                // Could track deleted notifications if needed
+            _ => None,
+        }
+    }
+
+    // URIs of the posts a #commit deletes. Deletes carry no record, just the path
+    fn deleted_posts(commit: &Ipld) -> Vec<String> {
+        let (Some(repo), Some(Ipld::List(ops))) = (lookup_str(commit, &["repo"]), lookup(commit, &["ops"])) else {
+            return Vec::new();
+        };
+        ops.iter()
+            .filter(|op| lookup_str(op, &["action"]) == Some("delete"))
+            .filter_map(|op| lookup_str(op, &["path"]))
+            .filter(|path| path.starts_with("app.bsky.feed.post/"))
+            .map(|path| format!("at://{}/{}", repo, path))
+            .collect()
+    }
+
+    // The records a #commit creates. Updates and deletes never notify anyone
+    fn created_records(commit: &Ipld) -> Result<Vec<CreatedRecord>> {
+        let Some(repo) = lookup_str(commit, &["repo"]) else {
+            bail!("Commit without a repo");
+        };
+        let Some(Ipld::List(ops)) = lookup(commit, &["ops"]) else {
+            return Ok(Vec::new());
+        };
+        // Oversized commits leave their blocks out
+        let blocks: HashMap<Cid, Vec<u8>> = match lookup(commit, &["blocks"]) {
+            Some(Ipld::Bytes(car)) if !car.is_empty() => dag_cbor::read_car(car)?,
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut records = Vec::new();
+        for op in ops {
+            if lookup_str(op, &["action"]) != Some("create") {
+                continue;
             }
-            SubscriptionMessage::Migrate(_) => {
-                // Could handle DID migrations if needed
+            let (Some(path), Some(Ipld::Link(cid))) = (lookup_str(op, &["path"]), lookup(op, &["cid"])) else {
+                continue;
+            };
+            let Some(block) = blocks.get(cid) else {
+                continue;
+            };
+            let value = match dag_cbor::decode(block) {
+                Ok(value) => value,
+                Err(e) => {
+                    log::info!("Skipping undecodable record {}: {}", path, e);
+                    continue;
+                }
+            };
+            records.push(CreatedRecord {
+                repo: repo.to_string(),
+                uri: format!("at://{}/{}", repo, path),
+                collection: path.split('/').next().unwrap_or_default().to_string(),
+                value,
+            });
+        }
+        Ok(records)
+    }
+
+    // Would this record appear in `did`'s notifications?
+    fn concerns(record: &CreatedRecord, did: &str) -> bool {
+        let ours = format!("at://{}/", did);
+        let points_at_us = |path: &[&str]| lookup_str(&record.value, path).is_some_and(|uri| uri.starts_with(&ours));
+        // Our own likes and replies don't notify us
+        if record.uri.starts_with(&ours) {
+            return false;
+        }
+
+        match record.collection.as_str() {
+            "app.bsky.feed.like" | "app.bsky.feed.repost" => points_at_us(&["subject", "uri"]),
+            "app.bsky.graph.follow" => lookup_str(&record.value, &["subject"]) == Some(did),
+            "app.bsky.feed.post" => {
+                points_at_us(&["reply", "parent", "uri"])
+                    || points_at_us(&["reply", "root", "uri"])
+                    // A quote, with or without media alongside
+                    || points_at_us(&["embed", "record", "uri"])
+                    || points_at_us(&["embed", "record", "record", "uri"])
+                    || Self::mentions(&record.value, did)
             }
+            _ => false,
         }
+    }
 
-        Ok(None)
+    fn mentions(post: &Ipld, did: &str) -> bool {
+        let Some(Ipld::List(facets)) = lookup(post, &["facets"]) else {
+            return false;
+        };
+        facets.iter()
+            .filter_map(|facet| match lookup(facet, &["features"]) {
+                Some(Ipld::List(features)) => Some(features),
+                _ => None,
+            })
+            .flatten()
+            .any(|feature| {
+                lookup_str(feature, &["$type"]) == Some("app.bsky.richtext.facet#mention")
+                    && lookup_str(feature, &["did"]) == Some(did)
+            })
     }
 
     pub fn try_recv(&mut self) -> Option<UpdateEvent> {
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{client::update::RealtimeSource, logging::LogSettings, ui::{components::images::ImageCacheSettings, emoji::EmojiSettings, filters::FilterSettings}};
+
+// User preferences. Kept separate from config.json, which belongs to the
+// bsky agent and only holds the session.
+pub const SETTINGS_PATH: &str = "settings.json";
+
+// How a new reply or mention gets your attention
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MentionAlert {
+    #[default]
+    Off,
+    // The terminal bell
+    Bell,
+    // Highlight the status line for a few seconds
+    Flash,
+    Both,
+}
+
+impl MentionAlert {
+    pub fn rings(self) -> bool {
+        matches!(self, MentionAlert::Bell | MentionAlert::Both)
+    }
+
+    pub fn flashes(self) -> bool {
+        matches!(self, MentionAlert::Flash | MentionAlert::Both)
+    }
+}
+
+// How much room each post takes in the timeline, threads and profiles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    #[default]
+    Normal,
+    // One line per post: handle, the start of the text and counts
+    Compact,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub emoji: EmojiSettings,
+    pub filters: FilterSettings,
+    // Extra timeline pages fetched when jumping to the end with G
+    pub auto_pagination_pages: usize,
+    // How long to wait for input before looking at background work again
+    pub tick_rate_ms: u64,
+    // How often to check for new notifications
+    pub notification_interval_secs: u64,
+    // How often to poll authors marked with :alert
+    pub author_alert_interval_secs: u64,
+    // Merge new posts into the timeline this often without waiting for gn. 0 is off
+    pub timeline_refresh_interval_secs: u64,
+    // Reopen the timeline at the post selected when Skyline last quit
+    pub resume_timeline_position: bool,
+    pub mention_alert: MentionAlert,
+    // Stream for live notifications, on top of the notification poll. Jetstream
+    // only sends records from followed accounts, so the poll still covers the rest
+    pub realtime: RealtimeSource,
+    // Take mouse events (wheel scrolling). Off leaves the mouse to the
+    // terminal, for selecting text
+    pub mouse: bool,
+    // Short names for commands, e.g. "tl" -> "timeline" or "d" -> "feed discover"
+    pub aliases: BTreeMap<String, String>,
+    // Columns :deck opens beside the timeline, e.g. ["notifications", "discover"]
+    pub deck: Vec<String>,
+    // Open threads with v in a pane beside the list they came from
+    pub split_threads: bool,
+    pub density: Density,
+    // Ask before deleting a post or unfollowing someone
+    pub confirm_destructive: bool,
+    pub image_cache: ImageCacheSettings,
+    pub log: LogSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            emoji: EmojiSettings::default(),
+            filters: FilterSettings::default(),
+            auto_pagination_pages: 3,
+            tick_rate_ms: 250,
+            notification_interval_secs: 120,
+            author_alert_interval_secs: 300,
+            timeline_refresh_interval_secs: 0,
+            resume_timeline_position: false,
+            mention_alert: MentionAlert::Off,
+            realtime: RealtimeSource::Off,
+            mouse: true,
+            aliases: BTreeMap::new(),
+            deck: Vec::new(),
+            split_threads: false,
+            density: Density::Normal,
+            confirm_destructive: true,
+            image_cache: ImageCacheSettings::default(),
+            log: LogSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        match std::fs::read_to_string(SETTINGS_PATH) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(settings) => settings,
+                Err(e) => {
+                    log::warn!("Ignoring invalid {}: {}", SETTINGS_PATH, e);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(SETTINGS_PATH, contents)?;
+        Ok(())
+    }
+}
@@ -1,2 +1,4 @@
 pub mod client;
 pub mod ui;
+pub mod config;
+pub mod logging;
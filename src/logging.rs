@@ -0,0 +1,318 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{atomic::{AtomicBool, Ordering}, Mutex},
+};
+use anyhow::{anyhow, Result};
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+
+// While the TUI owns the terminal, stderr output would land on top of it
+static TUI_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_tui_active(active: bool) {
+    TUI_ACTIVE.store(active, Ordering::Relaxed);
+}
+
+// The last few lines logged, for crash reports
+const RECENT_LINES: usize = 50;
+static RECENT: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+fn remember(record: &log::Record) {
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() == RECENT_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(record.args().to_string());
+    }
+}
+
+// Oldest first. Empty rather than waiting if a panic struck mid-log
+pub fn recent_lines() -> Vec<String> {
+    RECENT.try_lock().map(|recent| recent.iter().cloned().collect()).unwrap_or_default()
+}
+
+// Where logs go and how much detail they have. The command line overrides
+// these, see apply_args
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogSettings {
+    // off, error, warn, info, debug or trace
+    pub level: String,
+    // Levels for single modules, e.g. "skyline::client::update": "debug"
+    pub modules: BTreeMap<String, String>,
+    // Unset is skyline/skyline.log in the XDG state directory, see log_path
+    pub path: Option<PathBuf>,
+    // Also log to stderr, before the TUI starts and after it exits
+    pub stderr: bool,
+    // Once the log reaches this size it's moved to skyline.log.1 and a new
+    // one started. 0 never rotates
+    pub max_size_mb: u64,
+    // Rotated logs kept, .1 the newest
+    pub keep: usize,
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+            path: None,
+            stderr: false,
+            max_size_mb: 10,
+            keep: 3,
+        }
+    }
+}
+
+pub const USAGE: &str = "Usage: skyline [--log-level LEVEL] [--log-module MODULE=LEVEL] [--log-file PATH] [--log-stderr]";
+
+impl LogSettings {
+    // --log-level, --log-module, --log-file and --log-stderr
+    pub fn apply_args(&mut self, args: impl IntoIterator<Item = String>) -> Result<()> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!("{} needs a value\n{}", arg, USAGE));
+            match arg.as_str() {
+                "--log-level" => self.level = value()?,
+                "--log-module" => {
+                    let pair = value()?;
+                    let (module, level) = pair
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("--log-module takes MODULE=LEVEL, not {}", pair))?;
+                    self.modules.insert(module.to_string(), level.to_string());
+                }
+                "--log-file" => self.path = Some(PathBuf::from(value()?)),
+                "--log-stderr" => self.stderr = true,
+                _ => return Err(anyhow!("Unknown option {}\n{}", arg, USAGE)),
+            }
+        }
+        Ok(())
+    }
+
+    // $XDG_STATE_HOME/skyline/skyline.log, falling back to ~/.local/state
+    // and then to the working directory
+    pub fn log_path(&self) -> PathBuf {
+        if let Some(path) = &self.path {
+            return path.clone();
+        }
+        let state_dir = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state")));
+        match state_dir {
+            Some(dir) => dir.join("skyline").join("skyline.log"),
+            None => PathBuf::from("skyline.log"),
+        }
+    }
+}
+
+// The log file, moved aside for a fresh one whenever it outgrows max_bytes:
+// skyline.log becomes skyline.log.1, .1 becomes .2 and so on, and the
+// oldest past `keep` is deleted
+struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    keep: usize,
+}
+
+impl RotatingFile {
+    fn open(path: &Path, max_bytes: u64, keep: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self { path: path.to_path_buf(), file, written, max_bytes, keep })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if self.keep == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            // Missing files just mean fewer rotations so far
+            let _ = std::fs::remove_file(self.rotated(self.keep));
+            for n in (1..self.keep).rev() {
+                let _ = std::fs::rename(self.rotated(n), self.rotated(n + 1));
+            }
+            std::fs::rename(&self.path, self.rotated(1))?;
+            self.file = File::create(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+// fern flushes after every record, so rotating there never splits a line
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.file.write(buf)?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_bytes > 0 && self.written >= self.max_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter> {
+    LevelFilter::from_str(level).map_err(|_| anyhow!("Unknown log level {}, expected off, error, warn, info, debug or trace", level))
+}
+
+pub fn init(settings: &LogSettings) -> Result<()> {
+    let path = settings.log_path();
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut dispatch = fern::Dispatch::new()
+        .format(|out, message, record| {
+            out.finish(format_args!(
+                "{} [{}] {}: {}",
+                chrono::Local::now().format("%H:%M:%S"),
+                record.level(),
+                record.target(),
+                message
+            ))
+        })
+        .level(parse_level(&settings.level)?);
+    for (module, level) in &settings.modules {
+        dispatch = dispatch.level_for(module.clone(), parse_level(level)?);
+    }
+
+    let file = RotatingFile::open(&path, settings.max_size_mb * 1024 * 1024, settings.keep)?;
+    dispatch = dispatch
+        .chain(Box::new(file) as Box<dyn Write + Send>)
+        .chain(fern::Output::call(remember));
+    if settings.stderr {
+        dispatch = dispatch.chain(
+            fern::Dispatch::new()
+                .filter(|_| !TUI_ACTIVE.load(Ordering::Relaxed))
+                .chain(std::io::stderr()),
+        );
+    }
+    dispatch.apply()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    // A fresh directory per test, so they can run side by side
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("skyline-logging-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn args_override_settings() {
+        let mut settings = LogSettings::default();
+        settings.apply_args(args(&[
+            "--log-level", "debug",
+            "--log-module", "skyline::client::update=trace",
+            "--log-module", "hyper=warn",
+            "--log-file", "/tmp/other.log",
+            "--log-stderr",
+        ])).unwrap();
+        assert_eq!(settings.level, "debug");
+        assert_eq!(settings.modules.get("skyline::client::update").map(String::as_str), Some("trace"));
+        assert_eq!(settings.modules.get("hyper").map(String::as_str), Some("warn"));
+        assert_eq!(settings.log_path(), PathBuf::from("/tmp/other.log"));
+        assert!(settings.stderr);
+
+        // No arguments leaves everything alone
+        let mut untouched = LogSettings::default();
+        untouched.apply_args(Vec::new()).unwrap();
+        assert_eq!(untouched.level, "info");
+        assert!(untouched.modules.is_empty() && untouched.path.is_none() && !untouched.stderr);
+    }
+
+    #[test]
+    fn rejects_bad_args() {
+        for bad in [
+            &["--log-level"][..],
+            &["--log-module", "skyline"],
+            &["--log-file"],
+            &["--verbose"],
+            &["debug"],
+        ] {
+            assert!(LogSettings::default().apply_args(args(bad)).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn rotation_shifts_old_logs_and_drops_the_oldest() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("skyline.log");
+        let mut file = RotatingFile::open(&path, 1024, 2).unwrap();
+        let (one, two, three) = (file.rotated(1), file.rotated(2), file.rotated(3));
+
+        for line in ["first\n", "second\n", "third\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+            file.rotate().unwrap();
+        }
+        file.write_all(b"fourth\n").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(read(&path), "fourth\n");
+        assert_eq!(read(&one), "third\n");
+        assert_eq!(read(&two), "second\n");
+        assert!(!three.exists());
+        assert_eq!(file.written, 7);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotates_on_flush_once_past_the_limit() {
+        let dir = temp_dir("flush");
+        let path = dir.join("skyline.log");
+        let mut file = RotatingFile::open(&path, 10, 1).unwrap();
+
+        file.write_all(b"short\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(read(&path), "short\n");
+
+        file.write_all(b"over the limit\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(read(&path), "");
+        assert_eq!(read(&file.rotated(1)), "short\nover the limit\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn keeping_none_truncates_in_place() {
+        let dir = temp_dir("keep-none");
+        let path = dir.join("skyline.log");
+        let mut file = RotatingFile::open(&path, 1024, 0).unwrap();
+
+        file.write_all(b"gone\n").unwrap();
+        file.rotate().unwrap();
+        assert_eq!(read(&path), "");
+        assert!(!file.rotated(1).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,39 +1,42 @@
 // In main.rs
 use anyhow::Result;
+use ratatui::crossterm::event::{DisableFocusChange, DisableMouseCapture};
 use ratatui::crossterm::execute;
 use ratatui::crossterm::terminal::{disable_raw_mode, LeaveAlternateScreen};
 use std::io;
+use std::path::Path;
 use std::panic;
 
 use skyline::client::api::API;
+use skyline::config::Settings;
+use skyline::logging;
 use skyline::ui::App;
 
-use simplelog::{Config, LevelFilter, WriteLogger};
-use std::fs::File;
-
-pub fn setup_logging() -> std::io::Result<()> {
-    WriteLogger::init(
-        LevelFilter::Info,
-        Config::default(),
-        File::create("skyline.log")?,
-    )
-    .expect("Failed to initialize logger");
-    Ok(())
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
-    setup_logging()?;
+    // settings.json first, then the command line on top
+    let mut log_settings = Settings::load().log;
+    log_settings.apply_args(std::env::args().skip(1))?;
+    logging::init(&log_settings)?;
+    // Crash reports go beside the log
+    let crash_dir = log_settings.log_path().parent().map(Path::to_path_buf).unwrap_or_default();
 
     // Set up panic hook for cleanup
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        // Save what we can so the next launch can offer :restore
+        skyline::ui::recovery::write_on_panic();
+        let report = skyline::ui::recovery::write_crash_report(&panic_info.to_string(), &crash_dir);
         // Clean up terminal
+        logging::set_tui_active(false);
         let _ = disable_raw_mode();
         let mut stdout = io::stdout();
-        let _ = execute!(stdout, LeaveAlternateScreen);
+        let _ = execute!(stdout, DisableMouseCapture, DisableFocusChange, LeaveAlternateScreen);
         // Call the original panic handler
         original_hook(panic_info);
+        if let Some(path) = report {
+            eprintln!("A crash report was written to {}", path.display());
+        }
     }));
 
     // Create and run app
@@ -43,7 +46,7 @@ async fn main() -> Result<()> {
     if let Err(err) = app.run().await {
         // Clean up terminal before handling the error
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = execute!(io::stdout(), DisableMouseCapture, DisableFocusChange, LeaveAlternateScreen);
         eprintln!("Error: {:?}", err);
     }
 
@@ -1,15 +1,18 @@
-use crate::client::{api::API, update::{UpdateEvent, UpdateManager}};
+use crate::config::{Density, MentionAlert, Settings};
+use crate::client::{api::{is_connectivity_error, ApiError, API}, bluesky_client::{BlueskyClient, TrendTarget, CURATED_LIST, MODERATION_LIST}, outbox::{new_tid, Outbox, OutboxEntry, OutboxId, SendOutcome, OUTBOX_PATH}, task_queue::{Mutation, TaskId, TaskOutcome, TaskQueue}, author_alerts::{AuthorAlerts, AUTHOR_ALERTS_PATH}, links::{list_uri, BskyLink}, update::{ConnectionStatus, RealtimeSource, UpdateEvent, UpdateManager, MAX_WANTED_DIDS}};
 use anyhow::Result;
-use atrium_api::{app::bsky::feed::defs::PostView, types::string::{AtIdentifier, Handle}};
-use ratatui::crossterm::{event::{KeyCode, KeyEvent, KeyModifiers}, terminal::EnterAlternateScreen};
+use atrium_api::{app::bsky::{actor::defs::{ProfileView, ProfileViewDetailed}, feed::{defs::{FeedViewPost, PostView, PostViewData, ViewerStateData}, get_post_thread::OutputThreadRefs}, graph::defs::ListView, notification::list_notifications::NotificationData}, types::string::{AtIdentifier, Did}};
+use ratatui::crossterm::{event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind}, terminal::EnterAlternateScreen};
 use secrecy::SecretString;
 use tokio::sync::mpsc;
 use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    future::Future,
     sync::Arc,
     time::{Duration, Instant},
 };
 
-use super::{components::{command_input::CommandInput, images::ImageManager, login::LoginView, post_composer::PostComposer, post_list::PostList}, views::{View, ViewStack}};
+use super::{clipboard::copy_to_clipboard, emoji, filters, help, recovery::{self, RecoverySnapshot, ViewSummary}, session::{SavedSession, TimelinePosition}, task_manager::{TaskHandle, TaskManager}, components::{command_input::CommandInput, images::ImageManager, feed::FeedSource, info_view::InfoView, login::LoginView, profile_list::ProfileListSource, splash::Splash, thread::ReplySort, avatar_preview::AvatarPreview, confirm::ConfirmDialog, list_picker::ListPicker, toasts::{Severity, Toasts}, post_composer::PostComposer, post_list::{HeightCache, PostList, PostListBase, SharedHeightCache}}, views::{View, ViewStack}};
 
 use ratatui::crossterm::{
     event::{self, Event},
@@ -21,17 +24,181 @@ use std::io::{self, Write};
 
 use crate::ui::draw;
 
-pub struct App {
-    pub api: API,
-    pub loading: bool,
+// How many extra timeline pages a mark jump may load looking for its post
+const MAX_MARK_SEARCH_PAGES: usize = 5;
+
+// Oldest author alerts are dropped past this
+const MAX_AUTHOR_ALERTS: usize = 50;
+
+// (did, newest post uri, new posts) for each polled author
+type AlertPollResult = Vec<(String, Option<String>, Vec<NotificationData>)>;
+
+// Gives the appview a moment to see a write, and lets a burst of likes
+// share one refetch
+const REFRESH_DELAY: Duration = Duration::from_millis(200);
+
+// Largest count prefix taken, so 99999j doesn't spin for ages
+const MAX_COUNT: usize = 999;
+
+// Two clicks on the same item closer together than this open it, like `v`
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+// The timeline plus up to two more in deck mode
+const MAX_DECK_COLUMNS: usize = 3;
+
+// How often to check the top of the timeline for posts newer than what's shown
+const TIMELINE_PEEK_INTERVAL: Duration = Duration::from_secs(60);
+// After the realtime stream sees a followed account post, give the AppView a
+// moment to index it before peeking, and don't peek more often than this
+// however chatty the follows are
+const REALTIME_PEEK_DELAY: Duration = Duration::from_secs(5);
+const REALTIME_PEEK_MIN_GAP: Duration = Duration::from_secs(20);
+
+// How often to try sending posts from the outbox again
+const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+// While the terminal doesn't have focus, the tick and the notification and
+// timeline checks are this many times slower
+const UNFOCUSED_SLOWDOWN: u32 = 4;
+
+// How long the status line stays highlighted for a new reply or mention
+const MENTION_FLASH: Duration = Duration::from_secs(3);
+
+// How many errors :errors keeps
+const MAX_ERROR_HISTORY: usize = 50;
+
+// An error as :errors lists it, with where you were when it happened
+struct ErrorRecord {
+    at: chrono::DateTime<chrono::Local>,
+    message: String,
+    // The focused view's title, and its selected item (usually a post URI)
+    view: String,
+    selected: Option<String>,
+}
+
+// A view's data fetched on a spawned task, so a slow request doesn't hold
+// up the keyboard. The view itself is built back on the event loop
+enum Fetched {
+    // `split` is split_threads as it was when v was pressed
+    Thread { refs: OutputThreadRefs, split: bool },
+    AuthorFeed { profile: Box<ProfileViewDetailed>, feed: Vec<FeedViewPost> },
+}
+
+// (what was being opened, for the error message; its task; the result)
+type FetchResult = (&'static str, TaskHandle, Result<Fetched>);
+
+// What a ConfirmDialog does when answered with y
+pub enum Confirmable {
+    DeletePost { uri: String },
+    Unfollow { did: Did, record: String },
+}
+
+// Stands in for the record URI of a like, repost or follow that's still being created
+const PENDING_RECORD: &str = "";
+
+#[derive(Debug, Clone, Copy)]
+enum Interaction {
+    Like,
+    Repost,
+}
+
+impl Interaction {
+    fn verb(&self) -> &'static str {
+        match self {
+            Interaction::Like => "like",
+            Interaction::Repost => "repost",
+        }
+    }
+
+    fn record<'a>(&self, viewer: &'a ViewerStateData) -> Option<&'a String> {
+        match self {
+            Interaction::Like => viewer.like.as_ref(),
+            Interaction::Repost => viewer.repost.as_ref(),
+        }
+    }
+
+    // Set our record on the post, adjusting its count if that changes whether it's there
+    fn apply(&self, post: &mut PostViewData, record: Option<String>) {
+        let had_record = post.viewer.as_ref().is_some_and(|viewer| self.record(&viewer.data).is_some());
+        let count = match self {
+            Interaction::Like => &mut post.like_count,
+            Interaction::Repost => &mut post.repost_count,
+        };
+        match (had_record, record.is_some()) {
+            (false, true) => *count = Some(count.unwrap_or(0) + 1),
+            (true, false) => *count = Some((count.unwrap_or(0) - 1).max(0)),
+            _ => {}
+        }
+        self.apply_record(post, record);
+    }
+
+    // Set our record on the post without touching its count
+    fn apply_record(&self, post: &mut PostViewData, record: Option<String>) {
+        if let Some(viewer) = post.viewer.as_mut() {
+            match self {
+                Interaction::Like => viewer.data.like = record,
+                Interaction::Repost => viewer.data.repost = record,
+            }
+        }
+    }
+}
+
+// A tab other than the one showing: its views and deck columns, as they were
+// left. The showing tab lives in view_stack, deck and deck_focus
+struct Tab {
+    stack: ViewStack,
+    deck: Vec<ViewStack>,
+    deck_focus: usize,
+}
+
+impl Tab {
+    fn main_stack(&self) -> &ViewStack {
+        match self.deck_focus {
+            0 => &self.stack,
+            _ => &self.deck[0],
+        }
+    }
+
+    fn title(&self) -> String {
+        self.stack.views.last().map(View::short_title).unwrap_or_default()
+    }
+}
+
+// Generic over the backend so views can run against something other than a live PDS
+pub struct App<C: BlueskyClient = API> {
+    pub api: C,
+    // Loads, posts and uploads in flight, shown in the status line
+    pub task_manager: TaskManager,
+    // Set wherever something fails; the event loop turns it into a toast
     pub error: Option<String>,
     pub view_stack: ViewStack,
     pub status_line: String,
     pub image_manager: Arc<ImageManager>,
-    post_update_sender: mpsc::Sender<PostView>,
-    post_update_receiver: mpsc::Receiver<PostView>,
-    notification_check_interval: Duration,
+    // Measured post heights, for every column and tab
+    height_cache: SharedHeightCache,
+    // Likes, reposts and follows run here instead of blocking input
+    tasks: TaskQueue,
+    // Like or repost task -> what it does, and the post before and after the optimistic change
+    interaction_tasks: HashMap<TaskId, (Interaction, PostViewData, PostViewData)>,
+    // DID -> our follow record for them, None when we don't follow them.
+    // Only accounts followed or unfollowed this session; it beats whatever
+    // viewer state a view was fetched with
+    follow_states: HashMap<String, Option<String>>,
+    // Follow task -> who, and the follow record before the optimistic change
+    follow_tasks: HashMap<TaskId, (Did, Option<String>)>,
+    // Posts to refetch once a like or repost has gone through, batched together
+    pending_refreshes: HashSet<String>,
+    refresh_due: Option<Instant>,
+    refreshed_posts_sender: mpsc::Sender<Vec<PostView>>,
+    refreshed_posts_receiver: mpsc::Receiver<Vec<PostView>>,
+    // Parent author DID -> handle for "replying to" labels
+    reply_handle_sender: mpsc::Sender<(String, String)>,
+    reply_handle_receiver: mpsc::Receiver<(String, String)>,
+    reply_handles: HashMap<String, String>,
+    requested_reply_dids: HashSet<String>,
     last_notification_check: Instant,
+    // False between FocusLost and FocusGained, for terminals that report them
+    focused: bool,
     update_manager: UpdateManager,
     pub post_composer: Option<PostComposer>,
     pub composing: bool,
@@ -39,210 +206,1852 @@ pub struct App {
     pub command_mode: bool,
     pub login_view: Option<LoginView>,
     pub authenticated: bool,
+    pub settings: Settings,
+    // Startup progress, drawn instead of the views until the first load finishes
+    pub splash: Option<Splash>,
+    // Cropped avatar waiting for y/n before it's uploaded
+    pub avatar_preview: Option<AvatarPreview>,
+    // Key and command reference drawn over the current view
+    pub help: Option<InfoView>,
+    // Waiting on y or n before a delete or unfollow
+    pub confirming: Option<(ConfirmDialog, Confirmable)>,
+    // M on a profile: our lists, to add the author to or take them off
+    pub list_picker: Option<ListPicker>,
+    pub toasts: Toasts,
+    // Newest last
+    error_history: VecDeque<ErrorRecord>,
+    // Cache and runtime stats in the top right, toggled with F12
+    pub debug_overlay: bool,
+    // How long the last terminal.draw took
+    last_frame_time: Duration,
+    // First key of a two-key sequence such as `m a`, `' a` or `g g`
+    pending_key: Option<char>,
+    // Digits typed before a motion, as in 5j
+    pending_count: Option<usize>,
+    // Lowercased text of the in-view search started with /, while it's active
+    search: Option<String>,
+    // When and on which item the last left click landed, to spot double clicks
+    last_click: Option<(Instant, usize)>,
+    // Deck mode: the columns beside the focused one (view_stack), left to
+    // right with a gap at deck_focus. The timeline is always column 0. Empty
+    // outside deck mode
+    pub deck: Vec<ViewStack>,
+    pub deck_focus: usize,
+    // The other tabs, in order with a gap at tab_index for the one showing.
+    // Empty until :tabnew
+    tabs: Vec<Tab>,
+    pub tab_index: usize,
+    marks: HashMap<char, String>,
+    // Left behind by a crash in the previous session, until :restore
+    recovery: Option<RecoverySnapshot>,
+    // Where the timeline was at the last clean exit, used by the first load
+    resume_position: Option<TimelinePosition>,
+    // Views open at the last clean exit, reopened after the first load
+    saved_views: Vec<ViewSummary>,
+    author_alerts: AuthorAlerts,
+    alert_notifications: VecDeque<NotificationData>,
+    alert_sender: mpsc::Sender<AlertPollResult>,
+    alert_receiver: mpsc::Receiver<AlertPollResult>,
+    last_alert_check: Option<Instant>,
+    alert_poll_in_flight: bool,
+    // Posts that couldn't be sent for lack of a connection
+    outbox: Outbox,
+    outbox_sender: mpsc::Sender<Vec<(OutboxId, SendOutcome)>>,
+    outbox_receiver: mpsc::Receiver<Vec<(OutboxId, SendOutcome)>>,
+    last_outbox_attempt: Option<Instant>,
+    outbox_send_in_flight: bool,
+    // Background looks at the first timeline page, for the new posts indicator
+    timeline_peek_sender: mpsc::Sender<Vec<FeedViewPost>>,
+    timeline_peek_receiver: mpsc::Receiver<Vec<FeedViewPost>>,
+    last_timeline_peek: Instant,
+    timeline_peek_in_flight: bool,
+    // Threads and profiles opened from the keyboard or mouse arrive here
+    fetched_sender: mpsc::Sender<FetchResult>,
+    fetched_receiver: mpsc::Receiver<FetchResult>,
+    // When to peek early because a followed account just posted
+    realtime_peek_at: Option<Instant>,
+    // Replies and mentions already announced by the mention_alert setting
+    announced_mentions: HashSet<String>,
+    // Set after the first check, so whatever was waiting at startup doesn't go off at once
+    mentions_primed: bool,
+    // The status line is drawn highlighted until then
+    pub status_flash_until: Option<Instant>,
+    // Last word from the realtime stream, None while it isn't running
+    pub realtime_status: Option<ConnectionStatus>,
+    // Something on screen may have changed since the last draw. The event
+    // loop skips drawing while this is false
+    dirty: bool,
 }
 
-impl App {
-    pub fn new(api: API) -> Self {
-        let image_manager = Arc::new(ImageManager::new());
-        let (sender, receiver) = mpsc::channel(10);
+impl<C: BlueskyClient> App<C> {
+    pub fn new(api: C) -> Self {
+        let settings = Settings::load();
+        emoji::configure(&settings.emoji);
+        filters::configure(&settings.filters);
+        let image_manager = Arc::new(ImageManager::new(&settings.image_cache));
+        let height_cache = Arc::new(std::sync::Mutex::new(HeightCache::new()));
+        let recovery = RecoverySnapshot::take();
+        let saved_session = SavedSession::load();
+        let resume_position = saved_session.timeline.filter(|_| settings.resume_timeline_position);
+        // After a crash :restore offers the newer copy instead
+        let saved_views = if recovery.is_some() { Vec::new() } else { saved_session.views };
+        let mut command_input = CommandInput::new();
+        if let Some(snapshot) = &recovery {
+            command_input.command_history = snapshot.command_history.clone();
+        }
+        let (refreshed_posts_sender, refreshed_posts_receiver) = mpsc::channel(10);
+        let (reply_handle_sender, reply_handle_receiver) = mpsc::channel(10);
+        let (alert_sender, alert_receiver) = mpsc::channel(1);
+        let (outbox_sender, outbox_receiver) = mpsc::channel(1);
+        let (timeline_peek_sender, timeline_peek_receiver) = mpsc::channel(1);
+        let (fetched_sender, fetched_receiver) = mpsc::channel(10);
+        let tasks = TaskQueue::new(api.clone());
         Self {
             api,
-            loading: false,
+            task_manager: TaskManager::default(),
             error: None,
-            view_stack: ViewStack::new(Arc::clone(&image_manager)),
+            view_stack: ViewStack::new(Arc::clone(&image_manager), Arc::clone(&height_cache)),
             status_line: "".to_string(),
             image_manager,
-            post_update_sender: sender,
-            post_update_receiver: receiver,
-            notification_check_interval: Duration::from_secs(120),
+            height_cache,
+            tasks,
+            interaction_tasks: HashMap::new(),
+            follow_states: HashMap::new(),
+            follow_tasks: HashMap::new(),
+            pending_refreshes: HashSet::new(),
+            refresh_due: None,
+            refreshed_posts_sender,
+            refreshed_posts_receiver,
+            reply_handle_sender,
+            reply_handle_receiver,
+            reply_handles: HashMap::new(),
+            requested_reply_dids: HashSet::new(),
             last_notification_check: Instant::now(),
+            focused: true,
             update_manager: UpdateManager::new(),
             post_composer: None,
             composing: false,
-            command_input: CommandInput::new(),
+            command_input,
             command_mode: false,
             login_view: None,
             authenticated: false,
+            settings,
+            splash: None,
+            avatar_preview: None,
+            help: None,
+            confirming: None,
+            list_picker: None,
+            toasts: Toasts::default(),
+            error_history: VecDeque::new(),
+            debug_overlay: false,
+            last_frame_time: Duration::ZERO,
+            pending_key: None,
+            pending_count: None,
+            search: None,
+            last_click: None,
+            deck: Vec::new(),
+            deck_focus: 0,
+            tabs: Vec::new(),
+            tab_index: 0,
+            marks: HashMap::new(),
+            recovery,
+            resume_position,
+            saved_views,
+            author_alerts: AuthorAlerts::load(AUTHOR_ALERTS_PATH),
+            alert_notifications: VecDeque::new(),
+            alert_sender,
+            alert_receiver,
+            last_alert_check: None,
+            alert_poll_in_flight: false,
+            outbox: Outbox::load(OUTBOX_PATH),
+            outbox_sender,
+            outbox_receiver,
+            last_outbox_attempt: None,
+            outbox_send_in_flight: false,
+            timeline_peek_sender,
+            timeline_peek_receiver,
+            fetched_sender,
+            fetched_receiver,
+            last_timeline_peek: Instant::now(),
+            timeline_peek_in_flight: false,
+            realtime_peek_at: None,
+            announced_mentions: HashSet::new(),
+            mentions_primed: false,
+            status_flash_until: None,
+            realtime_status: None,
+            dirty: true,
+        }
+    }
+    pub async fn login(&mut self, identifier: String, password: SecretString) -> Result<()> {
+        self.api.login(identifier, password).await
+    }
+
+    pub async fn load_initial_posts(&mut self) {
+        let task = self.task_manager.start("Loading timeline");
+        let resume = self.resume_position.take();
+        if let View::Timeline(feed) = self.view_stack.current_view() {
+            let result = match resume {
+                Some(position) => feed.resume_from(&mut self.api, position.cursor, position.selected_uri.as_deref()).await,
+                None => feed.load_initial_posts(&mut self.api).await,
+            };
+            if let Err(e) = result {
+                self.error = Some(format!("Failed to load timeline: {}", e));
+            }
+        }
+        self.task_manager.finish(task);
+        self.update_status();
+    }
+
+    // Remember the open views, and where the timeline was if that's wanted,
+    // for the next launch
+    fn save_session(&self) {
+        if !self.authenticated {
+            return;
+        }
+        let timeline = match self.main_stack().views.first() {
+            Some(View::Timeline(feed)) if matches!(feed.source, FeedSource::Timeline) && self.settings.resume_timeline_position => {
+                Some(TimelinePosition {
+                    cursor: feed.selected_page_cursor(),
+                    selected_uri: feed.get_selected_post().map(|post| post.uri),
+                })
+            }
+            _ => None,
+        };
+        // The timeline at the bottom is always there
+        let views = self.main_stack().views.iter().skip(1).map(ViewSummary::from_view).collect();
+        let session = SavedSession { timeline, views };
+        if let Err(e) = session.save() {
+            log::warn!("Failed to save session: {}", e);
+        }
+    }
+
+    fn queue_post_refresh(&mut self, uri: String) {
+        self.pending_refreshes.insert(uri);
+        self.refresh_due.get_or_insert_with(|| Instant::now() + REFRESH_DELAY);
+    }
+
+    // Refetch every post queued since the first one waited REFRESH_DELAY,
+    // in as few getPosts calls as possible
+    fn flush_post_refreshes(&mut self) {
+        if self.refresh_due.is_none_or(|due| Instant::now() < due) {
+            return;
+        }
+        self.refresh_due = None;
+
+        let uris: Vec<String> = self.pending_refreshes.drain().collect();
+        let api = self.api.clone();
+        let sender = self.refreshed_posts_sender.clone();
+        tokio::spawn(async move {
+            match api.get_posts(&uris).await {
+                Ok(posts) => {
+                    sender.send(posts).await.ok();
+                }
+                Err(e) => log::info!("Could not refresh {} posts: {}", uris.len(), e),
+            }
+        });
+    }
+
+    // Fill in "replying to @handle" for replies whose parent we only know by DID,
+    // resolving each DID once in the background
+    fn resolve_reply_handles(&mut self) {
+        while let Ok((did, handle)) = self.reply_handle_receiver.try_recv() {
+            self.reply_handles.insert(did, handle);
+            self.dirty = true;
+        }
+
+        let view = self.view_stack.current_view();
+        for did in view.unresolved_reply_dids() {
+            if let Some(handle) = self.reply_handles.get(&did) {
+                view.set_reply_handle(&did, handle);
+                self.dirty = true;
+            } else if self.requested_reply_dids.insert(did.clone()) {
+                let api = self.api.clone();
+                let sender = self.reply_handle_sender.clone();
+                tokio::spawn(async move {
+                    match api.resolve_did_handle(&did).await {
+                        Ok(handle) => {
+                            sender.send((did, handle)).await.ok();
+                        }
+                        Err(e) => log::info!("Could not resolve reply parent {}: {}", did, e),
+                    }
+                });
+            }
+        }
+    }
+
+    fn recovery_snapshot(&self) -> RecoverySnapshot {
+        let draft = self.post_composer
+            .as_ref()
+            .filter(|composer| !composer.content.is_empty());
+        RecoverySnapshot {
+            views: self.main_stack().views.iter().map(ViewSummary::from_view).collect(),
+            draft: draft.map(|composer| composer.content.clone()),
+            draft_reply_to: draft.and_then(|composer| composer.reply_to.clone()),
+            command_history: self.command_input.command_history.clone(),
+            focus: self.view_stack.views.last().map(|view| format!(
+                "{}, item {} of {}, {}",
+                view.short_title(),
+                view.selected_index() + 1,
+                view.item_count(),
+                view.selection_key().unwrap_or_else(|| "nothing selected".to_string()),
+            )).unwrap_or_default(),
+        }
+    }
+
+    async fn restore_session(&mut self) -> Result<()> {
+        let Some(snapshot) = self.recovery.take() else {
+            self.status_line = "Nothing to restore".to_string();
+            return Ok(());
+        };
+
+        self.restore_views(&snapshot.views).await?;
+
+        if let Some(draft) = snapshot.draft {
+            let mut composer = PostComposer::new(snapshot.draft_reply_to);
+            composer.cursor_position = draft.len();
+            composer.content = draft;
+            self.post_composer = Some(composer);
+            self.composing = true;
+        }
+        Ok(())
+    }
+
+    // Reopen the views from the last clean exit on top of the timeline. A view
+    // that no longer loads, such as a deleted thread, is skipped
+    async fn restore_saved_views(&mut self, views: Vec<ViewSummary>) {
+        for summary in views {
+            if let Err(e) = self.restore_views(std::slice::from_ref(&summary)).await {
+                log::info!("Could not reopen {:?}: {}", summary, e);
+            }
+        }
+    }
+
+    async fn restore_views(&mut self, views: &[ViewSummary]) -> Result<()> {
+        for summary in views {
+            match summary {
+                ViewSummary::Timeline { selected_uri: Some(uri) } => {
+                    if let Some(timeline) = self.view_stack.views.first_mut() {
+                        if let Some(index) = timeline.position_of(uri) {
+                            timeline.select_index(index);
+                        }
+                    }
+                }
+                ViewSummary::Timeline { selected_uri: None } => {}
+                ViewSummary::Likes => {
+                    self.open_likes().await?;
+                }
+                ViewSummary::Generator { uri, name } => {
+                    self.switch_feed(FeedSource::Generator { uri: uri.clone(), name: name.clone() }).await?;
+                }
+                ViewSummary::Hashtag { tag } => {
+                    self.open_hashtag(tag).await?;
+                }
+                ViewSummary::Search { query } => {
+                    self.push_feed(FeedSource::Search { query: query.clone() }).await?;
+                }
+                ViewSummary::Trending => {
+                    self.open_trending().await?;
+                }
+                ViewSummary::Thread { anchor_uri } => {
+                    self.view_stack.push_thread_view(anchor_uri.clone(), &self.api).await?;
+                }
+                ViewSummary::PostDetail { uri } => {
+                    let post = self.api.get_post(uri).await?;
+                    self.view_stack.push_post_detail_view(post);
+                }
+                ViewSummary::AuthorFeed { did } => {
+                    let did = Did::new(did.clone()).map_err(|e| anyhow::anyhow!(e))?;
+                    // In order with the views around it, so not in the background
+                    let _ = self.view_stack.push_author_feed_view(AtIdentifier::Did(did), &self.api).await;
+                }
+                ViewSummary::Notifications => {
+                    self.open_notifications().await?;
+                }
+                ViewSummary::ProfileList { source } => {
+                    self.view_stack.push_profile_list_view(source.clone(), &self.api).await?;
+                }
+                // Reports are cheap to regenerate, so they aren't restored
+                ViewSummary::Info { .. } => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn open_notifications(&mut self) -> Result<()> {
+        self.view_stack.push_notifications_view();
+        if let View::Notifications(notifications) = self.view_stack.current_view() {
+            let task = self.task_manager.start("Loading notifications");
+            let result = notifications.load_notifications(&mut self.api).await;
+            self.task_manager.finish(task);
+            result?;
+            notifications.merge_alerts(&self.alert_notifications);
+        }
+        Ok(())
+    }
+
+    // Change what the bottom Timeline view shows and go back down to it
+    async fn switch_feed(&mut self, source: FeedSource) -> Result<()> {
+        while self.view_stack.views.len() > 1 {
+            self.view_stack.pop_view();
+        }
+        if let Some(View::Timeline(feed)) = self.view_stack.views.first_mut() {
+            let task = self.task_manager.start(format!("Loading {}", source.title()));
+            let result = feed.set_source(source, &mut self.api).await;
+            self.task_manager.finish(task);
+            result?;
+            self.status_line = format!("Showing {}", feed.source.title());
+        }
+        Ok(())
+    }
+
+    // :feed following|discover|<saved feed name>, or :feed alone to list them
+    async fn handle_feed_command(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            let saved = self.api.get_saved_feeds().await?;
+            let mut lines = vec![
+                "Feeds the timeline can show (:feed <name>):".to_string(),
+                String::new(),
+                "following — accounts you follow".to_string(),
+                "discover — Bluesky's Discover feed".to_string(),
+            ];
+            lines.extend(saved.into_iter().map(|(name, _)| name));
+            self.view_stack.push_info_view(InfoView::from_strings("📰 Feeds", lines));
+            return Ok(());
+        }
+
+        // Saved feed names can have spaces in them
+        let wanted = args.join(" ");
+        match self.feed_source_named(&wanted).await? {
+            Some(source) => self.switch_feed(source).await,
+            None => {
+                self.status_line = format!("No saved feed named {} (:feed lists them)", wanted);
+                Ok(())
+            }
+        }
+    }
+
+    // following, discover or one of the saved feeds, ignoring case
+    async fn feed_source_named(&self, wanted: &str) -> Result<Option<FeedSource>> {
+        Ok(match wanted.to_lowercase().as_str() {
+            "following" => Some(FeedSource::Timeline),
+            "discover" => Some(FeedSource::discover()),
+            lowered => self.api.get_saved_feeds().await?
+                .into_iter()
+                .find(|(name, _)| name.to_lowercase() == lowered)
+                .map(|(name, uri)| FeedSource::Generator { uri, name }),
+        })
+    }
+
+    // The stack with the timeline at the bottom: the first column of the
+    // first tab, whichever column and tab have focus
+    fn main_stack(&self) -> &ViewStack {
+        match (self.tab_index, self.deck_focus) {
+            (0, 0) => &self.view_stack,
+            (0, _) => &self.deck[0],
+            _ => self.tabs[0].main_stack(),
+        }
+    }
+
+    fn main_stack_mut(&mut self) -> &mut ViewStack {
+        match (self.tab_index, self.deck_focus) {
+            (0, 0) => &mut self.view_stack,
+            (0, _) => &mut self.deck[0],
+            _ => match self.tabs[0].deck_focus {
+                0 => &mut self.tabs[0].stack,
+                _ => &mut self.tabs[0].deck[0],
+            },
+        }
+    }
+
+    // What the debug overlay shows
+    pub fn debug_lines(&self) -> Vec<String> {
+        let realtime = match self.realtime_status {
+            None => "off".to_string(),
+            Some(ConnectionStatus::Connected) => "connected".to_string(),
+            Some(ConnectionStatus::Reconnecting { attempt }) => format!("reconnecting (attempt {})", attempt),
+            Some(ConnectionStatus::Disconnected) => "disconnected".to_string(),
+        };
+        let heights = self.height_cache.lock().unwrap().len();
+        let mut lines = vec![format!("Frame          {:.1} ms", self.last_frame_time.as_secs_f64() * 1000.0)];
+        lines.extend(self.image_manager.stats_lines());
+        lines.push(format!("Post heights   {}", heights));
+        lines.push(format!("API tasks      {}", self.tasks.pending_count()));
+        for label in self.task_manager.labels() {
+            lines.push(format!("Running        {}", label));
+        }
+        lines.push(format!("Realtime       {}", realtime));
+        lines
+    }
+
+    // Labels for the tab bar, named after each tab's top view. None with a single tab
+    pub fn tab_titles(&self) -> Option<Vec<String>> {
+        if self.tabs.is_empty() {
+            return None;
+        }
+        let mut titles: Vec<String> = self.tabs.iter().map(Tab::title).collect();
+        let showing = self.view_stack.views.last().map(View::short_title).unwrap_or_default();
+        titles.insert(self.tab_index, showing);
+        Some(titles)
+    }
+
+    // :tabnew opens a tab on the timeline, next to the current one
+    async fn new_tab(&mut self) {
+        let mut stack = ViewStack::new(Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
+        if let Some(View::Timeline(feed)) = stack.views.first_mut() {
+            let task = self.task_manager.start("Loading timeline");
+            let result = feed.load_initial_posts(&mut self.api).await;
+            self.task_manager.finish(task);
+            if let Err(e) = result {
+                self.error = Some(format!("Failed to load timeline: {}", e));
+                return;
+            }
+        }
+        // Just after the gap, so right of the showing tab
+        self.tabs.insert(self.tab_index, Tab { stack, deck: Vec::new(), deck_focus: 0 });
+        self.select_tab(self.tab_index + 1);
+    }
+
+    // Show another tab, putting the showing one back in its place
+    fn select_tab(&mut self, index: usize) {
+        if index == self.tab_index || index > self.tabs.len() {
+            return;
+        }
+        self.clear_search();
+        let target = self.tabs.remove(index - usize::from(index > self.tab_index));
+        let previous = Tab {
+            stack: std::mem::replace(&mut self.view_stack, target.stack),
+            deck: std::mem::replace(&mut self.deck, target.deck),
+            deck_focus: std::mem::replace(&mut self.deck_focus, target.deck_focus),
+        };
+        self.tabs.insert(self.tab_index - usize::from(index < self.tab_index), previous);
+        self.tab_index = index;
+    }
+
+    // gt / gT, wrapping around
+    fn select_next_tab(&mut self, forward: bool) {
+        let count = self.tabs.len() + 1;
+        let next = match forward {
+            true => (self.tab_index + 1) % count,
+            false => (self.tab_index + count - 1) % count,
+        };
+        self.select_tab(next);
+    }
+
+    // :tabclose shows the tab to the left, or the right for the first tab
+    fn close_tab(&mut self) {
+        if self.tabs.is_empty() {
+            self.status_line = "This is the only tab".to_string();
+            return;
+        }
+        let index = self.tab_index;
+        if index > 0 {
+            self.select_tab(index - 1);
+            self.tabs.remove(index - 1);
+        } else {
+            self.select_tab(1);
+            self.tabs.remove(0);
+            self.tab_index = 0;
+        }
+    }
+
+    // :deck opens the columns from settings (notifications if there are none)
+    // beside the timeline, or closes them again
+    async fn toggle_deck(&mut self) {
+        if !self.deck.is_empty() {
+            self.close_deck();
+            self.status_line = "Deck closed".to_string();
+            return;
+        }
+        let names = match self.settings.deck.is_empty() {
+            true => vec!["notifications".to_string()],
+            false => self.settings.deck.clone(),
+        };
+        for name in names.iter().take(MAX_DECK_COLUMNS - 1) {
+            match self.open_deck_column(name).await {
+                Ok(Some(column)) => self.deck.push(column),
+                Ok(None) => {}
+                Err(e) => self.error = Some(format!("Failed to open {} column: {}", name, e)),
+            }
+        }
+        if !self.deck.is_empty() {
+            self.status_line = "Tab / Shift+Tab move between columns, :deck closes them".to_string();
+        }
+    }
+
+    fn close_deck(&mut self) {
+        self.focus_column(0);
+        self.deck.clear();
+    }
+
+    // A column's views are opened by swapping in an empty stack and using the
+    // usual commands, so each column works like the single view does
+    async fn open_deck_column(&mut self, name: &str) -> Result<Option<ViewStack>> {
+        let column = ViewStack::new(Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
+        let focused = std::mem::replace(&mut self.view_stack, column);
+        let result = match name.to_lowercase().as_str() {
+            "notifications" => self.open_notifications().await,
+            "trending" => self.open_trending().await,
+            "likes" => self.open_likes().await,
+            tag if tag.starts_with('#') => self.open_hashtag(tag).await,
+            _ => match self.feed_source_named(name).await {
+                Ok(Some(source)) => self.push_feed(source).await,
+                Ok(None) => {
+                    self.status_line = format!("No column called {} (try notifications, trending, likes, #tag or a feed)", name);
+                    Ok(())
+                }
+                Err(e) => Err(e),
+            },
+        };
+        let mut column = std::mem::replace(&mut self.view_stack, focused);
+        result?;
+        if column.views.len() < 2 {
+            return Ok(None);
+        }
+        // What was opened becomes the column's bottom view, in place of the empty timeline
+        column.views.remove(0);
+        Ok(Some(column))
+    }
+
+    // Move focus to another deck column, swapping it into view_stack
+    fn focus_column(&mut self, index: usize) {
+        if index == self.deck_focus || index > self.deck.len() {
+            return;
+        }
+        self.clear_search();
+        let target = self.deck.remove(index - usize::from(index > self.deck_focus));
+        let previous = std::mem::replace(&mut self.view_stack, target);
+        self.deck.insert(self.deck_focus - usize::from(index < self.deck_focus), previous);
+        self.deck_focus = index;
+    }
+
+    fn focus_next_column(&mut self, forward: bool) {
+        let columns = self.deck.len() + 1;
+        if columns == 1 {
+            return;
+        }
+        let next = match forward {
+            true => (self.deck_focus + 1) % columns,
+            false => (self.deck_focus + columns - 1) % columns,
+        };
+        self.focus_column(next);
+    }
+
+    async fn open_hashtag(&mut self, tag: &str) -> Result<()> {
+        let tag = tag.trim_start_matches(['#', '＃']);
+        if tag.is_empty() {
+            self.status_line = "Usage: :tag <hashtag>".to_string();
+            return Ok(());
+        }
+        self.push_feed(FeedSource::Hashtag { tag: tag.to_string() }).await
+    }
+
+    async fn push_feed(&mut self, source: FeedSource) -> Result<()> {
+        let task = self.task_manager.start(format!("Loading {}", source.title()));
+        let result = self.view_stack.push_feed_view(source, &mut self.api).await;
+        self.task_manager.finish(task);
+        result
+    }
+
+    async fn open_trending(&mut self) -> Result<()> {
+        let task = self.task_manager.start("Loading trending topics");
+        let result = self.view_stack.push_trending_view(&self.api).await;
+        self.task_manager.finish(task);
+        result
+    }
+
+    // Show what the selected trending topic points at: posts with its
+    // hashtag, a search for it, or its feed in the timeline view
+    async fn open_selected_trend(&mut self) -> Result<()> {
+        let topic = match self.view_stack.current_view() {
+            View::Trending(trending) => trending.get_selected_topic().cloned(),
+            _ => None,
+        };
+        let Some(topic) = topic else {
+            return Ok(());
+        };
+        match topic.target() {
+            TrendTarget::Hashtag(tag) => self.open_hashtag(&tag).await,
+            TrendTarget::Search(query) => self.push_feed(FeedSource::Search { query }).await,
+            TrendTarget::Feed { actor, rkey } => {
+                let did = self.api.resolve_actor(&actor).await?;
+                let uri = format!("at://{}/app.bsky.feed.generator/{}", did.as_str(), rkey);
+                self.switch_feed(FeedSource::Generator { uri, name: topic.name().to_string() }).await
+            }
+        }
+    }
+
+    // `#` opens the first hashtag in the selected post; the rest are named so
+    // they can be reached with :tag
+    async fn open_selected_hashtag(&mut self) {
+        let Some(post) = self.view_stack.current_view().get_selected_post() else {
+            return;
+        };
+        let text = PostListBase::get_post_text(&post.into()).unwrap_or_default();
+        let tags = super::components::post::Post::hashtags(&text);
+        let Some(first) = tags.first() else {
+            self.status_line = "No hashtags in this post".to_string();
+            return;
+        };
+        if let Err(e) = self.open_hashtag(first).await {
+            self.error = Some(format!("Failed to load #{}: {}", first, e));
+            return;
+        }
+        if tags.len() > 1 {
+            let others: Vec<String> = tags[1..].iter().map(|tag| format!("#{}", tag)).collect();
+            self.status_line = format!("Also tagged {} (open with :tag)", others.join(" "));
+        }
+    }
+
+    async fn open_likes(&mut self) -> Result<()> {
+        let Some(session) = self.api.session().await else {
+            self.status_line = "Log in to see your likes".to_string();
+            return Ok(());
+        };
+        let actor = AtIdentifier::Did(session.did.clone());
+        let task = self.task_manager.start("Loading likes");
+        let result = self.view_stack.push_feed_view(FeedSource::Likes { actor }, &mut self.api).await;
+        self.task_manager.finish(task);
+        result
+    }
+
+    // Accounts related to the selected post, e.g. its likers
+    async fn open_profile_list(&mut self, source_for: fn(String) -> ProfileListSource) {
+        let Some(post) = self.view_stack.current_view().get_selected_post() else {
+            return;
+        };
+        let source = source_for(post.uri.clone());
+        let task = self.task_manager.start("Loading accounts");
+        if let Err(e) = self.view_stack.push_profile_list_view(source, &self.api).await {
+            self.error = Some(format!("Failed to load accounts: {}", e));
+        }
+        self.task_manager.finish(task);
+    }
+
+    async fn handle_like_post(&mut self) {
+        self.toggle_interaction(Interaction::Like).await;
+    }
+
+    async fn handle_repost(&mut self) {
+        self.toggle_interaction(Interaction::Repost).await;
+    }
+
+    // Flip the like or repost on the selected post straight away and queue the
+    // request. The post is refetched once it succeeds and put back the way it
+    // was if it fails or is cancelled
+    async fn toggle_interaction(&mut self, interaction: Interaction) {
+        let Some(post) = self.view_stack.current_view().get_selected_post() else {
+            log::info!("couldnt get selected post for {}", interaction.verb());
+            return;
+        };
+        let Some(viewer) = post.viewer.as_ref() else {
+            return;
+        };
+        let record = interaction.record(&viewer.data).cloned();
+        if record.as_deref() == Some(PENDING_RECORD) {
+            self.status_line = "Still saving the last change to this post".to_string();
+            return;
+        }
+
+        let mut optimistic = post.clone();
+        interaction.apply(&mut optimistic, if record.is_some() { None } else { Some(PENDING_RECORD.to_string()) });
+        self.update_post_everywhere(optimistic.clone().into());
+
+        let mutation = match (interaction, record.is_some()) {
+            (Interaction::Like, true) => Mutation::Unlike { post: post.clone() },
+            (Interaction::Like, false) => Mutation::Like { uri: post.uri.clone(), cid: post.cid.clone() },
+            (Interaction::Repost, true) => Mutation::Unrepost { post: post.clone() },
+            (Interaction::Repost, false) => Mutation::Repost { uri: post.uri.clone(), cid: post.cid.clone() },
+        };
+        let id = self.tasks.enqueue(mutation);
+        self.interaction_tasks.insert(id, (interaction, post, optimistic));
+    }
+
+    // Results from the task queue: confirm or roll back optimistic changes
+    fn apply_task_events(&mut self) {
+        while let Some(event) = self.tasks.try_recv() {
+            self.dirty = true;
+            let interaction = self.interaction_tasks.remove(&event.id);
+            let follow = self.follow_tasks.remove(&event.id);
+            match (event.outcome, interaction) {
+                (TaskOutcome::Done(record), Some((interaction, _, mut optimistic))) => {
+                    interaction.apply_record(&mut optimistic, record);
+                    self.queue_post_refresh(optimistic.uri.clone());
+                    self.update_post_everywhere(optimistic.into());
+                }
+                // Follows are the only tasks without a post to update
+                (TaskOutcome::Done(record), None) => {
+                    self.status_line = if record.is_some() { "Followed" } else { "Unfollowed" }.to_string();
+                    if let Some((did, _)) = follow {
+                        self.set_following(&did, record);
+                    }
+                }
+                (TaskOutcome::Failed(e), interaction) => {
+                    if let Some((_, original, _)) = interaction {
+                        self.update_post_everywhere(original.into());
+                    }
+                    if let Some((did, previous)) = follow {
+                        self.set_following(&did, previous);
+                    }
+                    self.error = Some(format!("Failed to {}: {}", event.description, e));
+                }
+                (TaskOutcome::Cancelled, interaction) => {
+                    if let Some((_, original, _)) = interaction {
+                        self.update_post_everywhere(original.into());
+                    }
+                    if let Some((did, previous)) = follow {
+                        self.set_following(&did, previous);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_yank(&mut self, with_attribution: bool) {
+        let Some(post) = self.view_stack.current_view().get_selected_post() else {
+            return;
+        };
+        let text = PostListBase::get_post_text(&post.clone().into()).unwrap_or_default();
+        let text = if with_attribution {
+            let posted_at: &chrono::DateTime<chrono::FixedOffset> = post.indexed_at.as_ref();
+            format!(
+                "{}\n— @{}, {}",
+                text,
+                post.author.handle.as_str(),
+                posted_at.with_timezone(&chrono::Local).format("%Y-%m-%d %-I:%M %p")
+            )
+        } else {
+            text
+        };
+
+        match copy_to_clipboard(&text) {
+            Ok(()) => self.toasts.push(Severity::Success, "Copied post to clipboard"),
+            Err(e) => self.error = Some(format!("Failed to copy to clipboard: {}", e)),
+        }
+    }
+
+    fn set_mark(&mut self, mark: char) {
+        if let Some(post) = self.view_stack.current_view().get_selected_post() {
+            self.marks.insert(mark, post.uri.to_string());
+            self.status_line = format!("Marked post as '{}'", mark);
+        }
+    }
+
+    async fn jump_to_mark(&mut self, mark: char) {
+        let Some(uri) = self.marks.get(&mark).cloned() else {
+            self.error = Some(format!("Mark '{}' is not set", mark));
+            return;
+        };
+
+        // The marked post may have been paged past, so keep loading older
+        // timeline pages for a while before giving up
+        let mut pages_loaded = 0;
+        loop {
+            if let Some(index) = self.view_stack.current_view().position_of(&uri) {
+                self.view_stack.current_view().select_index(index);
+                return;
+            }
+            match self.view_stack.current_view() {
+                View::Timeline(feed) if pages_loaded < MAX_MARK_SEARCH_PAGES => {
+                    let task = self.task_manager.start("Looking for the mark");
+                    feed.scroll(&self.api).await;
+                    self.task_manager.finish(task);
+                    pages_loaded += 1;
+                }
+                _ => break,
+            }
+        }
+
+        self.error = Some(format!("Mark '{}' is not in this view", mark));
+    }
+
+    // Jump to the last post, first pulling in a few more pages so the end of
+    // what happens to be loaded isn't mistaken for the end of the feed
+    // Move the selection down, fetching the next page when it gets near the
+    // end of what's loaded
+    async fn scroll_down_by(&mut self, steps: usize) {
+        self.view_stack.current_view().scroll_down_by(steps);
+        match self.view_stack.current_view() {
+            View::Timeline(feed) if feed.needs_more_content() => {
+                let task = self.task_manager.start("Loading more posts");
+                feed.scroll(&self.api).await;
+                self.task_manager.finish(task);
+            }
+            View::ProfileList(profile_list) if profile_list.needs_more_content() => {
+                let task = self.task_manager.start("Loading more accounts");
+                if let Err(e) = profile_list.load_more(&self.api).await {
+                    self.error = Some(format!("Failed to load more: {}", e));
+                }
+                self.task_manager.finish(task);
+            }
+            _ => {}
+        }
+    }
+
+    async fn jump_to_end(&mut self) {
+        let max_pages = self.settings.auto_pagination_pages;
+        if let View::Timeline(feed) = self.view_stack.current_view() {
+            let task = self.task_manager.start("Loading to the end");
+            for _ in 0..max_pages {
+                if feed.cursor.is_none() {
+                    break;
+                }
+                feed.scroll(&self.api).await;
+            }
+            self.task_manager.finish(task);
+        }
+
+        let view = self.view_stack.current_view();
+        let last_index = view.item_count().saturating_sub(1);
+        view.select_index(last_index);
+    }
+
+    fn handle_get_profile(&mut self, handle: AtIdentifier) {
+        self.open_author_feed_in_background(handle, "profile");
+    }
+    
+    pub async fn refresh_current_view(&mut self) -> Result<()> {
+        let task = self.task_manager.start("Refreshing");
+        let result = self.reload_current_view().await;
+        self.task_manager.finish(task);
+        result
+    }
+
+    async fn reload_current_view(&mut self) -> Result<()> {
+        let position = self.view_stack.current_view().position();
+        
+        match self.view_stack.current_view() {
+            View::Timeline(feed) => {
+                feed.reload_feed(&mut self.api).await?;
+            }
+            View::Thread(thread) => {
+                let params = atrium_api::app::bsky::feed::get_post_thread::ParametersData {
+                    uri: thread.anchor_uri.clone(),
+                    depth: Some(atrium_api::types::LimitedU16::MAX),
+                    parent_height: Some(atrium_api::types::LimitedU16::MAX),
+                };
+                
+                if let Ok(response) = self.api.get_post_thread(params).await {
+                    if let atrium_api::types::Union::Refs(thread_refs) = response.data.thread {
+                        thread.reload(thread_refs);
+                    }
+                }
+            }
+            View::AuthorFeed(author_feed) => {
+                let actor = AtIdentifier::Did(author_feed.profile.profile.did.clone());
+                let params = atrium_api::app::bsky::feed::get_author_feed::ParametersData {
+                    actor: actor.clone(),
+                    cursor: None,
+                    filter: None,
+                    include_pins: Some(true),
+                    limit: None,
+                };
+    
+                if let Ok(response) = self.api.get_author_feed(params).await {
+                    author_feed.posts.clear();
+                    author_feed.rendered_posts.clear();
+                    author_feed.reasons.clear();
+                    author_feed.filters.reset_counts();
+                    for feed_post in &response.feed {
+                        author_feed.add_feed_post(feed_post);
+                    }
+                }
+            }
+            View::Notifications(notifications) => {
+                notifications.load_notifications(&mut self.api).await?;
+                notifications.merge_alerts(&self.alert_notifications);
+            }
+            View::ProfileList(profile_list) => {
+                profile_list.load_initial(&self.api).await?;
+            }
+            View::Trending(trending) => {
+                trending.load(&self.api).await?;
+            }
+            View::PostDetail(detail) => {
+                let post = self.api.get_post(&detail.post.data.uri).await?;
+                detail.set_post(post);
+            }
+            View::Info(_) => {}
+        }
+        self.view_stack.current_view().restore_position(position);
+        Ok(())
+    }
+
+    async fn check_notifications(&mut self) {
+        let interval = Duration::from_secs(self.settings.notification_interval_secs) * self.slowdown();
+        if self.last_notification_check.elapsed() >= interval {
+            self.dirty = true;
+            let latest = if let View::Notifications(notifications) = self.view_stack.current_view() {
+                notifications.load_notifications(&mut self.api).await.ok();
+                notifications.merge_alerts(&self.alert_notifications);
+                notifications.notifications.iter().cloned().collect()
+            } else {
+                self.fetch_recent_notifications().await
+            };
+            self.announce_mentions(&latest);
+            self.last_notification_check = Instant::now();
+        }
+    }
+
+    // Outside the notifications view we only look when mention_alert needs to
+    async fn fetch_recent_notifications(&self) -> Vec<NotificationData> {
+        if self.settings.mention_alert == MentionAlert::Off || !self.authenticated || self.api.is_offline() {
+            return Vec::new();
+        }
+        let params = atrium_api::app::bsky::notification::list_notifications::ParametersData {
+            cursor: None,
+            limit: 25u8.try_into().ok(),
+            seen_at: None,
+            priority: None,
+        };
+        match self.api.list_notifications(params).await {
+            Ok(output) => output.notifications.iter().map(|notification| notification.data.clone()).collect(),
+            Err(e) => {
+                log::info!("Notification check failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    // Ring the bell and/or flash the status line for unread replies and
+    // mentions we haven't announced yet
+    fn announce_mentions(&mut self, notifications: &[NotificationData]) {
+        let fresh: Vec<&NotificationData> = notifications
+            .iter()
+            .filter(|notification| matches!(notification.reason.as_str(), "reply" | "mention") && !notification.is_read)
+            .filter(|notification| self.announced_mentions.insert(notification.uri.clone()))
+            .collect();
+        let primed = std::mem::replace(&mut self.mentions_primed, true);
+        let alert = self.settings.mention_alert;
+        if fresh.is_empty() || !primed || alert == MentionAlert::Off {
+            return;
+        }
+
+        if alert.rings() {
+            let mut stdout = io::stdout();
+            if let Err(e) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+                log::warn!("Failed to ring the bell: {}", e);
+            }
+        }
+        if alert.flashes() {
+            self.status_flash_until = Some(Instant::now() + MENTION_FLASH);
+        }
+        self.status_line = match fresh.as_slice() {
+            [notification] => format!(
+                "💬 @{} {}",
+                notification.author.handle.as_str(),
+                if notification.reason == "reply" { "replied to you" } else { "mentioned you" },
+            ),
+            many => format!("💬 {} new replies and mentions", many.len()),
+        };
+    }
+
+    // Auto-refresh, when it's on, replaces the plain peek's interval
+    fn timeline_peek_interval(&self) -> Duration {
+        let interval = match self.settings.timeline_refresh_interval_secs {
+            0 => TIMELINE_PEEK_INTERVAL,
+            secs => Duration::from_secs(secs),
+        };
+        interval * self.slowdown()
+    }
+
+    fn slowdown(&self) -> u32 {
+        if self.focused { 1 } else { UNFOCUSED_SLOWDOWN }
+    }
+
+    // A tick of 0 would spin
+    fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.settings.tick_rate_ms.max(10)) * self.slowdown()
+    }
+
+    // Fetch the first timeline page in the background every so often to see
+    // whether anything newer than what's loaded has been posted
+    fn peek_timeline(&mut self) {
+        let realtime_due = self.realtime_peek_at.is_some_and(|at| Instant::now() >= at)
+            && self.last_timeline_peek.elapsed() >= REALTIME_PEEK_MIN_GAP;
+        if self.timeline_peek_in_flight
+            || (!realtime_due && self.last_timeline_peek.elapsed() < self.timeline_peek_interval())
+            || !self.authenticated
+            || self.api.is_offline()
+        {
+            return;
+        }
+        self.last_timeline_peek = Instant::now();
+        self.realtime_peek_at = None;
+        if !matches!(self.main_stack().views.first(), Some(View::Timeline(feed)) if matches!(feed.source, FeedSource::Timeline)) {
+            return;
+        }
+
+        self.timeline_peek_in_flight = true;
+        let api = self.api.clone();
+        let sender = self.timeline_peek_sender.clone();
+        tokio::spawn(async move {
+            match api.get_timeline(None).await {
+                Ok((posts, _)) => {
+                    sender.send(posts).await.ok();
+                }
+                Err(e) => {
+                    log::info!("Timeline peek failed: {}", e);
+                    sender.send(Vec::new()).await.ok();
+                }
+            }
+        });
+    }
+
+    fn apply_timeline_peek(&mut self, posts: Vec<FeedViewPost>) {
+        self.timeline_peek_in_flight = false;
+        if posts.is_empty() {
+            return;
+        }
+        let auto_refresh = self.settings.timeline_refresh_interval_secs > 0;
+        if let Some(View::Timeline(feed)) = self.main_stack_mut().views.first_mut() {
+            // The timeline may have been switched to another feed since the peek went out
+            if !matches!(feed.source, FeedSource::Timeline) {
+                return;
+            }
+            feed.set_peeked_page(posts);
+            if auto_refresh {
+                feed.load_new_posts();
+            }
         }
     }
-    pub async fn login(&mut self, identifier: String, password: SecretString) -> Result<()> {
-        self.api.login(identifier, password).await
+
+    // Take a post deleted elsewhere out of every open view, keeping each one's
+    // selection on the same post, or its neighbour if that was the one deleted
+    fn remove_deleted_post(&mut self, uri: &str) {
+        let columns = std::iter::once(&mut self.view_stack)
+            .chain(self.deck.iter_mut())
+            .chain(self.tabs.iter_mut().flat_map(|tab| std::iter::once(&mut tab.stack).chain(tab.deck.iter_mut())));
+        for view in columns.flat_map(|column| column.views.iter_mut()) {
+            if !view.get_all_post_uris().iter().any(|shown| shown == uri) {
+                continue;
+            }
+            let position = view.position();
+            view.remove_post(uri);
+            view.restore_position(position);
+        }
     }
 
-    pub async fn load_initial_posts(&mut self) {
-        self.loading = true;
-        self.update_status();
-        if let View::Timeline(feed) = self.view_stack.current_view() {
-            feed.load_initial_posts(&mut self.api).await.unwrap();
+    fn load_new_posts(&mut self) {
+        let View::Timeline(feed) = self.view_stack.current_view() else {
+            return;
+        };
+        self.status_line = match feed.load_new_posts() {
+            0 => "No new posts".to_string(),
+            1 => "Loaded 1 new post above".to_string(),
+            added => format!("Loaded {} new posts above", added),
+        };
+    }
+
+    // Poll alerted authors in the background; results come back through alert_receiver
+    fn poll_author_alerts(&mut self) {
+        let interval = Duration::from_secs(self.settings.author_alert_interval_secs);
+        let due = self.last_alert_check.is_none_or(|last| last.elapsed() >= interval);
+        if !due || self.alert_poll_in_flight || self.author_alerts.is_empty() {
+            return;
+        }
+
+        self.alert_poll_in_flight = true;
+        self.last_alert_check = Some(Instant::now());
+        let api = self.api.clone();
+        let sender = self.alert_sender.clone();
+        let targets = self.author_alerts.poll_targets();
+        tokio::spawn(async move {
+            let mut results = Vec::new();
+            for (did, latest_seen) in targets {
+                match AuthorAlerts::fetch_new_posts(&api, &did, latest_seen.as_deref()).await {
+                    Ok((newest, posts)) => results.push((did, newest, posts)),
+                    Err(e) => log::info!("Author alert poll failed for {}: {}", did, e),
+                }
+            }
+            sender.send(results).await.ok();
+        });
+    }
+
+    fn apply_author_alerts(&mut self, results: AlertPollResult) {
+        self.alert_poll_in_flight = false;
+        let mut new_alerts = Vec::new();
+        for (did, newest, posts) in results {
+            if let Some(uri) = newest {
+                self.author_alerts.mark_seen(&did, uri);
+            }
+            new_alerts.extend(posts);
+        }
+        if let Err(e) = self.author_alerts.save() {
+            log::warn!("Failed to save author alerts: {}", e);
+        }
+        if new_alerts.is_empty() {
+            return;
+        }
+
+        new_alerts.sort_by(|a, b| b.indexed_at.as_ref().cmp(a.indexed_at.as_ref()));
+        for alert in new_alerts.into_iter().rev() {
+            self.alert_notifications.push_front(alert);
+        }
+        self.alert_notifications.truncate(MAX_AUTHOR_ALERTS);
+
+        if let View::Notifications(notifications) = self.view_stack.current_view() {
+            notifications.merge_alerts(&self.alert_notifications);
+        }
+    }
+
+    fn save_outbox(&self) {
+        if let Err(e) = self.outbox.save() {
+            log::warn!("Failed to save outbox: {}", e);
         }
-        self.loading = false;
-        self.update_status();
     }
 
-    async fn spawn_get_post_task(&self, delay: u64, update_uri: String) {
+    // Try the outbox again every so often, oldest post first, stopping at the
+    // first one that still can't get through. Results come back through outbox_receiver
+    async fn retry_outbox(&mut self) {
+        let due = self.last_outbox_attempt.is_none_or(|last| last.elapsed() >= OUTBOX_RETRY_INTERVAL);
+        if !due || self.outbox_send_in_flight || self.outbox.is_empty() {
+            return;
+        }
+        self.last_outbox_attempt = Some(Instant::now());
+        // Started offline, there's no session to post with until the next login
+        if self.api.session().await.is_none() {
+            return;
+        }
+
+        // Leave alone the one being edited, and ones the server already turned down
+        let editing = self.post_composer.as_ref().and_then(|composer| composer.outbox_id);
+        let entries: Vec<OutboxEntry> = self.outbox
+            .entries()
+            .iter()
+            .filter(|entry| entry.is_waiting() && Some(entry.id) != editing)
+            .cloned()
+            .collect();
+        if entries.is_empty() {
+            return;
+        }
+
+        self.outbox_send_in_flight = true;
         let api = self.api.clone();
-                let sender = self.post_update_sender.clone();
-                
-                tokio::spawn(async move {
-                    tokio::time::sleep(Duration::from_millis(delay)).await;
-                    if let Ok(updated_post) = api.get_post(&update_uri).await {
-                        sender.send(updated_post).await.ok();
-                    }
-                });
+        let sender = self.outbox_sender.clone();
+        tokio::spawn(async move {
+            let mut results = Vec::new();
+            for entry in entries {
+                let outcome = entry.send(&api).await;
+                let offline = matches!(outcome, SendOutcome::Offline);
+                results.push((entry.id, outcome));
+                if offline {
+                    break;
+                }
+            }
+            sender.send(results).await.ok();
+        });
     }
 
-    async fn handle_like_post(&mut self) {
-        if let Some(post) = self.view_stack.current_view().get_selected_post() {
-            let uri = post.uri.as_str();
-            if post.viewer
-                .as_ref()
-                .and_then(|v| v.data.like.as_ref())
-                .is_some() {
-                let _ = self.api.unlike_post(&post).await;
-            } else {
-                let cid = &post.cid;
-                let _ = self.api.like_post(uri, cid).await;
+    fn apply_outbox_results(&mut self, results: Vec<(OutboxId, SendOutcome)>) {
+        self.outbox_send_in_flight = false;
+        let mut sent = 0;
+        for (id, outcome) in results {
+            match outcome {
+                SendOutcome::Sent => {
+                    self.outbox.remove(id);
+                    sent += 1;
+                }
+                SendOutcome::Offline => {}
+                SendOutcome::Rejected(e) => {
+                    self.error = Some(format!("A queued post was rejected, see :outbox: {}", e));
+                    self.outbox.set_error(id, e);
+                }
             }
-            
-            self.spawn_get_post_task(200, uri.to_string()).await;
+        }
+        self.save_outbox();
+        if sent > 0 {
+            self.status_line = format!("📤 Sent {} posts from the outbox", sent);
         }
     }
 
-    async fn handle_repost(&mut self) {
-        if let Some(post) = self.view_stack.current_view().get_selected_post() {
-            let uri = post.uri.as_str();
-            if post.viewer
-                .as_ref()
-                .and_then(|v| v.data.repost.as_ref())
-                .is_some() {
-                let _ = self.api.unrepost(&post).await;
+    // :filter <kind> on|off changes a filter for the timeline until the next launch
+    async fn handle_filter_command(&mut self, args: &[&str]) -> Result<()> {
+        let show = match args.get(1).map(|arg| arg.to_lowercase()).as_deref() {
+            Some("on") => true,
+            Some("off") => false,
+            _ => {
+                self.status_line = "Usage: :filter reposts|replies on|off".to_string();
+                return Ok(());
+            }
+        };
+        let kind = args[0].to_lowercase();
+        if kind != "reposts" && kind != "replies" {
+            self.status_line = format!("Unknown filter: {} (try reposts or replies)", kind);
+            return Ok(());
+        }
+
+        let Some(timeline) = self.view_stack.views.first_mut() else {
+            return Ok(());
+        };
+        let position = timeline.position();
+        if let View::Timeline(feed) = timeline {
+            if kind == "reposts" {
+                feed.filters.set_hide_reposts(!show);
             } else {
-                let cid = &post.cid;
-                let _ = self.api.repost(uri, cid).await;
+                feed.filters.set_hide_replies(!show);
             }
-            
-            self.spawn_get_post_task(200, uri.to_string()).await;
-        } else {
-            log::info!("couldnt get selected post for repost");
+            self.status_line = if show {
+                // The hidden ones were never kept, so fetch them again
+                feed.reload_feed(&mut self.api).await?;
+                format!("Showing {} in the timeline", kind)
+            } else {
+                format!("Hiding {} in the timeline, {} removed", kind, feed.reapply_filters())
+            };
         }
+        timeline.restore_position(position);
+        Ok(())
     }
 
-    async fn handle_get_profile(&mut self, handle: AtIdentifier) {
-        let _ = self.view_stack.push_author_feed_view(handle, &self.api).await;
-    }
-    
-    pub async fn refresh_current_view(&mut self) -> Result<()> {
-        self.loading = true;
-        
-        match self.view_stack.current_view() {
-            View::Timeline(feed) => {
-                feed.reload_feed(&mut self.api).await?;
-            }
-            View::Thread(thread) => {
-                let params = atrium_api::app::bsky::feed::get_post_thread::Parameters {
-                    data: atrium_api::app::bsky::feed::get_post_thread::ParametersData {
-                        uri: thread.anchor_uri.clone().into(),
-                        depth: Some(atrium_api::types::LimitedU16::MAX),
-                        parent_height: Some(atrium_api::types::LimitedU16::MAX),
-                    },
-                    extra_data: ipld_core::ipld::Ipld::Null,
-                };
-                
-                if let Ok(response) = self.api.agent.api.app.bsky.feed.get_post_thread(params).await {
-                    if let atrium_api::types::Union::Refs(thread_refs) = response.data.thread {
-                        thread.posts.clear();
-                        thread.rendered_posts.clear();
-                        let _ = thread.process_thread_data(thread_refs);
+    fn handle_outbox_command(&mut self, args: &[&str]) {
+        let number = args.get(1).and_then(|arg| arg.parse::<usize>().ok());
+        match (args.first().copied(), number) {
+            (None, _) => {
+                let mut lines = vec![
+                    "Posts waiting for a connection (:outbox edit N, :outbox cancel N, :outbox retry):".to_string(),
+                    String::new(),
+                ];
+                if self.outbox.is_empty() {
+                    lines.push("Nothing queued".to_string());
+                }
+                for (index, entry) in self.outbox.entries().iter().enumerate() {
+                    let queued_at = chrono::DateTime::from_timestamp(entry.queued_at, 0)
+                        .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %-I:%M %p").to_string())
+                        .unwrap_or_default();
+                    lines.push(format!(
+                        "{}. {}{} (queued {})",
+                        index + 1,
+                        if entry.reply_to.is_some() { "↪ " } else { "" },
+                        entry.text.replace('\n', " "),
+                        queued_at,
+                    ));
+                    if let Some(error) = &entry.last_error {
+                        lines.push(format!("   ⚠ Rejected, edit to try again: {}", error));
                     }
                 }
+                self.view_stack.push_info_view(InfoView::from_strings("📤 Outbox", lines));
             }
-            View::AuthorFeed(author_feed) => {
-                let actor = AtIdentifier::Did(author_feed.profile.profile.did.clone());
-                let params = atrium_api::app::bsky::feed::get_author_feed::Parameters {
-                    data: atrium_api::app::bsky::feed::get_author_feed::ParametersData {
-                        actor: actor.clone(),
-                        cursor: None,
-                        filter: None,
-                        include_pins: None,
-                        limit: None,
+            (Some("edit"), Some(number)) => match self.outbox.get_numbered(number) {
+                Some(entry) => {
+                    let mut composer = PostComposer::new(entry.reply_to.clone());
+                    composer.cursor_position = entry.text.len();
+                    composer.content = entry.text.clone();
+                    composer.outbox_id = Some(entry.id);
+                    self.post_composer = Some(composer);
+                    self.composing = true;
+                }
+                None => self.status_line = format!("No post {} in the outbox", number),
+            },
+            (Some("cancel"), Some(number)) => match self.outbox.get_numbered(number).map(|entry| entry.id) {
+                Some(id) => {
+                    self.outbox.remove(id);
+                    self.save_outbox();
+                    self.status_line = format!("Removed post {} from the outbox", number);
+                }
+                None => self.status_line = format!("No post {} in the outbox", number),
+            },
+            (Some("retry"), _) => {
+                self.last_outbox_attempt = None;
+                self.status_line = "Retrying the outbox".to_string();
+            }
+            _ => self.status_line = "Usage: :outbox [edit N | cancel N | retry]".to_string(),
+        }
+    }
+
+    async fn submit_post(&mut self) {
+        let Some(composer) = &self.post_composer else {
+            return;
+        };
+        let content = composer.get_content().to_string();
+        let reply_to = composer.reply_to.clone();
+        let outbox_id = composer.outbox_id;
+
+        if outbox_id.is_some_and(|id| self.outbox.get(id).is_none()) {
+            // A retry got the old version out while it was being edited
+            self.error = Some("That post was already sent from the outbox, the edit wasn't posted".to_string());
+            self.composing = false;
+            self.post_composer = None;
+            return;
+        }
+
+        // Started offline, so there's no session to post with yet
+        if self.api.is_offline() && self.api.session().await.is_none() {
+            self.queue_in_outbox(outbox_id, content, reply_to, new_tid());
+            return;
+        }
+
+        // A queued post keeps the record key it was first sent with
+        let rkey = outbox_id
+            .and_then(|id| self.outbox.get(id))
+            .map_or_else(new_tid, |entry| entry.rkey.clone());
+        let task = self.task_manager.start("Posting");
+        let result = self.api.create_post(content.clone(), reply_to.clone(), &rkey).await;
+        self.task_manager.finish(task);
+        match result {
+            Ok(()) => {
+                if let Some(id) = outbox_id {
+                    self.outbox.remove(id);
+                    self.save_outbox();
+                }
+                self.toasts.push(Severity::Success, "Post created");
+                self.composing = false;
+                self.post_composer = None;
+                
+                // Refresh view based on context
+                match self.view_stack.current_view() {
+                    View::Timeline(feed) => {
+                        feed.load_initial_posts(&mut self.api).await.ok();
+                    },
+                    View::Thread(thread) => {
+                        let anchor_uri = thread.anchor_uri.clone();
+                        self.view_stack.push_thread_view(anchor_uri, &self.api).await.ok();
                     },
-                    extra_data: ipld_core::ipld::Ipld::Null,
+                    _ => {}
+                }
+            },
+            Err(e) if is_connectivity_error(&e) => self.queue_in_outbox(outbox_id, content, reply_to, rkey),
+            Err(e) => {
+                self.error = Some(format!("Failed to create post: {}", e));
+            }
+        }
+    }
+
+    fn queue_in_outbox(&mut self, outbox_id: Option<OutboxId>, content: String, reply_to: Option<String>, rkey: String) {
+        match outbox_id {
+            Some(id) => {
+                self.outbox.update(id, content);
+            }
+            None => {
+                self.outbox.push(content, reply_to, rkey);
+            }
+        }
+        self.save_outbox();
+        self.composing = false;
+        self.post_composer = None;
+        self.status_line = "📤 Couldn't reach Bluesky, the post is in the outbox and will be sent once the connection is back".to_string();
+    }
+
+    // The at:// URI of a list given as that or a bsky.app link, None if it's neither
+    async fn resolve_list(&self, target: &str) -> Result<Option<String>> {
+        match BskyLink::parse(target) {
+            Some(BskyLink::List { actor, rkey }) => {
+                let did = self.api.resolve_actor(&actor).await?;
+                Ok(Some(list_uri(did.as_str(), &rkey)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // :open on a list. There's no view of its members, just what it is and
+    // whether we're subscribed
+    async fn show_list(&mut self, uri: &str) -> Result<()> {
+        let list = self.api.get_list(uri).await?;
+        let kind = if list.purpose == MODERATION_LIST { "Moderation list" } else { "Curated list" };
+        let mut lines = vec![
+            format!("{} by @{}", kind, list.creator.handle.as_str()),
+            format!("{} members", list.list_item_count.unwrap_or(0)),
+            String::new(),
+        ];
+        if let Some(description) = &list.description {
+            lines.extend(description.lines().map(str::to_string));
+            lines.push(String::new());
+        }
+        let viewer = list.viewer.as_ref();
+        if viewer.is_some_and(|viewer| viewer.muted == Some(true)) {
+            lines.push("🔇 You mute everyone on this list".to_string());
+        }
+        if viewer.is_some_and(|viewer| viewer.blocked.is_some()) {
+            lines.push("⛔ You block everyone on this list".to_string());
+        }
+        if list.purpose == MODERATION_LIST && !viewer.is_some_and(|viewer| viewer.muted == Some(true) || viewer.blocked.is_some()) {
+            lines.push("Not subscribed. :listsub mute or :listsub block with this list's link to subscribe".to_string());
+        }
+        lines.push(list.uri.clone());
+        self.view_stack.push_info_view(InfoView::from_strings(format!("📋 {}", list.name), lines));
+        Ok(())
+    }
+
+    // The lists we've made
+    async fn own_lists(&self) -> Result<Vec<ListView>> {
+        let session = self.api.session().await.ok_or(ApiError::NotAuthenticated)?;
+        self.api.get_lists(AtIdentifier::Did(session.did.clone())).await
+    }
+
+    // One of `lists` by link, at:// URI or name, ignoring case
+    async fn pick_list(&self, lists: &[ListView], target: &str) -> Result<Option<ListView>> {
+        let uri = self.resolve_list(target).await?;
+        Ok(lists
+            .iter()
+            .find(|list| Some(&list.uri) == uri.as_ref() || list.name.eq_ignore_ascii_case(target))
+            .cloned())
+    }
+
+    // :newlist <name> makes a curated list
+    async fn create_list(&mut self, name: &str) -> Result<()> {
+        let lists = self.own_lists().await?;
+        if lists.iter().any(|list| list.name.eq_ignore_ascii_case(name)) {
+            self.status_line = format!("You already have a list called {}", name);
+            return Ok(());
+        }
+        self.api.create_list(name, CURATED_LIST).await?;
+        self.toasts.push(Severity::Success, format!("Created the list {}, :addtolist {} <handle> to fill it", name, name));
+        Ok(())
+    }
+
+    // :addtolist and :removefromlist take <list> [handle]. The list can be
+    // named with spaces in it, so the last word is only taken as the handle
+    // when the whole thing doesn't name a list. No handle means the selected
+    // post's author
+    async fn change_list_membership(&mut self, args: &[&str], add: bool) -> Result<()> {
+        let command = if add { "addtolist" } else { "removefromlist" };
+        if args.is_empty() {
+            self.status_line = format!("Usage: :{} <list> [handle]", command);
+            return Ok(());
+        }
+        let lists = self.own_lists().await?;
+        let (list, handle) = match self.pick_list(&lists, &args.join(" ")).await? {
+            Some(list) => (Some(list), None),
+            None if args.len() > 1 => {
+                let (handle, name) = args.split_last().unwrap();
+                (self.pick_list(&lists, &name.join(" ")).await?, Some(handle.trim_start_matches('@')))
+            }
+            None => (None, None),
+        };
+        let Some(list) = list else {
+            let names: Vec<&str> = lists.iter().map(|list| list.name.as_str()).collect();
+            self.status_line = match names.as_slice() {
+                [] => "You haven't made any lists, :newlist <name> starts one".to_string(),
+                _ => format!("No list of yours called that, you have: {}", names.join(", ")),
+            };
+            return Ok(());
+        };
+        let (did, handle) = match handle {
+            Some(handle) => (self.api.resolve_handle(handle).await?, handle.to_string()),
+            None => match self.view_stack.current_view().get_selected_post() {
+                Some(post) => (post.author.did.clone(), post.author.handle.to_string()),
+                None => {
+                    self.status_line = format!("Usage: :{} <list> [handle] (or select a post)", command);
+                    return Ok(());
+                }
+            },
+        };
+        self.set_list_membership(&list, did, &handle, add).await
+    }
+
+    // Each of our lists, and the listitem record putting `did` on it if any
+    async fn list_memberships(&self, did: &Did) -> Result<(Vec<ListView>, Vec<Option<String>>)> {
+        let lists = self.own_lists().await?;
+        let mut items = Vec::with_capacity(lists.len());
+        for list in &lists {
+            items.push(self.api.find_list_item(&list.uri, did).await?);
+        }
+        Ok((lists, items))
+    }
+
+    // M on a profile
+    async fn open_list_picker(&mut self) {
+        let View::AuthorFeed(author_feed) = self.view_stack.current_view() else {
+            return;
+        };
+        let did = author_feed.profile.profile.did.clone();
+        let handle = author_feed.profile.profile.handle.to_string();
+        let task = self.task_manager.start("Loading your lists");
+        let result = self.list_memberships(&did).await;
+        self.task_manager.finish(task);
+        match result {
+            Ok((lists, _)) if lists.is_empty() => {
+                self.status_line = "You haven't made any lists, :newlist <name> starts one".to_string();
+            }
+            Ok((lists, items)) => self.list_picker = Some(ListPicker::new(did, handle, lists, items)),
+            Err(e) => self.error = Some(format!("Failed to load your lists: {}", e)),
+        }
+    }
+
+    // Enter in the list picker: on the list if they weren't, off if they were
+    async fn toggle_picked_list(&mut self) {
+        let Some(picker) = &self.list_picker else {
+            return;
+        };
+        let Some((list, item)) = picker.selected() else {
+            return;
+        };
+        let (did, handle, uri, name) = (picker.did.clone(), picker.handle.clone(), list.uri.clone(), list.name.clone());
+        let item = item.map(str::to_string);
+        let task = self.task_manager.start(format!("Updating {}", name));
+        let result = match &item {
+            Some(record) => self.api.remove_list_item(record).await.map(|_| None),
+            None => self.api.add_to_list(&uri, &did).await.map(Some),
+        };
+        self.task_manager.finish(task);
+        match result {
+            Ok(record) => {
+                let message = match record {
+                    Some(_) => format!("Added @{} to {}", handle, name),
+                    None => format!("Removed @{} from {}", handle, name),
                 };
-    
-                if let Ok(response) = self.api.agent.api.app.bsky.feed.get_author_feed(params).await {
-                    author_feed.posts.clear();
-                    author_feed.rendered_posts.clear();
-                    for post in &response.feed {
-                        author_feed.add_post(post.post.data.clone());
-                    }
+                if let Some(picker) = &mut self.list_picker {
+                    picker.set_selected_item(record);
                 }
+                self.toasts.push(Severity::Success, message);
             }
-            View::Notifications(notifications) => {
-                notifications.load_notifications(&mut self.api).await?;
+            Err(e) => self.error = Some(format!("Failed to update {}: {}", name, e)),
+        }
+    }
+
+    // Put `did` on `list` or take them off, unless they're already that way
+    async fn set_list_membership(&mut self, list: &ListView, did: Did, handle: &str, add: bool) -> Result<()> {
+        match (add, self.api.find_list_item(&list.uri, &did).await?) {
+            (true, Some(_)) => self.status_line = format!("@{} is already on {}", handle, list.name),
+            (true, None) => {
+                self.api.add_to_list(&list.uri, &did).await?;
+                self.toasts.push(Severity::Success, format!("Added @{} to {}", handle, list.name));
+            }
+            (false, Some(record)) => {
+                self.api.remove_list_item(&record).await?;
+                self.toasts.push(Severity::Success, format!("Removed @{} from {}", handle, list.name));
             }
+            (false, None) => self.status_line = format!("@{} isn't on {}", handle, list.name),
         }
-    
-        self.loading = false;
         Ok(())
     }
 
-    async fn check_notifications(&mut self) {
-        if self.last_notification_check.elapsed() >= self.notification_check_interval {
-            if let View::Notifications(notifications) = self.view_stack.current_view() {
-                notifications.load_notifications(&mut self.api).await.ok();
+    // :listsub mute|block <list>
+    async fn subscribe_list(&mut self, mode: &str, target: &str) -> Result<()> {
+        let Some(uri) = self.resolve_list(target).await? else {
+            self.status_line = format!("Not a Bluesky list link: {}", target);
+            return Ok(());
+        };
+        let list = self.api.get_list(&uri).await?;
+        if list.purpose != MODERATION_LIST {
+            self.status_line = format!("{} is a curated list, only moderation lists can be muted or blocked", list.name);
+            return Ok(());
+        }
+        let viewer = list.viewer.as_ref();
+        match mode {
+            "mute" if viewer.is_some_and(|viewer| viewer.muted == Some(true)) => {
+                self.status_line = format!("Already muting {}", list.name);
             }
-            self.last_notification_check = Instant::now();
+            "mute" => {
+                self.api.mute_list(&uri).await?;
+                self.toasts.push(Severity::Success, format!("🔇 Muting everyone on {}", list.name));
+            }
+            _ if viewer.is_some_and(|viewer| viewer.blocked.is_some()) => {
+                self.status_line = format!("Already blocking {}", list.name);
+            }
+            _ => {
+                self.api.block_list(&uri).await?;
+                self.toasts.push(Severity::Success, format!("⛔ Blocking everyone on {}", list.name));
+            }
+        }
+        Ok(())
+    }
+
+    // :listunsub <list> drops a mute and a block, whichever are there
+    async fn unsubscribe_list(&mut self, target: &str) -> Result<()> {
+        let Some(uri) = self.resolve_list(target).await? else {
+            self.status_line = format!("Not a Bluesky list link: {}", target);
+            return Ok(());
+        };
+        let list = self.api.get_list(&uri).await?;
+        let viewer = list.viewer.as_ref();
+        let mut dropped = Vec::new();
+        if viewer.is_some_and(|viewer| viewer.muted == Some(true)) {
+            self.api.unmute_list(&uri).await?;
+            dropped.push("muting");
+        }
+        if let Some(record) = viewer.and_then(|viewer| viewer.blocked.as_deref()) {
+            self.api.unblock_list(record).await?;
+            dropped.push("blocking");
+        }
+        match dropped.as_slice() {
+            [] => self.status_line = format!("Not muting or blocking {}", list.name),
+            _ => self.toasts.push(Severity::Success, format!("Stopped {} {}", dropped.join(" and "), list.name)),
+        }
+        Ok(())
+    }
+
+    // :listsubs
+    async fn show_list_subscriptions(&mut self) -> Result<()> {
+        let mutes = self.api.get_list_mutes().await?;
+        let blocks = self.api.get_list_blocks().await?;
+        let mut lines = Vec::new();
+        for (heading, lists) in [("🔇 Muting everyone on:", mutes), ("⛔ Blocking everyone on:", blocks)] {
+            lines.push(heading.to_string());
+            if lists.is_empty() {
+                lines.push("  None".to_string());
+            }
+            lines.extend(lists.iter().map(|list| format!("  {} by @{}  {}", list.name, list.creator.handle.as_str(), list.uri)));
+            lines.push(String::new());
         }
+        lines.push("Subscribe with :listsub mute|block <list>, stop with :listunsub <list>".to_string());
+        self.view_stack.push_info_view(InfoView::from_strings("🛡 List Subscriptions", lines));
+        Ok(())
+    }
+
+    async fn toggle_author_alert(&mut self, handle: Option<&str>) -> Result<()> {
+        let (did, handle) = match handle {
+            Some(handle) => {
+                let handle = handle.trim_start_matches('@');
+                (self.api.resolve_handle(handle).await?.as_str().to_string(), handle.to_string())
+            }
+            None => match self.view_stack.current_view().get_selected_post() {
+                Some(post) => (post.author.did.as_str().to_string(), post.author.handle.to_string()),
+                None => {
+                    self.status_line = "Usage: :alert <handle> (or select a post)".to_string();
+                    return Ok(());
+                }
+            },
+        };
+
+        let enabled = self.author_alerts.toggle(&did, &handle);
+        self.author_alerts.save()?;
+        self.status_line = if enabled {
+            // Poll soon so the baseline is recorded
+            self.last_alert_check = None;
+            format!("🔔 You'll be notified when @{} posts", handle)
+        } else {
+            format!("Stopped alerts for @{}", handle)
+        };
+        Ok(())
     }
 
     async fn handle_follow(&mut self) {
-        let did = match self.view_stack.current_view() {
+        // (did, handle, our follow record as far as the view knows)
+        let author = match self.view_stack.current_view() {
             // When viewing notifications
             View::Notifications(notifications) => {
-                let notification = notifications.get_notification();
-                Some(notification.author.did.clone())
+                let author = notifications.get_notification().author;
+                let following = author.viewer.as_ref().and_then(|viewer| viewer.following.clone());
+                Some((author.did.clone(), author.handle.to_string(), following))
             },
             // When viewing regular posts (timeline, thread, author feed)
             _ => {
                 self.view_stack.current_view()
                     .get_selected_post()
-                    .map(|post| post.author.did.clone())
+                    .map(|post| {
+                        let following = post.author.viewer.as_ref().and_then(|viewer| viewer.following.clone());
+                        (post.author.did.clone(), post.author.handle.to_string(), following)
+                    })
             }
         };
-    
-        if let Some(did) = did {
-            // Get profile to check current follow status
-            let params = atrium_api::app::bsky::actor::get_profile::ParametersData {
-                actor: atrium_api::types::string::AtIdentifier::Did(did.clone())
-            }.into();
-            
-            match self.api.agent.api.app.bsky.actor.get_profile(params).await {
-                Ok(profile) => {
-                    let is_following = profile.viewer
-                        .as_ref()
-                        .and_then(|v| v.following.as_ref())
-                        .is_some();
-    
-                    if is_following {
-                        let _ = self.api.unfollow_actor(&did).await;
-                    } else {
-                        let _ = self.api.follow_actor(did).await;
-                    }
-    
-                    // Refresh the current view to show updated follow status
-                    if let Err(e) = self.refresh_current_view().await {
-                        self.error = Some(format!("Failed to refresh view: {}", e));
-                    }
-                }
-                Err(e) => {
-                    self.error = Some(format!("Failed to get profile: {}", e));
-                }
+        let Some((did, handle, following)) = author else {
+            return;
+        };
+        let following = match self.follow_states.get(did.as_str()) {
+            Some(state) => state.clone(),
+            None => following,
+        };
+
+        match following {
+            Some(record) if record == PENDING_RECORD => {
+                self.status_line = format!("Still saving the last change to following @{}", handle);
+            }
+            Some(record) if self.settings.confirm_destructive => {
+                self.confirming = Some((
+                    ConfirmDialog::new(format!("Unfollow @{}?", handle)),
+                    Confirmable::Unfollow { did, record },
+                ));
             }
+            Some(record) => self.toggle_follow(did, Some(record)),
+            None => self.toggle_follow(did, None),
+        }
+    }
+
+    // Show the follow or unfollow everywhere straight away and queue the
+    // request; apply_task_events settles it or puts `following` back
+    fn toggle_follow(&mut self, did: Did, following: Option<String>) {
+        let mutation = match following.clone() {
+            Some(record) => Mutation::Unfollow { did: did.clone(), record },
+            None => Mutation::Follow { did: did.clone() },
+        };
+        let optimistic = match following {
+            Some(_) => None,
+            None => Some(PENDING_RECORD.to_string()),
+        };
+        self.set_following(&did, optimistic);
+        let id = self.tasks.enqueue(mutation);
+        self.follow_tasks.insert(id, (did, following));
+    }
+
+    // Remember our follow record for `did` and put it on their posts in every
+    // column and tab, which redraws just those headers
+    fn set_following(&mut self, did: &Did, record: Option<String>) {
+        self.follow_states.insert(did.as_str().to_string(), record.clone());
+        let columns = std::iter::once(&mut self.view_stack)
+            .chain(self.deck.iter_mut())
+            .chain(self.tabs.iter_mut().flat_map(|tab| std::iter::once(&mut tab.stack).chain(tab.deck.iter_mut())));
+        for view in columns.flat_map(|column| column.views.iter_mut()) {
+            view.set_author_following(did.as_str(), record.as_deref());
+        }
+    }
+
+    // Put a like or repost change on the post in every column and tab showing
+    // it, so none is left waiting on a record that's already been saved
+    fn update_post_everywhere(&mut self, post: PostView) {
+        let columns = std::iter::once(&mut self.view_stack)
+            .chain(self.deck.iter_mut())
+            .chain(self.tabs.iter_mut().flat_map(|tab| std::iter::once(&mut tab.stack).chain(tab.deck.iter_mut())));
+        for view in columns.flat_map(|column| column.views.iter_mut()) {
+            view.update_post(post.clone());
+        }
+    }
+
+    // A refetched post's counts, everywhere it's shown
+    fn refresh_post_everywhere(&mut self, post: PostView) {
+        let columns = std::iter::once(&mut self.view_stack)
+            .chain(self.deck.iter_mut())
+            .chain(self.tabs.iter_mut().flat_map(|tab| std::iter::once(&mut tab.stack).chain(tab.deck.iter_mut())));
+        for view in columns.flat_map(|column| column.views.iter_mut()) {
+            view.refresh_post(post.clone());
+        }
+    }
+
+    fn record_error(&mut self, message: &str) {
+        if self.error_history.len() == MAX_ERROR_HISTORY {
+            self.error_history.pop_front();
+        }
+        let view = self.view_stack.views.last();
+        self.error_history.push_back(ErrorRecord {
+            at: chrono::Local::now(),
+            message: message.to_string(),
+            view: view.map(View::short_title).unwrap_or_default(),
+            selected: view.and_then(View::selection_key),
+        });
+    }
+
+    // :errors, newest first
+    fn show_error_history(&mut self) {
+        if self.error_history.is_empty() {
+            self.status_line = "No errors so far".to_string();
+            return;
+        }
+        let mut lines = Vec::new();
+        for record in self.error_history.iter().rev() {
+            lines.push(format!("{}  {}", record.at.format("%H:%M:%S"), record.message));
+            lines.push(match &record.selected {
+                Some(selected) => format!("          in {}, on {}", record.view, selected),
+                None => format!("          in {}", record.view),
+            });
+            lines.push(String::new());
+        }
+        self.view_stack.push_info_view(InfoView::from_strings("⚠ Errors", lines));
+    }
+
+    async fn confirmed(&mut self, action: Confirmable) {
+        match action {
+            Confirmable::DeletePost { uri } => self.delete_post(&uri).await,
+            Confirmable::Unfollow { did, record } => self.toggle_follow(did, Some(record)),
         }
     }
     
 
     pub async fn handle_input(&mut self, key: KeyEvent) {
+        // Works in every mode, so it can be watched while typing
+        if key.code == KeyCode::F(12) {
+            self.debug_overlay = !self.debug_overlay;
+            return;
+        }
+        // A count only applies to the key right after it
+        let count = self.pending_count.take();
         match (self.command_mode, self.composing) {
             (true, _) => match (key.code, key.modifiers) {
                 (KeyCode::Esc, _) => {
@@ -276,15 +2085,23 @@ impl App {
                                 self.command_mode = false;
                             }
                             
-                            if let Err(e) = self.handle_command(&command.to_lowercase()).await {
+                            if let Err(e) = self.handle_command(&command).await {
                                 self.error = Some(format!("Command error: {}", e));
                             }
                         }
                     }
                 },
                 (KeyCode::Tab, _) => {
+                    self.load_completions().await;
                     self.command_input.handle_tab();
                 },
+                // Readline-style editing
+                (KeyCode::Char('w'), KeyModifiers::CONTROL) => self.command_input.delete_word(),
+                (KeyCode::Char('u'), KeyModifiers::CONTROL) => self.command_input.delete_to_start(),
+                (KeyCode::Char('a'), KeyModifiers::CONTROL) | (KeyCode::Home, _) => self.command_input.move_cursor_home(),
+                (KeyCode::Char('e'), KeyModifiers::CONTROL) | (KeyCode::End, _) => self.command_input.move_cursor_end(),
+                (KeyCode::Char('b'), KeyModifiers::ALT) => self.command_input.move_word_left(),
+                (KeyCode::Char('f'), KeyModifiers::ALT) => self.command_input.move_word_right(),
                 (KeyCode::Char(c), mods) => {
                     if mods == KeyModifiers::NONE || mods == KeyModifiers::SHIFT {
                         self.command_input.insert_char(c);
@@ -304,35 +2121,7 @@ impl App {
                     self.composing = false;
                     self.post_composer = None;
                 },
-                (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
-                    if let Some(composer) = &self.post_composer {
-                        let content = composer.get_content().to_string();
-                        let reply_to = composer.reply_to.clone();
-                        
-                        match self.api.create_post(content, reply_to).await {
-                            Ok(()) => {
-                                self.status_line = "Post created successfully".to_string();
-                                self.composing = false;
-                                self.post_composer = None;
-                                
-                                // Refresh view based on context
-                                match self.view_stack.current_view() {
-                                    View::Timeline(feed) => {
-                                        feed.load_initial_posts(&mut self.api).await.ok();
-                                    },
-                                    View::Thread(thread) => {
-                                        let anchor_uri = thread.anchor_uri.clone();
-                                        self.view_stack.push_thread_view(anchor_uri, &self.api).await.ok();
-                                    },
-                                    _ => {}
-                                }
-                            },
-                            Err(e) => {
-                                self.error = Some(format!("Failed to create post: {}", e));
-                            }
-                        }
-                    }
-                },
+                (KeyCode::Char('s'), KeyModifiers::CONTROL) => self.submit_post().await,
                 (KeyCode::Char(c), mods) => {
                     if mods == KeyModifiers::NONE || mods == KeyModifiers::SHIFT {
                         if let Some(composer) = &mut self.post_composer {
@@ -357,46 +2146,164 @@ impl App {
                 },
                 _ => {}
             },
-    
+    
+            // Answering a confirm dialog. Anything but y or n is ignored
+            (false, false) if self.confirming.is_some() => match key.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') => {
+                    if let Some((_, action)) = self.confirming.take() {
+                        self.confirmed(action).await;
+                    }
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.confirming = None;
+                    self.toasts.push(Severity::Info, "Cancelled");
+                }
+                _ => {}
+            },
+
+            // Picking lists for the profile's author
+            (false, false) if self.list_picker.is_some() => match key.code {
+                KeyCode::Char('j') | KeyCode::Down => self.list_picker.as_mut().unwrap().move_down(),
+                KeyCode::Char('k') | KeyCode::Up => self.list_picker.as_mut().unwrap().move_up(),
+                KeyCode::Enter => self.toggle_picked_list().await,
+                KeyCode::Esc => self.list_picker = None,
+                _ => {}
+            },
+
+            // Reading the help overlay
+            (false, false) if self.help.is_some() => {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Char('?')) {
+                    self.help = None;
+                } else if let Some(help) = &mut self.help {
+                    match key.code {
+                        KeyCode::Char('j') | KeyCode::Down => help.scroll_down(),
+                        KeyCode::Char('k') | KeyCode::Up => help.scroll_up(),
+                        _ => {}
+                    }
+                }
+            },
+
+            // Confirming a new avatar
+            (false, false) if self.avatar_preview.is_some() => match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => self.upload_avatar().await,
+                KeyCode::Char('n') | KeyCode::Esc => {
+                    self.avatar_preview = None;
+                    self.toasts.push(Severity::Info, "Avatar change cancelled");
+                }
+                _ => {}
+            },
+
+            // Second key of a mark or g sequence
+            (false, false) if self.pending_key.is_some() => {
+                let pending = self.pending_key.take();
+                if let KeyCode::Char(c) = key.code {
+                    match (pending, c) {
+                        (Some('m'), c) => self.set_mark(c),
+                        (Some('\''), c) => self.jump_to_mark(c).await,
+                        (Some('g'), 'g') => self.view_stack.current_view().select_index(0),
+                        (Some('g'), 'n') => self.load_new_posts(),
+                        (Some('g'), 't') => self.select_next_tab(true),
+                        (Some('g'), 'T') => self.select_next_tab(false),
+                        _ => {}
+                    }
+                }
+            },
+
             // Finally visual mode
             (false, false) => match (key.code, key.modifiers) {
                 // Enter command mode
                 (KeyCode::Char(':'), KeyModifiers::NONE) => {
                     self.command_mode = true;
                 },
-                
-                (KeyCode::Char('j'), KeyModifiers::NONE) => {
-                    self.view_stack.current_view().scroll_down();
-                    if let View::Timeline(feed) = self.view_stack.current_view() {
-                        if feed.needs_more_content() {
-                            self.loading = true;
-                            feed.scroll(&self.api).await;
-                            self.loading = false;
+                // In-view search goes through :find
+                (KeyCode::Char('/'), _) => {
+                    self.command_mode = true;
+                    self.command_input.set_content("find ");
+                },
+                (KeyCode::Char('n'), KeyModifiers::NONE) if self.search.is_some() => self.next_match(true),
+                (KeyCode::Char('N'), KeyModifiers::SHIFT) if self.search.is_some() => self.next_match(false),
+                (KeyCode::Esc, _) if self.search.is_some() => self.clear_search(),
+
+                // Count prefix. A leading 0 isn't one
+                (KeyCode::Char(digit @ '0'..='9'), KeyModifiers::NONE) if digit != '0' || count.is_some() => {
+                    let digit = digit.to_digit(10).unwrap_or(0) as usize;
+                    self.pending_count = Some((count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                },
+
+                (KeyCode::Char('j'), KeyModifiers::NONE) => self.scroll_down_by(count.unwrap_or(1)).await,
+                (KeyCode::Char('k'), KeyModifiers::NONE) => self.view_stack.current_view().scroll_up_by(count.unwrap_or(1)),
+                // A page, or half of one, of whatever fits on screen
+                (KeyCode::PageDown, _) | (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+                    let page = self.view_stack.current_view().page_size();
+                    let steps = if key.code == KeyCode::PageDown { page } else { page.div_ceil(2) };
+                    self.scroll_down_by(steps * count.unwrap_or(1)).await;
+                },
+                (KeyCode::PageUp, _) | (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+                    let page = self.view_stack.current_view().page_size();
+                    let steps = if key.code == KeyCode::PageUp { page } else { page.div_ceil(2) };
+                    self.view_stack.current_view().scroll_up_by(steps * count.unwrap_or(1));
+                },
+                (KeyCode::Char('<'), _) => self.view_stack.current_view().scroll_selected_horizontal(-8),
+                (KeyCode::Char('>'), _) => self.view_stack.current_view().scroll_selected_horizontal(8),
+                (KeyCode::Char('z'), KeyModifiers::NONE) => {
+                    // Threads fold replies; feeds open posts a collapse rule folded;
+                    // notifications open or fold a group
+                    let filtered_status = |expanded: Option<bool>| match expanded {
+                        Some(true) => "Showing filtered post".to_string(),
+                        Some(false) => "Filtered post folded away".to_string(),
+                        None => "This post isn't filtered".to_string(),
+                    };
+                    let status = match self.view_stack.current_view() {
+                        View::Thread(thread) => Some(match thread.toggle_collapsed() {
+                            Some(0) => "Expanded replies".to_string(),
+                            Some(hidden) => format!("Collapsed {} replies", hidden),
+                            None => "No replies to collapse".to_string(),
+                        }),
+                        View::Timeline(feed) => Some(filtered_status(feed.toggle_collapsed())),
+                        View::AuthorFeed(author_feed) => Some(filtered_status(author_feed.toggle_collapsed())),
+                        View::Notifications(notifications) => Some(match notifications.toggle_group() {
+                            Some(true) => "Expanded group".to_string(),
+                            Some(false) => "Grouped notifications".to_string(),
+                            None => "This notification isn't grouped".to_string(),
+                        }),
+                        _ => None,
+                    };
+                    if let Some(status) = status {
+                        self.status_line = status;
+                    }
+                },
+                (KeyCode::Char('t'), KeyModifiers::NONE) => {
+                    if let View::Thread(thread) = self.view_stack.current_view() {
+                        if !thread.jump_to_root() {
+                            self.status_line = "Top of the loaded thread, earlier posts aren't shown".to_string();
                         }
                     }
                 },
-                (KeyCode::Char('k'), KeyModifiers::NONE) => self.view_stack.current_view().scroll_up(),
+                (KeyCode::Char('T'), KeyModifiers::SHIFT) => {
+                    if let View::Thread(thread) = self.view_stack.current_view() {
+                        thread.jump_to_anchor();
+                    }
+                },
+                (KeyCode::Char('G'), KeyModifiers::SHIFT) => self.jump_to_end().await,
+                (KeyCode::Char('g'), KeyModifiers::NONE) => self.pending_key = Some('g'),
+                (KeyCode::Char('m'), KeyModifiers::NONE) => self.pending_key = Some('m'),
+                (KeyCode::Char('\''), _) => self.pending_key = Some('\''),
                 (KeyCode::Char('l'), KeyModifiers::NONE) => self.handle_like_post().await,
+                (KeyCode::Char('y'), KeyModifiers::NONE) => self.handle_yank(false),
+                (KeyCode::Char('Y'), KeyModifiers::SHIFT) => self.handle_yank(true),
                 (KeyCode::Char('r'), KeyModifiers::NONE) => self.handle_repost().await,
                 (KeyCode::Char('f'), KeyModifiers::NONE) => self.handle_follow().await,
-                (KeyCode::Char('v'), KeyModifiers::NONE) => {
-                    if let Some(post) = self.view_stack.current_view().get_selected_post() {
-                        let uri = post.uri.to_string();
-                        if self.view_stack.current_view().can_view_thread(&uri) {
-                            if let Err(e) = self.view_stack.push_thread_view(uri, &self.api).await {
-                                self.error = Some(format!("Failed to load thread: {}", e));
-                            }
-                        }
-                    }
-                },
+                (KeyCode::Char('#'), _) => self.open_selected_hashtag().await,
+                (KeyCode::Char('?'), _) => self.help = Some(help::help_view()),
+                (KeyCode::Char('v'), KeyModifiers::NONE) => self.view_selected_thread(),
+                (KeyCode::Tab, KeyModifiers::NONE) => self.focus_next_column(true),
+                (KeyCode::BackTab, _) => self.focus_next_column(false),
                 (KeyCode::Char('V'), KeyModifiers::SHIFT) => {
                     if let Some(post) = self.view_stack.current_view().get_selected_post() {
                         if let Some(quoted_post) = super::components::post::Post::extract_quoted_post_data(&post.into()) {
                             let quoted_uri = quoted_post.uri.to_string();
                             if self.view_stack.current_view().can_view_thread(&quoted_uri) {
-                                if let Err(e) = self.view_stack.push_thread_view(quoted_uri, &self.api).await {
-                                    self.error = Some(format!("Failed to load quoted thread: {}", e));
-                                }
+                                self.open_thread_in_background(quoted_uri, false, "quoted thread");
                             }
                         }
                     }
@@ -409,22 +2316,54 @@ impl App {
                     };
                     if !currently_notifs_view {self.view_stack.push_notifications_view();}
                     if let View::Notifications(notifications) = self.view_stack.current_view() {
-                        self.loading = true;
+                        let task = self.task_manager.start("Loading notifications");
                         let _ = notifications.load_notifications(&mut self.api).await;
-                        self.loading = false;
+                        notifications.merge_alerts(&self.alert_notifications);
+                        self.task_manager.finish(task);
+                    }
+                },
+                (KeyCode::Char('L'), KeyModifiers::SHIFT) => {
+                    self.open_profile_list(|uri| ProfileListSource::Likers { uri }).await
+                },
+                (KeyCode::Char('R'), KeyModifiers::SHIFT) => {
+                    self.open_profile_list(|uri| ProfileListSource::Reposters { uri }).await
+                },
+                (KeyCode::Enter, _)
+                    if matches!(self.view_stack.views.last(), Some(View::Thread(thread)) if thread.is_action_selected()) =>
+                {
+                    if let View::Thread(thread) = self.view_stack.current_view() {
+                        let task = self.task_manager.start("Loading thread posts");
+                        match thread.activate_selected_row(&self.api).await {
+                            Ok(status) => self.status_line = status,
+                            Err(e) => self.error = Some(format!("Failed to load thread posts: {}", e)),
+                        }
+                        self.task_manager.finish(task);
+                    }
+                },
+                (KeyCode::Enter, _) | (KeyCode::Char('a'), KeyModifiers::NONE)
+                    if matches!(self.view_stack.views.last(), Some(View::ProfileList(_))) =>
+                {
+                    let did = match self.view_stack.current_view() {
+                        View::ProfileList(profile_list) => profile_list.get_selected_profile().map(|profile| profile.did.clone()),
+                        _ => None,
+                    };
+                    if let Some(did) = did {
+                        self.handle_get_profile(AtIdentifier::Did(did));
+                    }
+                },
+                (KeyCode::Enter, _) if matches!(self.view_stack.views.last(), Some(View::Trending(_))) => {
+                    if let Err(e) = self.open_selected_trend().await {
+                        self.error = Some(format!("Failed to open trend: {}", e));
                     }
                 },
+                (KeyCode::Enter, _) => {
+                    self.open_post_detail();
+                },
                 (KeyCode::Char('a'), KeyModifiers::NONE) => {
                     if let View::Notifications(notifications) = self.view_stack.current_view() {
                         let selected_author_did = &notifications.get_notification().author.did;
                         let actor = AtIdentifier::Did(selected_author_did.clone());
-                        match self.view_stack.push_author_feed_view(actor, &self.api).await {
-                            Ok(_) => {},
-                            Err(e) => {
-                                log::info!("Error pushing author feed view: {:?}", e);
-                                self.error = Some(format!("Failed to load author feed: {}", e));
-                            }
-                        }
+                        self.open_author_feed_in_background(actor, "author feed");
                     } else if let Some(post) = self.view_stack.current_view().get_selected_post() {
                         let selected_author_did = post.author.did.clone();
                         
@@ -437,49 +2376,281 @@ impl App {
                 
                         if !is_same_author {
                             let actor = AtIdentifier::Did(selected_author_did);
-                            match self.view_stack.push_author_feed_view(actor, &self.api).await {
-                                Ok(_) => {},
-                                Err(e) => {
-                                    log::info!("Error pushing author feed view: {:?}", e);
-                                    self.error = Some(format!("Failed to load author feed: {}", e));
-                                }
-                            }
+                            self.open_author_feed_in_background(actor, "author feed");
                         }
                     }
                 },
+                (KeyCode::Char('M'), KeyModifiers::SHIFT)
+                    if matches!(self.view_stack.views.last(), Some(View::AuthorFeed(_))) =>
+                {
+                    self.open_list_picker().await
+                },
                 (KeyCode::Char('A'), KeyModifiers::SHIFT) => {
-                    if let Some(session) = self.api.agent.get_session().await {
+                    if let Some(session) = self.api.session().await {
                         // Get the logged-in user's DID
                         let did = &session.did;
                         let actor = AtIdentifier::Did(did.clone());
-                        
-                        match self.view_stack.push_author_feed_view(actor, &self.api).await {
-                            Ok(_) => {},
-                            Err(e) => {
-                                log::info!("Error pushing logged-in user feed view: {:?}", e);
-                                self.error = Some(format!("Failed to load your profile: {}", e));
-                            }
-                        }
+                        self.open_author_feed_in_background(actor, "your profile");
                     }
                 },
+                (KeyCode::Esc, _) if self.view_stack.views.len() == 1 && self.deck_focus > 0 => {
+                    self.close_focused_column();
+                }
                 (KeyCode::Esc, _) => {
                     self.view_stack.pop_view();
                 }
                 _ => {}
             }
         }
-    
+
+        // Catch posts loaded, or views opened, since the search started
+        if let Some(query) = &self.search {
+            self.view_stack.current_view().set_search_highlight(Some(query));
+        }
+        self.update_status();
+    }
+
+    // Open the selected post on a page of its own, with everything the list
+    // leaves out. Already there, Enter does nothing
+    fn open_post_detail(&mut self) {
+        if matches!(self.view_stack.current_view(), View::PostDetail(_)) {
+            return;
+        }
+        if let Some(post) = self.view_stack.current_view().get_selected_post() {
+            self.view_stack.push_post_detail_view(post.into());
+        }
+    }
+
+    // Open the selected post's thread, or in notifications the post it's about
+    fn view_selected_thread(&mut self) {
+        let uri = if let View::Notifications(notifications) = self.view_stack.current_view() {
+            match notifications.selected_post_uri() {
+                Some(uri) => uri,
+                // e.g. a follow
+                None => {
+                    self.status_line = "This notification isn't about a post".to_string();
+                    return;
+                }
+            }
+        } else if let Some(post) = self.view_stack.current_view().get_selected_post() {
+            let uri = post.uri.to_string();
+            if !self.view_stack.current_view().can_view_thread(&uri) {
+                return;
+            }
+            uri
+        } else {
+            return;
+        };
+
+        let split = self.settings.split_threads && !matches!(self.view_stack.current_view(), View::Thread(_));
+        self.open_thread_in_background(uri, split, "thread");
+    }
+
+    // Run `fetch` on its own task; apply_fetched picks up the result.
+    // `on_top` when it'll be pushed over the current view, which shows a
+    // placeholder meanwhile
+    fn fetch_in_background(&mut self, what: &'static str, on_top: bool, fetch: impl Future<Output = Result<Fetched>> + Send + 'static) {
+        let label = format!("Opening {}", what);
+        let task = match on_top {
+            true => self.task_manager.start_view(label),
+            false => self.task_manager.start(label),
+        };
+        let sender = self.fetched_sender.clone();
+        tokio::spawn(async move {
+            sender.send((what, task, fetch.await)).await.ok();
+        });
+    }
+
+    fn open_thread_in_background(&mut self, uri: String, split: bool, what: &'static str) {
+        let api = self.api.clone();
+        self.fetch_in_background(what, !split, async move {
+            let refs = ViewStack::fetch_thread(uri, &api).await?;
+            anyhow::Ok(Fetched::Thread { refs, split })
+        });
+    }
+
+    fn open_author_feed_in_background(&mut self, actor: AtIdentifier, what: &'static str) {
+        let api = self.api.clone();
+        self.fetch_in_background(what, true, async move {
+            let (profile, feed) = ViewStack::fetch_author_feed(actor, &api).await?;
+            anyhow::Ok(Fetched::AuthorFeed { profile: Box::new(profile), feed })
+        });
+    }
+
+    // The view goes to whichever column has focus by now, which is the one
+    // being looked at
+    fn apply_fetched(&mut self, what: &'static str, task: TaskHandle, result: Result<Fetched>) {
+        self.task_manager.finish(task);
+        match result {
+            Ok(Fetched::Thread { refs, split: true }) => self.open_thread_pane(refs),
+            Ok(Fetched::Thread { refs, split: false }) => self.view_stack.push_thread(refs),
+            Ok(Fetched::AuthorFeed { profile, feed }) => self.view_stack.push_author_feed(*profile, feed),
+            Err(e) => {
+                log::info!("Error opening {}: {:?}", what, e);
+                self.error = Some(format!("Failed to load {}: {}", what, e));
+            }
+        }
+        self.update_status();
+    }
+
+    // split_threads: show the thread in a column to the right, keeping focus
+    // where it is. A thread column already on the right is reused
+    fn open_thread_pane(&mut self, thread_refs: OutputThreadRefs) {
+        let mut pane = ViewStack::new(Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
+        pane.push_thread(thread_refs);
+        pane.views.remove(0);
+
+        // deck.last() is the rightmost column unless that's the focused one
+        let rightmost_is_thread = self.deck_focus < self.deck.len()
+            && matches!(self.deck.last().and_then(|column| column.views.first()), Some(View::Thread(_)));
+        if rightmost_is_thread {
+            if let Some(column) = self.deck.last_mut() {
+                *column = pane;
+            }
+        } else if self.deck.len() + 1 < MAX_DECK_COLUMNS {
+            self.deck.push(pane);
+        } else {
+            // No room for another column
+            self.view_stack.views.append(&mut pane.views);
+        }
+    }
+
+    // Esc at the bottom of a column other than the timeline's closes it
+    fn close_focused_column(&mut self) {
+        let index = self.deck_focus;
+        if index == 0 {
+            return;
+        }
+        self.focus_column(index - 1);
+        self.deck.remove(index - 1);
+    }
+
+    // A left click selects the item under it; a second click on it soon after opens its thread
+    async fn handle_click(&mut self, column: u16, row: u16) {
+        // In deck mode a click in another column moves focus there first
+        let other_column = self.deck.iter().position(|stack| {
+            stack.views.last().is_some_and(|view| view.item_at(column, row).is_some())
+        });
+        if let Some(position) = other_column {
+            self.focus_column(position + usize::from(position >= self.deck_focus));
+            self.last_click = None;
+        }
+        let Some(index) = self.view_stack.current_view().item_at(column, row) else {
+            return;
+        };
+        let double = self.last_click
+            .is_some_and(|(at, clicked)| clicked == index && at.elapsed() <= DOUBLE_CLICK_TIME);
+        self.view_stack.current_view().select_index(index);
+        if double {
+            self.last_click = None;
+            self.view_selected_thread();
+        } else {
+            self.last_click = Some((Instant::now(), index));
+        }
+    }
+
+    // The wheel moves the selection a post at a time, like j and k
+    async fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if self.command_mode || self.composing || self.avatar_preview.is_some() || self.confirming.is_some() || self.list_picker.is_some() {
+            return;
+        }
+        let down = match mouse.kind {
+            MouseEventKind::ScrollDown => true,
+            MouseEventKind::ScrollUp => false,
+            MouseEventKind::Down(MouseButton::Left) if self.help.is_none() => {
+                self.handle_click(mouse.column, mouse.row).await;
+                self.update_status();
+                return;
+            }
+            _ => return,
+        };
+        if let Some(help) = &mut self.help {
+            if down {
+                help.scroll_down();
+            } else {
+                help.scroll_up();
+            }
+        } else if down {
+            self.scroll_down_by(1).await;
+        } else {
+            self.view_stack.current_view().scroll_up();
+        }
         self.update_status();
     }
+
+    fn search_matches(&mut self) -> Vec<usize> {
+        let Some(query) = &self.search else {
+            return Vec::new();
+        };
+        self.view_stack.current_view()
+            .search_texts()
+            .iter()
+            .enumerate()
+            .filter(|(_, text)| text.contains(query.as_str()))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Select the next match after the selection, or the one before it, wrapping around
+    fn next_match(&mut self, forward: bool) {
+        let matches = self.search_matches();
+        let selected = self.view_stack.current_view().selected_index();
+        let next = if forward {
+            matches.iter().find(|&&index| index > selected).or(matches.first())
+        } else {
+            matches.iter().rev().find(|&&index| index < selected).or(matches.last())
+        };
+        if let Some(&index) = next {
+            self.view_stack.current_view().select_index(index);
+        }
+    }
+
+    fn start_search(&mut self, query: &str) {
+        self.search = Some(query.to_lowercase());
+        // Start from the selected item itself if it matches
+        let selected = self.view_stack.current_view().selected_index();
+        if !self.search_matches().contains(&selected) {
+            self.next_match(true);
+        }
+    }
+
+    fn clear_search(&mut self) {
+        self.search = None;
+        for view in &mut self.view_stack.views {
+            view.set_search_highlight(None);
+        }
+    }
     
+    fn is_builtin_command(name: &str) -> bool {
+        help::COMMANDS.iter().any(|command| command.name.eq_ignore_ascii_case(name))
+    }
+
+    // Swap an alias at the start of `command` for what it stands for, keeping
+    // any arguments after it. Aliases can't replace built-in commands, and
+    // aren't expanded again, so one can't loop into another
+    fn expand_alias(&self, command: &str) -> Option<String> {
+        let command = command.trim();
+        let (name, rest) = command.split_once(char::is_whitespace).unwrap_or((command, ""));
+        if Self::is_builtin_command(name) {
+            return None;
+        }
+        let (_, target) = self.settings.aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))?;
+        Some(format!("{} {}", target.trim().trim_start_matches(':'), rest).trim_end().to_string())
+    }
+
     //Helper function to handle command parsing and execution
     async fn handle_command(&mut self, command: &str) -> Result<()> {
+        let expanded = self.expand_alias(command);
+        let command = expanded.as_deref().unwrap_or(command);
         let parts: Vec<&str> = command.split_whitespace().collect();
         if parts.is_empty() {
             return Ok(());
         }
-    
-        match parts[0] {
+
+        // Only the name is case-insensitive, arguments such as file paths keep their case
+        match parts[0].to_lowercase().as_str() {
             "login" => {
                 if !self.authenticated {
                     if let Some(login_view) = &mut self.login_view {
@@ -494,14 +2665,89 @@ impl App {
                     }
                 }
             },
+            "help" => {
+                self.help = Some(help::help_view());
+            }
+            "errors" => self.show_error_history(),
+            "listsub" => match (parts.get(1).copied(), parts.get(2)) {
+                (Some(mode @ ("mute" | "block")), Some(target)) => self.subscribe_list(mode, target).await?,
+                _ => self.status_line = "Usage: :listsub mute|block <list url or at:// uri>".to_string(),
+            },
+            "listunsub" => match parts.get(1) {
+                Some(target) => self.unsubscribe_list(target).await?,
+                None => self.status_line = "Usage: :listunsub <list url or at:// uri>".to_string(),
+            },
+            "listsubs" => self.show_list_subscriptions().await?,
+            "newlist" => match parts[1..].join(" ") {
+                name if name.is_empty() => self.status_line = "Usage: :newlist <name>".to_string(),
+                name => self.create_list(&name).await?,
+            },
+            "addtolist" => self.change_list_membership(&parts[1..], true).await?,
+            "removefromlist" => self.change_list_membership(&parts[1..], false).await?,
+            "alias" => {
+                if self.settings.aliases.is_empty() {
+                    self.status_line = "No aliases yet, add them under aliases in settings.json".to_string();
+                } else {
+                    let lines = self.settings.aliases
+                        .iter()
+                        .map(|(alias, target)| match Self::is_builtin_command(alias) {
+                            true => format!(":{} → {} (ignored, :{} is a built-in command)", alias, target, alias),
+                            false => format!(":{} → :{}", alias, target),
+                        })
+                        .collect();
+                    self.view_stack.push_info_view(InfoView::from_strings("🔤 Aliases", lines));
+                }
+            }
+            "deck" => {
+                self.toggle_deck().await;
+            }
+            "density" => {
+                // For this session; the density setting picks the default
+                match parts.get(1).map(|density| density.to_lowercase()).as_deref() {
+                    Some("compact") => self.settings.density = Density::Compact,
+                    Some("normal") => self.settings.density = Density::Normal,
+                    _ => self.status_line = "Usage: :density compact|normal".to_string(),
+                }
+            }
+            "tabnew" => {
+                self.new_tab().await;
+            }
+            "tabclose" => {
+                self.close_tab();
+            }
+            "reconnect" => {
+                if self.settings.realtime == RealtimeSource::Off {
+                    self.status_line = "Realtime updates are off (see the realtime setting)".to_string();
+                } else {
+                    // Starting over also picks up accounts followed since
+                    self.status_line = "Reconnecting realtime updates".to_string();
+                    self.realtime_status = None;
+                    self.start_realtime_updates().await;
+                }
+            }
             "logout" => {
                 // Clear API session
                 self.api.logout().await?;
+                // The worker holds its own copy of the old session
+                self.tasks.cancel_pending();
+                self.update_manager.stop().await;
+                self.realtime_status = None;
+                self.command_input.forget_completions();
+                self.close_deck();
+                self.tabs.clear();
+                self.tab_index = 0;
+                self.tasks = TaskQueue::new(self.api.clone());
+                self.interaction_tasks.clear();
+                // Unsent posts belong to the account that wrote them
+                if let Err(e) = self.outbox.clear() {
+                    log::warn!("Failed to clear outbox: {}", e);
+                }
+                SavedSession::clear();
                 
                 // Reset app state
                 self.authenticated = false;
                 self.login_view = Some(LoginView::new());
-                self.view_stack = ViewStack::new(Arc::clone(&self.image_manager));
+                self.view_stack = ViewStack::new(Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
                 self.command_mode = false;
                 self.command_input.clear();
                 self.status_line = "Logged out successfully".to_string();
@@ -525,12 +2771,7 @@ impl App {
                 self.refresh_current_view().await?;
             },
             "notifications" => {
-                self.view_stack.push_notifications_view();
-                if let View::Notifications(notifications) = self.view_stack.current_view() {
-                    self.loading = true;
-                    notifications.load_notifications(&mut self.api).await?;
-                    self.loading = false;
-                }
+                self.open_notifications().await?;
             },
             "timeline" => {
                 while self.view_stack.views.len() > 1 {
@@ -546,48 +2787,171 @@ impl App {
             "repost" => {
                 self.handle_repost().await;
             },
+            "filter" => {
+                self.handle_filter_command(&parts[1..]).await?;
+            },
+            "feed" => {
+                self.handle_feed_command(&parts[1..]).await?;
+            },
+            "trending" => {
+                self.open_trending().await?;
+            },
+            "tag" => {
+                self.open_hashtag(parts.get(1).copied().unwrap_or_default()).await?;
+            },
+            "outbox" => {
+                self.handle_outbox_command(&parts[1..]);
+            },
+            "cancel" => {
+                self.status_line = match self.tasks.cancel_pending() {
+                    0 => "Nothing waiting to cancel".to_string(),
+                    cancelled => format!("Cancelled {} queued changes", cancelled),
+                };
+            },
             "profile" => {
                 //if we have an arg, handle argument to go to specific profile
                 if parts.len() > 1 {
-                    let actor = AtIdentifier::Handle(
-                        Handle::new(
-                            parts[1].to_string()
-                        ).unwrap());
-                    self.handle_get_profile(actor).await;
+                    let did = self.api.resolve_actor(parts[1]).await?;
+                    self.handle_get_profile(AtIdentifier::Did(did));
                 } 
                 // otherwise go to profile belonging to highlighted post
                 else {
                     if let Some(post) = self.view_stack.current_view().get_selected_post() {
                         let actor = &post.author.did;
-                        self.handle_get_profile(AtIdentifier::Did(actor.clone())).await;
+                        self.handle_get_profile(AtIdentifier::Did(actor.clone()));
                     } else {
                         if let View::Notifications(notif_view) =  self.view_stack.current_view() {
                             let actor = &notif_view.get_notification().author.did;
-                            self.handle_get_profile(AtIdentifier::Did(actor.clone())).await;
+                            self.handle_get_profile(AtIdentifier::Did(actor.clone()));
                         }
                     }
                 }
             }
+            "open" => {
+                let Some(target) = parts.get(1) else {
+                    self.status_line = "Usage: :open <bsky.app url or at:// uri>".to_string();
+                    return Ok(());
+                };
+                match BskyLink::parse(target) {
+                    Some(BskyLink::Post { actor, rkey }) => {
+                        let did = self.api.resolve_actor(&actor).await?;
+                        let uri = format!("at://{}/app.bsky.feed.post/{}", did.as_str(), rkey);
+                        self.open_thread_in_background(uri, false, "thread");
+                    }
+                    Some(BskyLink::Profile { actor }) => {
+                        let did = self.api.resolve_actor(&actor).await?;
+                        self.handle_get_profile(AtIdentifier::Did(did));
+                    }
+                    Some(BskyLink::List { actor, rkey }) => {
+                        let did = self.api.resolve_actor(&actor).await?;
+                        self.show_list(&list_uri(did.as_str(), &rkey)).await?;
+                    }
+                    None => {
+                        self.status_line = format!("Not a Bluesky post, profile or list link: {}", target);
+                    }
+                }
+            }
+            "alert" => {
+                self.toggle_author_alert(parts.get(1).copied()).await?;
+            }
+            "alerts" => {
+                let mut lines = vec!["Authors you get 🔔 alerts for (toggle with :alert <handle>):".to_string(), String::new()];
+                if self.author_alerts.is_empty() {
+                    lines.push("None yet".to_string());
+                }
+                lines.extend(self.author_alerts.handles().into_iter().map(|handle| format!("@{}", handle)));
+                self.view_stack.push_info_view(InfoView::from_strings("🔔 Author Alerts", lines));
+            }
+            "find" => {
+                let query = parts[1..].join(" ");
+                if query.is_empty() {
+                    self.clear_search();
+                } else {
+                    self.start_search(&query);
+                }
+            }
+            "goto" => {
+                let view = self.view_stack.current_view();
+                let total = view.item_count();
+                match parts.get(1).and_then(|n| n.parse::<usize>().ok()) {
+                    // Counted from 1, like the x / y in the status line
+                    Some(n) if (1..=total).contains(&n) => view.select_index(n - 1),
+                    Some(_) => self.status_line = format!("There are {} items here", total),
+                    None => self.status_line = "Usage: :goto <number>".to_string(),
+                }
+            }
+            "likes" => {
+                self.open_likes().await?;
+            }
+            "filters" => {
+                let lines = match self.view_stack.current_view().filters() {
+                    Some(filters) => filters.status_lines(),
+                    None => vec!["This view isn't filtered".to_string()],
+                };
+                self.view_stack.push_info_view(InfoView::from_strings("🔍 Filters", lines));
+            }
+            "likers" => {
+                self.open_profile_list(|uri| ProfileListSource::Likers { uri }).await;
+            }
+            "reposters" => {
+                self.open_profile_list(|uri| ProfileListSource::Reposters { uri }).await;
+            }
+            "sort" => {
+                let Some(sort) = parts.get(1).and_then(|arg| ReplySort::from_arg(arg)) else {
+                    self.status_line = "Usage: :sort likes|newest|oldest".to_string();
+                    return Ok(());
+                };
+                match self.view_stack.current_view() {
+                    View::Thread(thread) => {
+                        thread.set_reply_sort(sort);
+                        self.status_line = format!("Replies sorted by {}", sort.label());
+                    }
+                    _ => self.status_line = "Replies can only be sorted in a thread".to_string(),
+                }
+            }
+            "avatar" => {
+                // Everything after the command name, so paths may contain spaces
+                let path = command.trim_start()[parts[0].len()..].trim();
+                if path.is_empty() {
+                    self.status_line = "Usage: :avatar <path to image>".to_string();
+                    return Ok(());
+                }
+                let path = match path.strip_prefix("~/") {
+                    Some(rest) => std::path::PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(rest),
+                    None => std::path::PathBuf::from(path),
+                };
+                self.avatar_preview = Some(AvatarPreview::load(&path, Arc::clone(&self.image_manager)).await?);
+            }
+            "restore" => {
+                if parts.get(1).is_some_and(|arg| arg.eq_ignore_ascii_case("discard")) {
+                    self.recovery = None;
+                    self.status_line = "Discarded recovered session".to_string();
+                } else {
+                    self.restore_session().await?;
+                }
+            }
+            "diagnose-images" => {
+                let diagnostics = self.image_manager.diagnose();
+                self.view_stack.push_info_view(InfoView::from_strings(
+                    "🖼️ Image Diagnostics",
+                    diagnostics.lines(),
+                ));
+            }
             "delete" => {
                 if let Some(post) = self.view_stack.current_view().get_selected_post() {
                     // Only allow deletion if the post author's DID matches the current user's DID
-                    if let Some(session) = self.api.agent.get_session().await {
-                        if post.author.did == session.did {
-                            match self.api.delete_post(&post.uri).await {
-                                Ok(_) => {
-                                    self.status_line = "Post deleted successfully".to_string();
-                                    // Refresh the current view to reflect the deletion
-                                    self.refresh_current_view().await.ok();
-                                }
-                                Err(e) => {
-                                    self.error = Some(format!("Failed to delete post: {}", e));
-                                }
-                            }
-                        } else {
+                    if let Some(session) = self.api.session().await {
+                        if post.author.did != session.did {
                             self.status_line = "You can only delete your own posts".to_string();
+                        } else if self.settings.confirm_destructive {
+                            self.confirming = Some((
+                                ConfirmDialog::new("Delete this post? It can't be brought back."),
+                                Confirmable::DeletePost { uri: post.uri.clone() },
+                            ));
+                        } else {
+                            self.delete_post(&post.uri).await;
                         }
                     }
-                    let _ = self.refresh_current_view().await;
                 }
             }
             _ => {
@@ -597,6 +2961,103 @@ impl App {
         Ok(())
     }
 
+    async fn delete_post(&mut self, uri: &str) {
+        match self.api.delete_post(uri).await {
+            Ok(_) => {
+                self.toasts.push(Severity::Success, "Post deleted");
+                // Refresh the current view to reflect the deletion
+                self.refresh_current_view().await.ok();
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to delete post: {}", e));
+            }
+        }
+    }
+
+    async fn upload_avatar(&mut self) {
+        let Some(preview) = self.avatar_preview.take() else {
+            return;
+        };
+        let task = self.task_manager.start("Uploading avatar");
+        let result = self.api.update_avatar(preview.jpeg).await;
+        self.task_manager.finish(task);
+        match result {
+            Ok(()) => self.toasts.push(Severity::Success, format!("Avatar updated from {}", preview.path.display())),
+            Err(e) => self.error = Some(format!("Failed to update avatar: {}", e)),
+        }
+    }
+
+    async fn start_realtime_updates(&mut self) {
+        if self.settings.realtime == RealtimeSource::Off {
+            return;
+        }
+        let Some(session) = self.api.session().await else {
+            return;
+        };
+        let did = session.did.as_str().to_string();
+        // Only listen to ourselves and the accounts we follow. If the follows
+        // can't be had, listen to everyone rather than miss things
+        let wanted_dids = match self.followed_dids(session.did.clone()).await {
+            Ok(mut dids) => {
+                dids.insert(0, did.clone());
+                dids
+            }
+            Err(e) => {
+                log::warn!("Could not load follows, realtime updates will be unfiltered: {}", e);
+                Vec::new()
+            }
+        };
+        if let Err(e) = self.update_manager.start(did, self.settings.realtime, wanted_dids).await {
+            log::warn!("Could not start realtime updates: {}", e);
+        }
+    }
+
+    async fn followed_dids(&self, did: Did) -> Result<Vec<String>> {
+        let follows = self.fetch_follows(did, MAX_WANTED_DIDS).await?;
+        Ok(follows.iter().map(|profile| profile.did.as_str().to_string()).collect())
+    }
+
+    // Everyone `did` follows, up to about `limit` of them
+    async fn fetch_follows(&self, did: Did, limit: usize) -> Result<Vec<ProfileView>> {
+        let mut all = Vec::new();
+        let mut cursor = None;
+        loop {
+            let (follows, next) = self.api.get_follows(AtIdentifier::Did(did.clone()), cursor).await?;
+            let done = follows.is_empty();
+            all.extend(follows);
+            match next {
+                Some(next) if !done && all.len() < limit => cursor = Some(next),
+                _ => break,
+            }
+        }
+        Ok(all)
+    }
+
+    // Fetch what the argument being completed can be, the first time it's needed
+    async fn load_completions(&mut self) {
+        let command = self.command_input.argument_command().map(str::to_string);
+        match command.as_deref() {
+            Some("profile" | "alert") if self.command_input.follow_handles.is_none() => {
+                let Some(session) = self.api.session().await else {
+                    return;
+                };
+                match self.fetch_follows(session.did.clone(), MAX_WANTED_DIDS).await {
+                    Ok(follows) => {
+                        let mut handles: Vec<String> = follows.iter().map(|profile| profile.handle.as_str().to_string()).collect();
+                        handles.sort();
+                        self.command_input.follow_handles = Some(handles);
+                    }
+                    Err(e) => log::info!("Could not load follows to complete: {}", e),
+                }
+            }
+            Some("feed") if self.command_input.feed_names.is_none() => match self.api.get_saved_feeds().await {
+                Ok(feeds) => self.command_input.feed_names = Some(feeds.into_iter().map(|(name, _)| name).collect()),
+                Err(e) => log::info!("Could not load saved feeds to complete: {}", e),
+            },
+            _ => {}
+        }
+    }
+
     async fn handle_login_input(&mut self, input: String) -> Result<()> {
         if let Some(login_view) = &mut self.login_view {
             if let Some(username) = &login_view.username {
@@ -609,9 +3070,11 @@ impl App {
                         self.command_input.password_mode = false;
                         self.command_mode = false;
                         
-                        self.loading = true;
                         self.load_initial_posts().await;
-                        self.loading = false;
+                        self.start_realtime_updates().await;
+                        if !self.settings.deck.is_empty() {
+                            self.toggle_deck().await;
+                        }
                     }
                     Err(e) => {
                         login_view.loading = false;  // Clear loading on error
@@ -630,45 +3093,156 @@ impl App {
     pub async fn run(mut self) -> Result<()> {
         // Terminal initialization
         enable_raw_mode()?;
+        crate::logging::set_tui_active(true);
         let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
+        execute!(stdout, EnterAlternateScreen, EnableFocusChange)?;
+        if self.settings.mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
         let backend = ratatui::backend::CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
+        // The terminal was already probed when the ImageManager was created
+        let mut splash = Splash::new();
+        splash.begin("Probing terminal graphics");
+        splash.finish(self.image_manager.probe_summary());
+        splash.begin("Restoring session");
+        self.splash = Some(splash);
+        terminal.draw(|f| draw(f, &mut self))?;
+
         // Check authentication
-        if let Some(_session) = self.api.agent.get_session().await {
+        if let Some(session) = self.api.session().await {
+            self.authenticated = true;
+            if let Some(splash) = &mut self.splash {
+                splash.finish(format!("@{}", session.handle.as_str()));
+            }
+            self.start_realtime_updates().await;
+        } else if self.api.is_offline() {
+            // Couldn't reach the PDS to resume, but there's a saved copy to read
             self.authenticated = true;
+            if let Some(splash) = &mut self.splash {
+                splash.finish("offline, showing saved posts");
+            }
         } else {
             self.login_view = Some(LoginView::new());
+            if let Some(splash) = &mut self.splash {
+                splash.finish("not logged in");
+            }
         }
 
         // Main event loop with authentication check
         if self.authenticated {
+            if let Some(splash) = &mut self.splash {
+                splash.begin("Fetching timeline");
+            }
+            terminal.draw(|f| draw(f, &mut self))?;
+
             self.load_initial_posts().await;
+            let post_count = self.view_stack.current_view().item_count();
+            if let Some(splash) = &mut self.splash {
+                match &self.error {
+                    Some(e) => splash.fail(e.clone()),
+                    None => splash.finish(format!("{} posts", post_count)),
+                }
+            }
+            terminal.draw(|f| draw(f, &mut self))?;
+
+            let saved_views = std::mem::take(&mut self.saved_views);
+            if !saved_views.is_empty() {
+                if let Some(splash) = &mut self.splash {
+                    splash.begin("Reopening views");
+                }
+                terminal.draw(|f| draw(f, &mut self))?;
+                self.restore_saved_views(saved_views).await;
+                let reopened = self.view_stack.views.len() - 1;
+                if let Some(splash) = &mut self.splash {
+                    splash.finish(format!("{} reopened", reopened));
+                }
+                terminal.draw(|f| draw(f, &mut self))?;
+            }
+
+            if !self.settings.deck.is_empty() {
+                self.toggle_deck().await;
+            }
         }
+        self.splash = None;
 
         let result = self.event_loop(&mut terminal).await;
+        self.save_session();
+        if let Err(e) = self.api.flush_offline_cache().await {
+            log::warn!("Failed to save offline cache: {}", e);
+        }
         self.cleanup(&mut terminal)?;
         result
     }
 
     async fn event_loop<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
-        let tick_rate = Duration::from_millis(250);
         let mut last_tick = Instant::now();
 
         loop {
+            let tick_rate = self.tick_rate();
             // Check for post updates
-            while let Ok(updated_post) = self.post_update_receiver.try_recv() {
-                self.view_stack.current_view().update_post(updated_post);
+            self.apply_task_events();
+            self.flush_post_refreshes();
+            while let Ok(posts) = self.refreshed_posts_receiver.try_recv() {
+                self.dirty = true;
+                for post in posts {
+                    self.refresh_post_everywhere(post);
+                }
+            }
+            self.resolve_reply_handles();
+            while let Ok(results) = self.alert_receiver.try_recv() {
+                self.dirty = true;
+                self.apply_author_alerts(results);
+            }
+            self.poll_author_alerts();
+            while let Ok(results) = self.outbox_receiver.try_recv() {
+                self.dirty = true;
+                self.apply_outbox_results(results);
             }
+            self.retry_outbox().await;
+            while let Ok((what, task, result)) = self.fetched_receiver.try_recv() {
+                self.dirty = true;
+                self.apply_fetched(what, task, result);
+            }
+            while let Ok(posts) = self.timeline_peek_receiver.try_recv() {
+                self.dirty = true;
+                self.apply_timeline_peek(posts);
+            }
+            self.peek_timeline();
 
-            terminal.draw(|f| draw(f, self))?;
+            if self.image_manager.take_ready() {
+                self.dirty = true;
+            }
+            if let Some(error) = self.error.take() {
+                self.record_error(&error);
+                self.toasts.push(Severity::Error, error);
+                self.dirty = true;
+            }
+            if self.toasts.expire() {
+                self.dirty = true;
+            }
+            // The highlight has to be drawn away once it runs out
+            if self.status_flash_until.is_some_and(|until| Instant::now() >= until) {
+                self.status_flash_until = None;
+                self.dirty = true;
+            }
+            if self.dirty {
+                // Everything the snapshot holds changes through an event or
+                // update, and those all ask for a redraw
+                recovery::record(self.recovery_snapshot());
+                let started = Instant::now();
+                terminal.draw(|f| draw(f, self))?;
+                self.last_frame_time = started.elapsed();
+                self.dirty = false;
+            }
 
             let timeout = tick_rate
                 .checked_sub(last_tick.elapsed())
                 .unwrap_or_else(|| Duration::from_secs(0));
 
             if event::poll(timeout)? {
+                self.dirty = true;
                 match event::read()? {
                     Event::Key(key) => {
                         if key.code == KeyCode::Char('q') && !self.command_mode && !self.composing {
@@ -676,30 +3250,49 @@ impl App {
                         }
                         self.handle_input(key).await;
                     }
-                    Event::Mouse(_) => {}
+                    Event::Mouse(mouse) => self.handle_mouse(mouse).await,
+                    // Lists see the new width when they're next drawn and measure their posts again
                     Event::Resize(_, _) => {}
-                    Event::FocusGained => {}
-                    Event::FocusLost => {}
+                    Event::FocusGained => self.focused = true,
+                    Event::FocusLost => self.focused = false,
                     Event::Paste(_) => {}
                 }
             }
 
             // Handle real-time updates
             while let Some(event) = self.update_manager.try_recv() {
+                self.dirty = true;
                 match event {
                     UpdateEvent::Notification { uri } => {
-                        if let View::Notifications(notifications) = self.view_stack.current_view() {
-                            notifications.handle_new_notification(uri, &self.api).await?;
-                        }
+                        let latest = if let View::Notifications(notifications) = self.view_stack.current_view() {
+                            // Transient; the next poll picks it up
+                            if let Err(e) = notifications.handle_new_notification(uri, &self.api).await {
+                                log::info!("Could not fetch the new notification: {}", e);
+                            }
+                            notifications.notifications.iter().cloned().collect()
+                        } else {
+                            self.fetch_recent_notifications().await
+                        };
+                        self.announce_mentions(&latest);
+                    }
+                    UpdateEvent::PostDeleted { uri } => self.remove_deleted_post(&uri),
+                    UpdateEvent::FollowedPost { uri } => {
+                        log::debug!("Followed account posted {}", uri);
+                        self.realtime_peek_at.get_or_insert_with(|| Instant::now() + REALTIME_PEEK_DELAY);
                     }
-                    UpdateEvent::ConnectionStatus(_status) => {
-                        // Handle connection status...
+                    UpdateEvent::ConnectionStatus(status) => {
+                        self.realtime_status = Some(status);
                     }
                 }
             }
             
             if last_tick.elapsed() >= tick_rate {
                 self.check_notifications().await;
+                // Downloads and tasks come and go without asking for a redraw,
+                // and the spinner has to turn
+                if self.debug_overlay || self.task_manager.is_busy() {
+                    self.dirty = true;
+                }
                 last_tick = Instant::now();
             }
         }
@@ -707,29 +3300,51 @@ impl App {
 
     fn cleanup<B: Backend + Write>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         disable_raw_mode()?;
-        execute!(terminal.backend_mut(), LeaveAlternateScreen,)?;
+        execute!(terminal.backend_mut(), DisableMouseCapture, DisableFocusChange, LeaveAlternateScreen,)?;
         terminal.show_cursor()?;
+        crate::logging::set_tui_active(false);
         Ok(())
     }
 
     pub fn update_status(&mut self) {
-        self.status_line = if self.loading {
-            "Loading...".to_string()
-        } else if let Some(err) = &self.error {
-            err.to_string()
+        self.status_line = if self.tasks.pending_count() > 0 {
+            format!("Saving {} changes... (:cancel to drop the ones not started)", self.tasks.pending_count())
+        } else if let Some(quota) = self.api.rate_limit().filter(|quota| quota.retrying || quota.is_low()) {
+            if quota.retrying {
+                "⏳ Rate limited by the server, retrying...".to_string()
+            } else {
+                format!("⚠ {} of {} API requests left until the rate limit resets", quota.remaining, quota.limit)
+            }
+        } else if !self.outbox.is_empty() {
+            format!("📤 {} posts in the outbox waiting for a connection (:outbox to review)", self.outbox.len())
+        } else if self.recovery.is_some() {
+            "⚠ Skyline didn't exit cleanly last time. :restore to reopen your draft and last view, :restore discard to dismiss".to_string()
         } else {
             let (selected, total) = match self.view_stack.current_view() {
                 View::Timeline(feed) => (feed.selected_index() + 1, feed.posts.len()),
-                View::Thread(thread) => (thread.selected_index() + 1, thread.posts.len()),
+                View::Thread(thread) => (thread.selected_index() + 1, thread.rows.len()),
                 View::AuthorFeed(author_feed) => {(author_feed.selected_index() + 1, author_feed.posts.len())},
-                View::Notifications(notification_view) => {(notification_view.selected_index() + 1, notification_view.notifications.len())},
+                View::Notifications(notification_view) => {(notification_view.selected_index() + 1, notification_view.row_count())},
+                View::Info(info_view) => (info_view.selected_index() + 1, info_view.lines.len()),
+                View::ProfileList(profile_list) => (profile_list.selected_index() + 1, profile_list.profiles.len()),
+                View::Trending(trending) => (trending.selected_index() + 1, trending.topics.len()),
+                View::PostDetail(detail) => (detail.scroll_offset + 1, detail.line_count()),
             };
             
-            format!(
-                "🌆 Press q to quit, j/k to navigate, l to like/unlike, v to view a thread, a to view a profile, and ESC to back out of one {} / {}",
-                selected,
-                total
-            )
+            let matches = self.search_matches();
+            match &self.search {
+                Some(query) => {
+                    match matches.iter().position(|&index| index == selected - 1) {
+                        Some(position) => format!("🔍 \"{}\": match {} of {} · n/N for the next/previous, ESC to stop · {} / {}", query, position + 1, matches.len(), selected, total),
+                        None => format!("🔍 \"{}\": {} matches · n/N to jump to one, ESC to stop · {} / {}", query, matches.len(), selected, total),
+                    }
+                }
+                None => format!(
+                    "🌆 Press q to quit, j/k to navigate, l to like/unlike, v to view a thread, a to view a profile, and ESC to back out of one {} / {}",
+                    selected,
+                    total
+                ),
+            }
         };
     }
 }
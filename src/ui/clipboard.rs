@@ -0,0 +1,19 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::io::{self, Write};
+
+// Copy text to the system clipboard using the OSC 52 escape sequence.
+// This works over SSH and needs no platform clipboard libraries, but the
+// terminal has to allow clipboard writes (most modern terminals do).
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let encoded = STANDARD.encode(text);
+    let mut stdout = io::stdout();
+
+    if std::env::var("TMUX").is_ok() {
+        // tmux only forwards the sequence when wrapped in a passthrough
+        write!(stdout, "\x1bPtmux;\x1b\x1b]52;c;{}\x07\x1b\\", encoded)?;
+    } else {
+        write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+    }
+
+    stdout.flush()
+}
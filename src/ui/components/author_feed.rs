@@ -1,25 +1,33 @@
 use std::{collections::{HashMap, VecDeque}, sync::Arc};
-use atrium_api::{app::bsky::feed::defs::{PostView, PostViewData}, types::Object};
+use atrium_api::app::bsky::feed::defs::{FeedViewPost, PostView, PostViewData};
 use ratatui::{buffer::Buffer, layout::Rect, widgets::{StatefulWidget, Widget}};
-use super::{author_profile::AuthorProfile, images::ImageManager, post::{types::{PostContext, PostState}, Post}, post_list::{PostList, PostListBase}};
+use crate::ui::filters::{CollapsedPosts, FilterPipeline, ViewKind};
+use super::{author_profile::AuthorProfile, images::ImageManager, post::{reason::PostReason, types::{PostContext, PostState}, Post}, post_list::{PostHeights, PostList, PostListBase, SharedHeightCache}};
 
 pub struct AuthorFeed {
     pub profile: AuthorProfile,
-    pub posts: VecDeque<PostView>,
+    // Shared with the matching rendered_posts entry
+    pub posts: VecDeque<Arc<PostView>>,
     pub rendered_posts: Vec<Post>,
-    pub post_heights: HashMap<String, u16>,
+    pub post_heights: PostHeights,
+    pub reasons: HashMap<String, PostReason>,
+    pub filters: FilterPipeline,
+    pub collapsed: CollapsedPosts,
     pub base: PostListBase,
     pub image_manager: Arc<ImageManager>,
 }
 
 impl AuthorFeed {
-    pub fn new(profile: AuthorProfile, feed_data: Vec<Object<PostViewData>>, image_manager: Arc<ImageManager>) -> Self {
+    pub fn new(profile: AuthorProfile, feed_data: Vec<FeedViewPost>, image_manager: Arc<ImageManager>, height_cache: SharedHeightCache) -> Self {
         log::info!("Creating new author feed");
         let mut author_feed = Self {
             profile: profile,
             posts: VecDeque::new(),
             rendered_posts: Vec::new(),
-            post_heights: HashMap::new(),
+            post_heights: PostHeights::new(height_cache),
+            reasons: HashMap::new(),
+            filters: FilterPipeline::for_view(ViewKind::AuthorFeed),
+            collapsed: CollapsedPosts::default(),
             base: PostListBase::new(),
             image_manager: image_manager,
         };
@@ -29,20 +37,71 @@ impl AuthorFeed {
         return author_feed;
     }
 
-    fn process_feed_data(&mut self, feed_data: Vec<Object<PostViewData>>) {
-        for post in feed_data {
-            self.add_post(post.data);
+    // Same as Feed::set_compact
+    pub fn set_compact(&mut self, compact: bool) {
+        if self.base.compact == compact {
+            return;
         }
+        self.base.compact = compact;
+        self.post_heights.clear();
+        self.base.scroll_offset = self.base.selected_index;
     }
 
-    pub fn add_post(&mut self, post: PostViewData) {
-        self.rendered_posts.push(Post::new(
-            post.clone().into(),
+    fn process_feed_data(&mut self, feed_data: Vec<FeedViewPost>) {
+        for feed_post in &feed_data {
+            self.add_feed_post(feed_post);
+        }
+    }
+
+    pub fn add_feed_post(&mut self, feed_post: &FeedViewPost) {
+        let uri = feed_post.post.data.uri.clone();
+        // The pinned post also shows up again in its chronological spot
+        if self.posts.iter().any(|post| post.data.uri == uri) {
+            return;
+        }
+
+        let reason = PostReason::from_feed_post(feed_post);
+        if !self.filters.allows(&feed_post.post.data, reason.as_ref()) {
+            return;
+        }
+        let collapse_rule = self.filters.collapse_rule(&feed_post.post.data);
+        if let Some(rule) = &collapse_rule {
+            self.collapsed.insert(uri.clone(), rule.clone());
+        }
+        let post = Arc::new(feed_post.post.clone());
+        let rendered = Post::new(
+            Arc::clone(&post),
             PostContext {
                 image_manager: self.image_manager.clone(),
                 indent_level: 0,
-            }));
-        self.posts.push_back(post.into());
+            })
+            .with_reason(reason.clone())
+            .with_collapsed(collapse_rule);
+
+        // Keep the pinned post at the top regardless of where it arrives
+        if reason == Some(PostReason::Pinned) {
+            self.rendered_posts.insert(0, rendered);
+            self.posts.push_front(post);
+        } else {
+            self.rendered_posts.push(rendered);
+            self.posts.push_back(post);
+        }
+
+        if let Some(reason) = reason {
+            self.reasons.insert(uri, reason);
+        }
+    }
+
+    // Open or re-fold the selected post if a collapse rule matched it
+    pub fn toggle_collapsed(&mut self) -> Option<bool> {
+        let index = self.base.selected_index;
+        let uri = self.posts.get(index)?.data.uri.clone();
+        let expanded = self.collapsed.toggle(&uri)?;
+        if let Some(rendered) = self.rendered_posts.get_mut(index) {
+            rendered.set_collapsed(self.collapsed.rule_for(&uri).cloned());
+        }
+        self.post_heights.remove(&uri);
+        Some(expanded)
     }
 
 }
@@ -58,7 +117,7 @@ impl PostList for AuthorFeed {
         profile_height + self.posts
             .iter()
             .take(self.base.scroll_offset)
-            .filter_map(|post| self.post_heights.get(&post.uri.to_string()))
+            .filter_map(|post| self.post_heights.get(&post.uri))
             .sum::<u16>()
     }
 
@@ -77,7 +136,7 @@ impl PostList for AuthorFeed {
         // Then check posts
         for (i, post) in self.posts.iter().enumerate().skip(self.base.scroll_offset) {
             let height = self.post_heights
-                .get(&post.data.uri.to_string())
+                .get(&post.data.uri)
                 .copied()
                 .unwrap_or(6);
     
@@ -93,18 +152,32 @@ impl PostList for AuthorFeed {
     }
 
     fn ensure_post_heights(&mut self, area: Rect) {
+        let resized = self.post_heights.set_width(area.width);
+        self.post_heights.retain_loaded(self.posts.iter().map(|post| post.uri.as_str()));
         let posts_to_calculate: Vec<_> = self.posts
             .iter()
-            .filter(|post| !self.post_heights.contains_key(&post.uri.to_string()))
+            .filter(|post| !self.post_heights.contains_key(&post.uri))
             .cloned()
             .collect();
     
         for post in posts_to_calculate {
-            let has_images = super::post::Post::extract_images_from_post(&post.clone().into()).is_some();
-            let height = PostListBase::calculate_post_height(&post.clone().into(), area.width);
+            let has_images = Post::has_images(&post);
+            let reason_height = self.reasons.contains_key(&post.uri) as u16;
+            let height = if self.base.compact {
+                1
+            } else if self.collapsed.rule_for(&post.uri).is_some() {
+                Post::COLLAPSED_HEIGHT
+            } else {
+                self.post_heights.measure(&post, area.width) + reason_height
+            };
             log::info!("Calculated height {} for post {}, has_images: {}", height, post.uri, has_images);
             self.post_heights.insert(post.uri.to_string(), height);
         }
+
+        // Same as Feed
+        if resized {
+            self.select_index(self.base.selected_index);
+        }
     }
     
     fn scroll_down(&mut self) {
@@ -122,7 +195,7 @@ impl PostList for AuthorFeed {
         for (i, post) in self.posts.iter().enumerate().skip(self.base.scroll_offset) {
             if i == next_index {
                 let height = self.post_heights
-                    .get(&post.data.uri.to_string())
+                    .get(&post.data.uri)
                     .copied()
                     .unwrap_or(6);
                     
@@ -134,7 +207,7 @@ impl PostList for AuthorFeed {
                         }
                         if let Some(first_post) = self.posts.get(self.base.scroll_offset) {
                             let first_height = self.post_heights
-                                .get(&first_post.data.uri.to_string())
+                                .get(&first_post.data.uri)
                                 .copied()
                                 .unwrap_or(6);
                             y_position -= first_height;
@@ -145,7 +218,7 @@ impl PostList for AuthorFeed {
                 break;
             }
             let height = self.post_heights
-                .get(&post.data.uri.to_string())
+                .get(&post.data.uri)
                 .copied()
                 .unwrap_or(6);
             y_position += height;
@@ -181,6 +254,33 @@ impl PostList for AuthorFeed {
         self.base.selected_index
     }
 
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.base.select_index(
+            index,
+            &self.posts,
+            |post| self.post_heights
+                .get(&post.data.uri)
+                .copied()
+                .unwrap_or(6)
+        );
+    }
+
     fn get_post(&self, index: usize) -> Option<PostViewData> {
         self.posts.get(index).map(|post| post.data.clone())
     }
@@ -192,6 +292,7 @@ impl Widget for &mut AuthorFeed {
         // Similar to Feed's render, but handle profile at top if scroll_offset is 0
         let mut current_y = area.y;
         self.base.last_known_height = area.height;
+        self.base.last_layout.clear();
         self.ensure_post_heights(area);
 
         if self.base.scroll_offset == 0 {
@@ -226,16 +327,20 @@ impl Widget for &mut AuthorFeed {
                 width: area.width,
                 height: remaining_height.min(post_height),
             };
+            self.base.last_layout.push((i, post_area));
 
             post.render(
                 post_area,
                 buf,
                 &mut PostState {
                     selected: self.base.selected_index == i,
+                    compact: self.base.compact,
                 },
             );
 
             current_y = current_y.saturating_add(post_height);
         }
+
+        self.base.render_scrollbar(area, buf, self.rendered_posts.len());
     }
 }
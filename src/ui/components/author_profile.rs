@@ -79,6 +79,39 @@ impl AuthorProfile {
     pub fn height(&self) -> u16 {
         self.height
     }
+
+    // How we stand with them, from the profile's viewer state: blocks, mutes
+    // and who follows whom
+    fn relationship_badges(&self) -> Vec<Span<'static>> {
+        let Some(viewer) = &self.profile.viewer else {
+            return Vec::new();
+        };
+        let badge = |text: &'static str, color: Color| {
+            Span::styled(format!(" {} ", text), Style::default().fg(Color::Black).bg(color))
+        };
+        let mut badges = Vec::new();
+        if viewer.blocked_by == Some(true) {
+            badges.push(badge("Blocks you", Color::Red));
+        }
+        if viewer.blocking.is_some() {
+            badges.push(badge("Blocked", Color::Red));
+        }
+        if viewer.muted == Some(true) {
+            badges.push(badge("Muted", Color::Yellow));
+        }
+        match (viewer.following.is_some(), viewer.followed_by.is_some()) {
+            (true, true) => badges.push(badge("Mutuals", Color::Green)),
+            (false, true) => badges.push(badge("Follows you", Color::Cyan)),
+            (true, false) => badges.push(badge("Following", Color::Blue)),
+            (false, false) => {}
+        }
+        // A space between each
+        let count = badges.len();
+        badges.into_iter()
+            .enumerate()
+            .flat_map(|(i, badge)| std::iter::once(badge).chain((i + 1 < count).then(|| Span::raw(" "))))
+            .collect()
+    }
 }
 
 impl Widget for &AuthorProfile {
@@ -118,7 +151,7 @@ impl Widget for &AuthorProfile {
             ])
             .split(horizontal_layout[1]);
 
-        // Render name and handle
+        // Render name and handle, with our relationship to them underneath
         let name_line = Line::from(vec![
             Span::styled(
                 self.profile.display_name.clone().unwrap_or_default(),
@@ -146,7 +179,7 @@ impl Widget for &AuthorProfile {
             .wrap(ratatui::widgets::Wrap { trim: true });
 
         block.render(area, buf);
-        Paragraph::new(name_line).render(info_layout[0], buf);
+        Paragraph::new(vec![name_line, Line::from(self.relationship_badges())]).render(info_layout[0], buf);
         Paragraph::new(stats_line).render(info_layout[1], buf);
         bio_widget.render(info_layout[2], buf);
     }
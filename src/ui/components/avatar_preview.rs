@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use anyhow::{Context, Result};
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use super::images::ImageManager;
+
+// Bluesky rejects avatar blobs over 1MB and shows them at well under 1000px
+const MAX_AVATAR_SIZE: u32 = 1000;
+const MAX_AVATAR_BYTES: usize = 1_000_000;
+
+// A local image cropped to the centred square Bluesky will show, waiting for
+// the user to confirm the upload
+pub struct AvatarPreview {
+    pub path: PathBuf,
+    pub jpeg: Vec<u8>,
+    original_size: (u32, u32),
+    cropped_size: u32,
+    // Key of the cropped image in the ImageManager's decoded cache
+    cache_key: String,
+    image_manager: Arc<ImageManager>,
+}
+
+impl AvatarPreview {
+    pub async fn load(path: &Path, image_manager: Arc<ImageManager>) -> Result<Self> {
+        let raw_data = std::fs::read(path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        let image = image::load_from_memory(&raw_data)
+            .with_context(|| format!("{} isn't an image we can read", path.display()))?;
+
+        let original_size = (image.width(), image.height());
+        let cropped = Self::crop_square(&image);
+        let jpeg = Self::encode(&cropped)?;
+
+        let cache_key = format!("avatar-preview:{}", path.display());
+        image_manager
+            .decoded_cache
+            .write()
+            .await
+            .insert(cache_key.clone(), cropped.clone());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            jpeg,
+            original_size,
+            cropped_size: cropped.width(),
+            cache_key,
+            image_manager,
+        })
+    }
+
+    fn crop_square(image: &DynamicImage) -> DynamicImage {
+        let side = image.width().min(image.height());
+        let x = (image.width() - side) / 2;
+        let y = (image.height() - side) / 2;
+        let square = image.crop_imm(x, y, side, side);
+        if side > MAX_AVATAR_SIZE {
+            square.resize_exact(MAX_AVATAR_SIZE, MAX_AVATAR_SIZE, FilterType::Lanczos3)
+        } else {
+            square
+        }
+    }
+
+    // JPEG keeps photos well under the blob limit; step the quality down if not
+    fn encode(image: &DynamicImage) -> Result<Vec<u8>> {
+        let rgb = image.to_rgb8();
+        for quality in [90, 80, 70, 60] {
+            let mut jpeg = Vec::new();
+            JpegEncoder::new_with_quality(&mut jpeg, quality).encode_image(&rgb)?;
+            if jpeg.len() <= MAX_AVATAR_BYTES {
+                return Ok(jpeg);
+            }
+        }
+        Err(anyhow::anyhow!("Image is still over 1MB after compressing"))
+    }
+}
+
+impl Widget for &AvatarPreview {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("🖼️ New avatar");
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(4)])
+            .split(inner_area);
+
+        // Terminal cells are about twice as tall as they are wide
+        let side = chunks[0].height.min(chunks[0].width / 2);
+        let image_area = Rect {
+            x: chunks[0].x + chunks[0].width.saturating_sub(side * 2) / 2,
+            y: chunks[0].y,
+            width: side * 2,
+            height: side,
+        };
+        match self.image_manager.get_or_create_sixel(&self.cache_key, image_area) {
+            Some(sixel) => {
+                let protocol = ratatui_image::protocol::Protocol::Sixel(sixel);
+                ratatui_image::Image::new(&protocol).render(image_area, buf);
+            }
+            None => buf.set_string(image_area.x, image_area.y, "Loading preview...", Style::default().fg(Color::DarkGray)),
+        }
+
+        let (width, height) = self.original_size;
+        let lines = vec![
+            Line::from(Span::raw(self.path.display().to_string())),
+            Line::from(Span::styled(
+                format!("Cropped {}x{} to {}x{} · {} KB", width, height, self.cropped_size, self.cropped_size, self.jpeg.len() / 1024),
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("y/Enter", Style::default().fg(Color::Green)),
+                Span::raw(" upload · "),
+                Span::styled("n/Esc", Style::default().fg(Color::Red)),
+                Span::raw(" cancel"),
+            ]),
+        ];
+        Paragraph::new(lines).render(chunks[1], buf);
+    }
+}
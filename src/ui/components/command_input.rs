@@ -6,13 +6,16 @@ use ratatui::{
     text::{Line, Span},
 };
 
-use std::collections::HashSet;
+use std::{collections::HashSet, path::Path};
+
+use crate::ui::help;
 
 #[derive(Default)]
 pub struct TabCompletion {
     suggestions: Vec<String>,
     current_index: Option<usize>,
-    partial_command: String,
+    // What the last Tab put in, so the next one knows to keep cycling
+    last_inserted: Option<String>,
 }
 
 impl TabCompletion {
@@ -20,18 +23,16 @@ impl TabCompletion {
         Self {
             suggestions: Vec::new(),
             current_index: None,
-            partial_command: String::new(),
+            last_inserted: None,
         }
     }
 
-    fn update_suggestions(&mut self, input: &str, commands: &HashSet<&str>) {
-        self.partial_command = input.to_string();
-        self.suggestions = commands
-            .iter()
-            .filter(|cmd| cmd.starts_with(input))
-            .map(|&cmd| cmd.to_string())
-            .collect();
-        self.suggestions.sort();
+    fn is_cycling(&self, input: &str) -> bool {
+        self.last_inserted.as_deref() == Some(input)
+    }
+
+    fn set_suggestions(&mut self, suggestions: Vec<String>) {
+        self.suggestions = suggestions;
         self.current_index = if self.suggestions.is_empty() {
             None
         } else {
@@ -43,6 +44,7 @@ impl TabCompletion {
         if let Some(index) = self.current_index {
             let suggestion = &self.suggestions[index];
             self.current_index = Some((index + 1) % self.suggestions.len());
+            self.last_inserted = Some(suggestion.clone());
             Some(suggestion)
         } else {
             None
@@ -62,26 +64,15 @@ pub struct CommandInput {
     commands: HashSet<&'static str>,
     tab_completion: TabCompletion,
     pub password_mode: bool,
+    // Argument completions fetched on first use: handles we follow for
+    // :profile and :alert, saved feed names for :feed
+    pub follow_handles: Option<Vec<String>>,
+    pub feed_names: Option<Vec<String>>,
 }
 
 impl CommandInput {
     pub fn new() -> Self {
-        let mut commands = HashSet::new();
-        commands.insert("post");
-        commands.insert("reply");
-        commands.insert("refresh");
-        commands.insert("notifications");
-        commands.insert("timeline");
-        commands.insert("profile");
-        commands.insert("like");
-        commands.insert("repost");
-        // commands.insert("help");
-        // commands.insert("search");
-        // commands.insert("block");
-        // commands.insert("mute");
-        commands.insert("delete");
-        commands.insert("login");
-        commands.insert("logout");
+        let commands = help::COMMANDS.iter().map(|command| command.name).collect();
 
         Self {
             content: String::new(),
@@ -91,60 +82,209 @@ impl CommandInput {
             commands,
             tab_completion: TabCompletion::new(),
             password_mode: false,
+            follow_handles: None,
+            feed_names: None,
         }
     }
 
+    // The command being typed, once there's a space after it and its
+    // arguments are being completed
+    pub fn argument_command(&self) -> Option<&str> {
+        self.content[..self.cursor_position].split_once(' ').map(|(command, _)| command)
+    }
+
+    // After logging out, the next account's follows and feeds are different
+    pub fn forget_completions(&mut self) {
+        self.follow_handles = None;
+        self.feed_names = None;
+    }
+
+    // Complete the command name, or the argument after it. Each further Tab
+    // cycles to the next match
     pub fn handle_tab(&mut self) {
-        // Get the current word being typed
-        let input = self.get_current_word().to_lowercase();
-        
-        // If this is the first tab, update suggestions
-        if self.tab_completion.partial_command != input {
-            self.tab_completion.update_suggestions(&input, &self.commands);
+        let before_cursor = &self.content[..self.cursor_position];
+        let start = before_cursor.find(' ').map_or(0, |space| space + 1);
+        let input = &before_cursor[start..];
+
+        if !self.tab_completion.is_cycling(input) {
+            let suggestions = match before_cursor.split_once(' ') {
+                None => {
+                    let input = input.to_lowercase();
+                    let mut commands: Vec<String> = self.commands
+                        .iter()
+                        .filter(|command| command.starts_with(&input))
+                        .map(|command| command.to_string())
+                        .collect();
+                    commands.sort();
+                    commands
+                }
+                Some((command, _)) => self.argument_suggestions(command, input),
+            };
+            self.tab_completion.set_suggestions(suggestions);
         }
-        
-        // Get next suggestion
+
         if let Some(suggestion) = self.tab_completion.next_suggestion() {
-            // Replace current word with suggestion
-            let (before, _) = self.content.split_at(self.cursor_position - input.len());
-            self.content = format!("{}{}", before, suggestion);
+            self.content = format!("{}{}", &self.content[..start], suggestion);
             self.cursor_position = self.content.len();
         }
     }
 
-    fn get_current_word(&self) -> String {
-        let before_cursor = &self.content[..self.cursor_position];
-        before_cursor
-            .split_whitespace()
-            .last()
-            .unwrap_or("")
-            .to_string()
+    fn argument_suggestions(&self, command: &str, input: &str) -> Vec<String> {
+        let lowered = input.to_lowercase();
+        let matching = |candidates: &[String], prefix: &str| -> Vec<String> {
+            candidates.iter()
+                .filter(|candidate| candidate.to_lowercase().starts_with(prefix))
+                .cloned()
+                .collect()
+        };
+        match command {
+            "profile" | "alert" => {
+                let handles = self.follow_handles.as_deref().unwrap_or_default();
+                matching(handles, lowered.trim_start_matches('@'))
+            }
+            "feed" => {
+                let mut names = vec!["following".to_string(), "discover".to_string()];
+                names.extend(self.feed_names.iter().flatten().cloned());
+                matching(&names, &lowered)
+            }
+            "avatar" => Self::path_suggestions(input),
+            _ => Vec::new(),
+        }
+    }
+
+    // Entries in the directory `input` points into whose names start with
+    // what follows its last slash. Directories get a trailing slash so the
+    // next Tab goes inside
+    fn path_suggestions(input: &str) -> Vec<String> {
+        let (dir_part, name_part) = match input.rfind('/') {
+            Some(slash) => input.split_at(slash + 1),
+            None => ("", input),
+        };
+        let dir = match dir_part {
+            "" => ".".to_string(),
+            _ => match (dir_part.strip_prefix("~/"), std::env::var("HOME")) {
+                (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+                _ => dir_part.to_string(),
+            },
+        };
+        let Ok(entries) = std::fs::read_dir(Path::new(&dir)) else {
+            return Vec::new();
+        };
+
+        let mut suggestions: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                // Hidden files only when asked for
+                if !name.starts_with(name_part) || (name.starts_with('.') && !name_part.starts_with('.')) {
+                    return None;
+                }
+                let slash = if entry.path().is_dir() { "/" } else { "" };
+                Some(format!("{}{}{}", dir_part, name, slash))
+            })
+            .collect();
+        suggestions.sort();
+        suggestions
+    }
+
+    // cursor_position is a byte offset into content, always on a char boundary
+    fn previous_boundary(&self) -> Option<usize> {
+        self.content[..self.cursor_position]
+            .chars()
+            .next_back()
+            .map(|c| self.cursor_position - c.len_utf8())
+    }
+
+    fn next_boundary(&self) -> Option<usize> {
+        self.content[self.cursor_position..]
+            .chars()
+            .next()
+            .map(|c| self.cursor_position + c.len_utf8())
+    }
+
+    // Start of the word before the cursor, skipping any spaces in between
+    fn word_start_before(&self) -> usize {
+        let before = self.content[..self.cursor_position].trim_end();
+        before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(space, c)| space + c.len_utf8())
+    }
+
+    // End of the word after the cursor, skipping any spaces in between
+    fn word_end_after(&self) -> usize {
+        let after = &self.content[self.cursor_position..];
+        let word = after.len() - after.trim_start().len();
+        after[word..]
+            .find(char::is_whitespace)
+            .map_or(self.content.len(), |space| self.cursor_position + word + space)
     }
 
     pub fn insert_char(&mut self, c: char) {
         self.content.insert(self.cursor_position, c);
-        self.cursor_position += 1;
+        self.cursor_position += c.len_utf8();
     }
 
     pub fn delete_char(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
-            self.content.remove(self.cursor_position);
+        if let Some(previous) = self.previous_boundary() {
+            self.content.remove(previous);
+            self.cursor_position = previous;
         }
     }
 
     pub fn move_cursor_left(&mut self) {
-        if self.cursor_position > 0 {
-            self.cursor_position -= 1;
+        if let Some(previous) = self.previous_boundary() {
+            self.cursor_position = previous;
         }
     }
 
     pub fn move_cursor_right(&mut self) {
-        if self.cursor_position < self.content.len() {
-            self.cursor_position += 1;
+        if let Some(next) = self.next_boundary() {
+            self.cursor_position = next;
         }
     }
 
+    // Ctrl+A
+    pub fn move_cursor_home(&mut self) {
+        self.cursor_position = 0;
+    }
+
+    // Ctrl+E
+    pub fn move_cursor_end(&mut self) {
+        self.cursor_position = self.content.len();
+    }
+
+    // Alt+B
+    pub fn move_word_left(&mut self) {
+        self.cursor_position = self.word_start_before();
+    }
+
+    // Alt+F
+    pub fn move_word_right(&mut self) {
+        self.cursor_position = self.word_end_after();
+    }
+
+    // Ctrl+W
+    pub fn delete_word(&mut self) {
+        let start = self.word_start_before();
+        self.content.replace_range(start..self.cursor_position, "");
+        self.cursor_position = start;
+    }
+
+    // Ctrl+U: everything before the cursor, the whole line when it's at the end
+    pub fn delete_to_start(&mut self) {
+        self.content.replace_range(..self.cursor_position, "");
+        self.cursor_position = 0;
+    }
+
+    // Start the line off with `text`, cursor at the end
+    pub fn set_content(&mut self, text: &str) {
+        self.content = text.to_string();
+        self.cursor_position = self.content.len();
+        self.history_position = None;
+    }
+
     pub fn clear(&mut self) {
         self.content.clear();
         self.cursor_position = 0;
@@ -209,24 +349,26 @@ impl StatefulWidget for &CommandInput {
 
         
         // Render content with cursor
-        let content = if self.password_mode {
-            // Show asterisks instead of actual content
-            "*".repeat(self.content.len())
+        let (content, cursor) = if self.password_mode {
+            // Show asterisks instead of actual content, one per character
+            let masked_cursor = self.content[..self.cursor_position].chars().count();
+            ("*".repeat(self.content.chars().count()), masked_cursor)
         } else {
-            self.content.clone()
+            (self.content.clone(), self.cursor_position)
         };
-        let (before_cursor, after_cursor) = content.split_at(self.cursor_position);
-        
+        let (before_cursor, after_cursor) = content.split_at(cursor);
+        let under_cursor = after_cursor.chars().next().map_or(0, char::len_utf8);
+
         let mut spans = vec![
             Span::raw(before_cursor),
             Span::styled(
-                if after_cursor.is_empty() { "_" } else { &after_cursor[..1] },
+                if after_cursor.is_empty() { "_" } else { &after_cursor[..under_cursor] },
                 Style::default().bg(Color::White).fg(Color::Black)
             ),
         ];
 
         if !after_cursor.is_empty() {
-            spans.push(Span::raw(&after_cursor[1..]));
+            spans.push(Span::raw(&after_cursor[under_cursor..]));
         }
 
         // Prefix with ':'
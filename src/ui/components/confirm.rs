@@ -0,0 +1,59 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+// A yes/no question drawn over the current view before something that can't
+// be undone. App keeps what to do on yes beside it
+pub struct ConfirmDialog {
+    pub prompt: String,
+}
+
+impl ConfirmDialog {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        Self { prompt: prompt.into() }
+    }
+
+    // Centred in `area`, wide enough for most prompts on one line
+    fn area(&self, area: Rect) -> Rect {
+        let width = area.width.min(60);
+        let height = area.height.min(6);
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+impl Widget for &ConfirmDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = self.area(area);
+        Clear.render(area, buf);
+        let key = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        let lines = vec![
+            Line::from(self.prompt.as_str()),
+            Line::default(),
+            Line::from(vec![
+                Span::styled("y", key),
+                Span::raw(" yes   "),
+                Span::styled("n", key),
+                Span::raw(" no"),
+            ]),
+        ];
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red))
+                    .title("Are you sure?"),
+            )
+            .render(area, buf);
+    }
+}
@@ -1,60 +1,376 @@
 
-use std::{collections::{HashMap, VecDeque}, sync::Arc};
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::Arc};
 
-use atrium_api::app::bsky::feed::defs::{PostView, PostViewData};
+use atrium_api::{app::bsky::feed::defs::{FeedViewPost, PostView, PostViewData}, types::string::AtIdentifier};
 use ratatui::{buffer::Buffer, layout::Rect, widgets::{Block, Borders, StatefulWidget, Widget}};
 
-use crate::{client::api::API, ui};
+use crate::{client::bluesky_client::BlueskyClient, ui::{self, filters::{CollapsedPosts, FilterPipeline, ViewKind}}};
 use anyhow::Result;
-use super::{images::ImageManager, post::types::PostContext, post_list::{PostList, PostListBase}};
+use super::{images::ImageManager, post::{reason::PostReason, types::PostContext}, post_list::{PostHeights, PostList, PostListBase, SharedHeightCache}};
+
+// Bluesky's own "Discover" feed generator
+pub const DISCOVER_FEED_URI: &str = "at://did:plc:z72i7hdynmk6r22z27h6tvur/app.bsky.feed.generator/whats-hot";
+
+// Where a Feed's pages come from
+#[derive(Clone, Debug)]
+pub enum FeedSource {
+    Timeline,
+    Likes { actor: AtIdentifier },
+    // A feed generator shown in place of the home timeline
+    Generator { uri: String, name: String },
+    // Newest posts tagged #tag
+    Hashtag { tag: String },
+    // Newest posts matching a search, e.g. a trending topic
+    Search { query: String },
+}
+
+impl FeedSource {
+    pub fn discover() -> Self {
+        FeedSource::Generator {
+            uri: DISCOVER_FEED_URI.to_string(),
+            name: "Discover".to_string(),
+        }
+    }
+
+    pub fn view_kind(&self) -> ViewKind {
+        match self {
+            FeedSource::Timeline | FeedSource::Generator { .. } => ViewKind::Timeline,
+            FeedSource::Likes { .. } => ViewKind::Likes,
+            FeedSource::Hashtag { .. } | FeedSource::Search { .. } => ViewKind::Search,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self {
+            FeedSource::Timeline => "🌃 Timeline".to_string(),
+            FeedSource::Likes { .. } => "❤️ Likes".to_string(),
+            FeedSource::Generator { name, .. } => format!("🌃 Timeline · {}", name),
+            FeedSource::Hashtag { tag } => format!("# {}", tag),
+            FeedSource::Search { query } => format!("🔎 {}", query),
+        }
+    }
+}
 
 pub struct Feed {
-    pub posts: VecDeque<PostView>,
+    pub source: FeedSource,
+    // Shared with the matching rendered_posts entry
+    pub posts: VecDeque<Arc<PostView>>,
     pub rendered_posts: Vec<super::post::Post>,
     pub cursor: Option<String>,
-    pub post_heights: HashMap<String, u16>,
+    pub post_heights: PostHeights,
+    pub reasons: HashMap<String, PostReason>,
+    // URIs of every post shown, so one reposted by several people or repeated
+    // across overlapping pages is only listed once
+    seen_uris: HashSet<String>,
     pub status_line: Option<String>,
     pub image_manager: Arc<ImageManager>,
+    pub filters: FilterPipeline,
+    pub collapsed: CollapsedPosts,
+    // Newest post of the last first page we took in, filtered out or not
+    head_uri: Option<String>,
+    // Posts above head_uri found by a background peek, newest first, waiting for `gn`
+    pending_new: Vec<FeedViewPost>,
+    // (index of the page's first post, cursor it was fetched with), oldest page last
+    page_cursors: Vec<(usize, Option<String>)>,
     base: PostListBase,
 }
 
 impl Feed {
-    pub fn new(image_manager: Arc<ImageManager>) -> Self {
+    pub fn new(image_manager: Arc<ImageManager>, height_cache: SharedHeightCache) -> Self {
+        Self::with_source(FeedSource::Timeline, image_manager, height_cache)
+    }
+
+    pub fn with_source(source: FeedSource, image_manager: Arc<ImageManager>, height_cache: SharedHeightCache) -> Self {
         Self {
+            filters: FilterPipeline::for_view(source.view_kind()),
+            collapsed: CollapsedPosts::default(),
+            source,
             posts: VecDeque::new(),
             rendered_posts: Vec::new(),
             cursor: None,
-            post_heights: HashMap::new(),
+            post_heights: PostHeights::new(height_cache),
+            reasons: HashMap::new(),
+            seen_uris: HashSet::new(),
             status_line: Some("".to_string()),
             image_manager,
+            head_uri: None,
+            pending_new: Vec::new(),
+            page_cursors: Vec::new(),
             base: PostListBase::new(),
         }
     }
 
+    // Switch between one-line and full posts. Heights are worked out again,
+    // with the selected post brought to the top so it stays in view
+    pub fn set_compact(&mut self, compact: bool) {
+        if self.base.compact == compact {
+            return;
+        }
+        self.base.compact = compact;
+        self.post_heights.clear();
+        self.base.scroll_offset = self.base.selected_index;
+    }
+
     // Use delegated getters/setters for base fields
     pub fn selected_index(&self) -> usize {
         self.base.selected_index
     }
 
-    pub fn post_heights(&self) -> &HashMap<String, u16> {
+    pub fn post_heights(&self) -> &PostHeights {
         &self.post_heights
     }
 
 
-    pub async fn load_initial_posts(&mut self, api: &mut API) -> Result<()> {
-        let timeline_result = api.get_timeline(None).await;
+    // Keep the rendered post, the raw view and any repost reason in step
+    fn push_feed_post(&mut self, feed_post: &FeedViewPost) {
+        if self.merge_duplicate(feed_post) {
+            return;
+        }
+        if let Some(rendered) = self.render_feed_post(feed_post) {
+            self.seen_uris.insert(feed_post.post.data.uri.clone());
+            self.posts.push_back(Arc::clone(rendered.post()));
+            self.rendered_posts.push(rendered);
+        }
+    }
+
+    fn push_front_feed_post(&mut self, feed_post: &FeedViewPost) -> bool {
+        if self.merge_duplicate(feed_post) {
+            return false;
+        }
+        match self.render_feed_post(feed_post) {
+            Some(rendered) => {
+                self.seen_uris.insert(feed_post.post.data.uri.clone());
+                self.posts.push_front(Arc::clone(rendered.post()));
+                self.rendered_posts.insert(0, rendered);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // A post we already show comes in again. Its reposter joins the existing
+    // entry's repost line rather than the post being listed twice. Returns
+    // false if the post isn't actually here
+    fn merge_duplicate(&mut self, feed_post: &FeedViewPost) -> bool {
+        let uri = &feed_post.post.data.uri;
+        if !self.seen_uris.contains(uri) {
+            return false;
+        }
+        let Some(index) = self.posts.iter().position(|post| &post.data.uri == uri) else {
+            // Removed since, e.g. deleted
+            self.seen_uris.remove(uri);
+            return false;
+        };
+
+        let reason = PostReason::from_feed_post(feed_post);
+        if !matches!(reason, Some(PostReason::Repost { .. })) || !self.filters.allows(&feed_post.post.data, reason.as_ref()) {
+            return true;
+        }
+        if let Some(reason) = reason {
+            match self.reasons.get_mut(uri) {
+                Some(existing) => existing.merge(&reason),
+                None => {
+                    self.reasons.insert(uri.clone(), reason);
+                }
+            }
+        }
+        if let Some(rendered) = self.rendered_posts.get_mut(index) {
+            rendered.set_reason(self.reasons.get(uri).cloned());
+        }
+        // A post that gains a repost line is a line taller
+        self.post_heights.remove(uri);
+        true
+    }
+
+    // None when the filters hide the post
+    fn render_feed_post(&mut self, feed_post: &FeedViewPost) -> Option<super::post::Post> {
+        let reason = PostReason::from_feed_post(feed_post);
+        if !self.filters.allows(&feed_post.post.data, reason.as_ref()) {
+            return None;
+        }
+        if let Some(reason) = &reason {
+            self.reasons.insert(feed_post.post.data.uri.clone(), reason.clone());
+        }
+        let collapse_rule = self.filters.collapse_rule(&feed_post.post.data);
+        if let Some(rule) = &collapse_rule {
+            self.collapsed.insert(feed_post.post.data.uri.clone(), rule.clone());
+        }
+        Some(
+            super::post::Post::new(
+                Arc::new(feed_post.post.clone()),
+                PostContext {
+                    image_manager: self.image_manager.clone(),
+                    indent_level: 0,
+                },
+            )
+            .with_reason(reason)
+            .with_reply_to(super::post::Post::reply_parent_handle(feed_post))
+            .with_collapsed(collapse_rule),
+        )
+    }
+
+    // Open or re-fold the selected post if a collapse rule matched it
+    pub fn toggle_collapsed(&mut self) -> Option<bool> {
+        let index = self.base.selected_index;
+        let uri = self.posts.get(index)?.data.uri.clone();
+        let expanded = self.collapsed.toggle(&uri)?;
+        if let Some(rendered) = self.rendered_posts.get_mut(index) {
+            rendered.set_collapsed(self.collapsed.rule_for(&uri).cloned());
+        }
+        self.post_heights.remove(&uri);
+        Some(expanded)
+    }
+
+    // Record what a background peek at the first page found above what we're
+    // showing. Nothing is shown until load_new_posts
+    pub fn set_peeked_page(&mut self, page: Vec<FeedViewPost>) {
+        let Some(head_uri) = &self.head_uri else {
+            return;
+        };
+        self.pending_new = page
+            .into_iter()
+            .take_while(|feed_post| &feed_post.post.data.uri != head_uri)
+            .filter(|feed_post| !self.seen_uris.contains(&feed_post.post.data.uri))
+            .collect();
+    }
+
+    pub fn new_post_count(&self) -> usize {
+        self.pending_new.len()
+    }
+
+    // Put peeked posts on top, keeping the selected post selected and where it
+    // was on screen. Returns how many were added
+    pub fn load_new_posts(&mut self) -> usize {
+        let pending = std::mem::take(&mut self.pending_new);
+        if let Some(newest) = pending.first() {
+            self.head_uri = Some(newest.post.data.uri.clone());
+        }
+        let was_empty = self.posts.is_empty();
+        let mut added = 0;
+        for feed_post in pending.iter().rev() {
+            if self.push_front_feed_post(feed_post) {
+                added += 1;
+            }
+        }
+        if !was_empty {
+            self.base.selected_index += added;
+            self.base.scroll_offset += added;
+        }
+        if added > 0 {
+            for (start, _) in &mut self.page_cursors {
+                *start += added;
+            }
+            self.page_cursors.insert(0, (0, None));
+        }
+        added
+    }
+
+    // Run the filters over what's already loaded after their rules changed,
+    // dropping whatever they now hide. Returns how many posts were removed
+    pub fn reapply_filters(&mut self) -> usize {
+        let keep: Vec<bool> = self.posts
+            .iter()
+            .map(|post| self.filters.allows(&post.data, self.reasons.get(&post.data.uri)))
+            .collect();
+        let removed = keep.iter().filter(|keep| !**keep).count();
+        if removed == 0 {
+            return 0;
+        }
+
+        // Page starts move up by however many posts before them went
+        for (start, _) in &mut self.page_cursors {
+            *start = keep.iter().take(*start).filter(|keep| **keep).count();
+        }
+        let posts = std::mem::take(&mut self.posts);
+        let rendered_posts = std::mem::take(&mut self.rendered_posts);
+        for ((post, rendered), keep) in posts.into_iter().zip(rendered_posts).zip(keep) {
+            if keep {
+                self.posts.push_back(post);
+                self.rendered_posts.push(rendered);
+            } else {
+                self.seen_uris.remove(&post.data.uri);
+                self.reasons.remove(&post.data.uri);
+            }
+        }
+        removed
+    }
+
+    // Drop a post that's been deleted. Returns whether it was here
+    pub fn remove_post(&mut self, uri: &str) -> bool {
+        let Some(index) = self.posts.iter().position(|post| post.data.uri == uri) else {
+            return false;
+        };
+        self.posts.remove(index);
+        self.rendered_posts.remove(index);
+        self.seen_uris.remove(uri);
+        self.reasons.remove(uri);
+        self.post_heights.remove(uri);
+        for (start, _) in &mut self.page_cursors {
+            if *start > index {
+                *start -= 1;
+            }
+        }
+        true
+    }
+
+    // Cursor of the page the selected post came from, for resuming there later
+    pub fn selected_page_cursor(&self) -> Option<String> {
+        self.page_cursors
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= self.base.selected_index)
+            .and_then(|(_, cursor)| cursor.clone())
+    }
+
+    // Start from a saved page instead of the newest one. Anything newer turns
+    // up through the new posts indicator. Falls back to the first page if the
+    // saved one can't be fetched
+    pub async fn resume_from(&mut self, api: &mut impl BlueskyClient, cursor: Option<String>, selected_uri: Option<&str>) -> Result<()> {
+        if cursor.is_none() {
+            self.load_initial_posts(api).await?;
+        } else {
+            match self.fetch_page(api, cursor.clone()).await {
+                Ok((posts, next_cursor)) => {
+                    self.head_uri = posts.first().map(|feed_post| feed_post.post.data.uri.clone());
+                    self.pending_new.clear();
+                    self.page_cursors.push((self.posts.len(), cursor));
+                    for feed_post in &posts {
+                        self.push_feed_post(feed_post);
+                    }
+                    self.cursor = next_cursor;
+                }
+                Err(e) => {
+                    log::info!("Could not resume the timeline at its saved page: {}", e);
+                    self.load_initial_posts(api).await?;
+                }
+            }
+        }
+
+        if let Some(index) = selected_uri.and_then(|uri| self.posts.iter().position(|post| post.data.uri == uri)) {
+            self.select_index(index);
+        }
+        Ok(())
+    }
+
+    async fn fetch_page(&self, api: &impl BlueskyClient, cursor: Option<String>) -> Result<(Vec<FeedViewPost>, Option<String>)> {
+        match &self.source {
+            FeedSource::Timeline => api.get_timeline(cursor).await,
+            FeedSource::Likes { actor } => api.get_actor_likes(actor.clone(), cursor).await,
+            FeedSource::Generator { uri, .. } => api.get_feed(uri, cursor).await,
+            FeedSource::Hashtag { tag } => api.search_posts(&format!("#{}", tag), cursor).await,
+            FeedSource::Search { query } => api.search_posts(query, cursor).await,
+        }
+    }
+
+    pub async fn load_initial_posts(&mut self, api: &mut impl BlueskyClient) -> Result<()> {
+        let timeline_result = self.fetch_page(api, None).await;
         Ok(match timeline_result {
             Ok((posts, cursor)) => {
-                for feed_post in posts {
-                    self.rendered_posts.push(super::post::Post::new(
-                        feed_post.post.clone(),
-                        PostContext {
-                            image_manager: self.image_manager.clone(),
-                            indent_level: 0,
-                        }
-                    ));
-                    // Extract the PostView from FeedViewPost
-                    self.posts.push_back(feed_post.post.clone());
+                self.head_uri = posts.first().map(|feed_post| feed_post.post.data.uri.clone());
+                self.pending_new.clear();
+                self.page_cursors.push((self.posts.len(), None));
+                for feed_post in &posts {
+                    self.push_feed_post(feed_post);
                 }
                 self.cursor = cursor;
             }
@@ -64,18 +380,12 @@ impl Feed {
         })
     }
 
-    pub async fn scroll(&mut self, api: &API) {
-                match api.get_timeline(self.cursor.clone()).await {
+    pub async fn scroll(&mut self, api: &impl BlueskyClient) {
+                match self.fetch_page(api, self.cursor.clone()).await {
                     Ok((feed_posts, cursor)) => {
-                        for feed_post in feed_posts {
-                            self.rendered_posts.push(super::post::Post::new(
-                                feed_post.post.clone(),
-                                PostContext {
-                                    image_manager: self.image_manager.clone(),
-                                    indent_level: 0,
-                                },
-                            ));
-                            self.posts.push_back(feed_post.post.clone());
+                        self.page_cursors.push((self.posts.len(), self.cursor.clone()));
+                        for feed_post in &feed_posts {
+                            self.push_feed_post(feed_post);
                         }
                         self.cursor = cursor;
                     }
@@ -85,7 +395,39 @@ impl Feed {
                 }
             }
     
-            pub async fn reload_feed(&mut self, api: &mut API) -> Result<()> {
+    fn clear_posts(&mut self) {
+        self.posts.clear();
+        self.rendered_posts.clear();
+        self.page_cursors.clear();
+        self.seen_uris.clear();
+        self.reasons.clear();
+        self.filters.reset_counts();
+    }
+
+    // Show a different source in this view, e.g. Discover in place of the home
+    // timeline. Filters toggled with :filter carry over
+    pub async fn set_source(&mut self, source: FeedSource, api: &mut impl BlueskyClient) -> Result<()> {
+        self.source = source;
+        self.clear_posts();
+        self.cursor = None;
+        self.head_uri = None;
+        self.pending_new.clear();
+        self.post_heights.clear();
+        self.collapsed = CollapsedPosts::default();
+        self.base.selected_index = 0;
+        self.base.scroll_offset = 0;
+        self.load_initial_posts(api).await
+    }
+
+            pub async fn reload_feed(&mut self, api: &mut impl BlueskyClient) -> Result<()> {
+                // Only the timeline can be re-anchored; other sources just start over
+                if !matches!(self.source, FeedSource::Timeline) {
+                    self.clear_posts();
+                    self.base.selected_index = 0;
+                    self.base.scroll_offset = 0;
+                    return self.load_initial_posts(api).await;
+                }
+
                 // Store the URI of the currently selected post if we have one
                 let current_uri = self.posts
                     .get(self.base.selected_index)
@@ -94,39 +436,28 @@ impl Feed {
                 if let Some(anchor_uri) = current_uri {
                     // Clear existing posts but remember our position
                     let selected_index = self.base.selected_index;
-                    self.posts.clear();
-                    self.rendered_posts.clear();
+                    self.clear_posts();
                     
                     // Get the timeline centered around our current post
-                    let params = atrium_api::app::bsky::feed::get_timeline::ParametersData {
-                        algorithm: None,
-                        // We want posts before our current position
-                        cursor: None, // We'll need to implement a way to get the cursor for a specific post
-                        limit: Some(atrium_api::types::LimitedNonZeroU8::MAX),
-                    };
-        
-                    match api.agent.api.app.bsky.feed.get_timeline(params.into()).await {
-                        Ok(response) => {
+                    // We'll need to implement a way to get the cursor for a specific post
+                    match api.get_timeline(None).await {
+                        Ok((feed, cursor)) => {
                             // Find the index of our anchor post in the new response
-                            let anchor_index = response.feed.iter()
+                            let anchor_index = feed.iter()
                                 .position(|post| post.post.data.uri == anchor_uri);
         
                             if let Some(_index) = anchor_index {
+                                self.head_uri = feed.first().map(|feed_post| feed_post.post.data.uri.clone());
+                                self.pending_new.clear();
+                                self.page_cursors.push((0, None));
                                 // Add all posts to our feed
-                                for feed_post in response.feed.clone() {
-                                    self.rendered_posts.push(super::post::Post::new(
-                                        feed_post.post.clone(),
-                                        PostContext {
-                                            image_manager: self.image_manager.clone(),
-                                            indent_level: 0,
-                                        },
-                                    ));
-                                    self.posts.push_back(feed_post.post.clone());
+                                for feed_post in &feed {
+                                    self.push_feed_post(feed_post);
                                 }
         
                                 // Restore our selected position
                                 self.base.selected_index = selected_index;
-                                self.cursor = response.cursor.clone();
+                                self.cursor = cursor;
         
                                 // Pre-fetch the next page if we're close to the end
                                 if self.needs_more_content() {
@@ -137,7 +468,7 @@ impl Feed {
                                 self.load_initial_posts(api).await?;
                             }
                         }
-                        Err(e) => return Err(e.into()),
+                        Err(e) => return Err(e),
                     }
                 } else {
                     // If we don't have a current post, just do a fresh load
@@ -154,7 +485,7 @@ impl PostList for Feed {
         self.posts
             .iter()
             .take(self.base.scroll_offset)
-            .filter_map(|post| self.post_heights.get(&post.data.uri.to_string()))
+            .filter_map(|post| self.post_heights.get(&post.data.uri))
             .sum()
     }
 
@@ -164,7 +495,7 @@ impl PostList for Feed {
 
         for (i, post) in self.posts.iter().enumerate().skip(self.base.scroll_offset) {
             let height = self.post_heights
-                .get(&post.data.uri.to_string())
+                .get(&post.data.uri)
                 .copied()
                 .unwrap_or(6);
 
@@ -180,23 +511,38 @@ impl PostList for Feed {
     }
 
     fn ensure_post_heights(&mut self, area: Rect) {
+        let resized = self.post_heights.set_width(area.width);
+        self.post_heights.retain_loaded(self.posts.iter().map(|post| post.data.uri.as_str()));
         let posts_to_calculate: Vec<_> = self.posts
             .iter()
-            .filter(|post| !self.post_heights.contains_key(&post.data.uri.to_string()))
+            .filter(|post| !self.post_heights.contains_key(&post.data.uri))
             .cloned()
             .collect();
 
         for post in posts_to_calculate {
-            let height = PostListBase::calculate_post_height(&post, area.width);
+            // Reposts get an extra line for the attribution
+            let reason_height = self.reasons.contains_key(&post.data.uri) as u16;
+            let height = if self.base.compact {
+                1
+            } else if self.collapsed.rule_for(&post.data.uri).is_some() {
+                super::post::Post::COLLAPSED_HEIGHT
+            } else {
+                self.post_heights.measure(&post, area.width) + reason_height
+            };
             self.post_heights.insert(post.data.uri.to_string(), height);
         }
+
+        // Posts may have grown taller, so make sure the selection is still on screen
+        if resized {
+            self.select_index(self.base.selected_index);
+        }
     }
 
     fn scroll_down(&mut self) {
         self.base.handle_scroll_down(
             &self.posts,
             |post| self.post_heights
-                .get(&post.data.uri.to_string())
+                .get(&post.data.uri)
                 .copied()
                 .unwrap_or(6)
         );
@@ -214,6 +560,33 @@ impl PostList for Feed {
         self.base.selected_index
     }
 
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.base.select_index(
+            index,
+            &self.posts,
+            |post| self.post_heights
+                .get(&post.data.uri)
+                .copied()
+                .unwrap_or(6)
+        );
+    }
+
     fn get_post(&self, index: usize) -> Option<PostViewData> {
         self.posts.get(index).map(|post| post.data.clone())
     }
@@ -222,12 +595,18 @@ impl PostList for Feed {
 
 impl Widget for &mut Feed {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.new_post_count() {
+            0 => self.source.title(),
+            1 => format!("{} · 1 new post — press gn to load", self.source.title()),
+            count => format!("{} · {} new posts — press gn to load", self.source.title(), count),
+        };
         let block = Block::default()
         .borders(Borders::ALL)
-        .title("🌃 Timeline");
+        .title(title);
         let inner_area = block.inner(area);
         // info!("Feed render area: {:?}", area);
         self.base.last_known_height = inner_area.height;
+        self.base.last_layout.clear();
         self.ensure_post_heights(inner_area);
 
         let mut current_y = inner_area.y;
@@ -255,16 +634,20 @@ impl Widget for &mut Feed {
 
             // info!("Post {} area: {:?} (clipped from original height: {})",
             //   i, post_area, post_height);
+            self.base.last_layout.push((i, post_area));
 
             post.render(
                 post_area,
                 buf,
                 &mut ui::components::post::types::PostState {
                     selected: self.base.selected_index == i,
+                    compact: self.base.compact,
                 },
             );
 
             current_y = current_y.saturating_add(post_height);
         }
+
+        self.base.render_scrollbar(area, buf, self.rendered_posts.len());
     }
 }
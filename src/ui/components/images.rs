@@ -10,9 +10,42 @@ use ratatui::style::{self, Style};
 use ratatui::widgets::{Block, Borders, Widget};
 use ratatui_image::{protocol, Image};
 use reqwest;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+// How many images each cache keeps, and roughly how much memory decoded
+// images and sixels may take. Whichever limit is hit first evicts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageCacheSettings {
+    // Downloaded files, still compressed
+    pub raw: usize,
+    pub decoded: usize,
+    pub sixel: usize,
+    pub decoded_budget_mb: usize,
+    pub sixel_budget_mb: usize,
+}
+
+impl Default for ImageCacheSettings {
+    fn default() -> Self {
+        Self {
+            raw: 200,
+            decoded: 100,
+            sixel: 50,
+            decoded_budget_mb: 256,
+            sixel_budget_mb: 64,
+        }
+    }
+}
+
+fn capacity(entries: usize) -> NonZeroUsize {
+    NonZeroUsize::new(entries).unwrap_or(NonZeroUsize::MIN)
+}
+
 #[derive(Hash, PartialEq, Eq)]
 pub struct SixelCacheKey {
     url: String,
@@ -31,13 +64,18 @@ impl SixelCacheKey {
 }
 
 pub struct SixelCache {
-    cache: LruCache<SixelCacheKey, protocol::sixel::Sixel>,
+    // Each sixel with the bytes it was estimated at
+    cache: LruCache<SixelCacheKey, (protocol::sixel::Sixel, usize)>,
+    bytes: usize,
+    budget: usize,
 }
 
 impl SixelCache {
-    pub fn new() -> Self {
+    pub fn new(settings: &ImageCacheSettings) -> Self {
         Self {
-            cache: LruCache::new(50.try_into().unwrap()),
+            cache: LruCache::new(capacity(settings.sixel)),
+            bytes: 0,
+            budget: settings.sixel_budget_mb * 1024 * 1024,
         }
     }
 
@@ -45,19 +83,43 @@ impl SixelCache {
         &mut self,
         cache_key: &SixelCacheKey,
     ) -> Option<&ratatui_image::protocol::sixel::Sixel> {
-        self.cache.get(cache_key)
+        self.cache.get(cache_key).map(|(sixel, _)| sixel)
     }
 
     pub fn contains(&self, cache_key: &SixelCacheKey) -> bool {
         self.cache.peek(cache_key).is_some()
     }
 
+    // The sixel's encoded data isn't exposed, so `bytes` is an estimate
     pub fn insert(
         &mut self,
         cache_key: SixelCacheKey,
         data: ratatui_image::protocol::sixel::Sixel,
+        bytes: usize,
     ) {
-        self.cache.put(cache_key, data);
+        self.bytes += bytes;
+        if let Some((_, (_, old_bytes))) = self.cache.push(cache_key, (data, bytes)) {
+            self.bytes -= old_bytes;
+        }
+        // Always keep the newest, however big
+        while self.bytes > self.budget && self.cache.len() > 1 {
+            match self.cache.pop_lru() {
+                Some((_, (_, old_bytes))) => self.bytes -= old_bytes,
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
     }
 }
 
@@ -69,9 +131,9 @@ pub struct ImageCache {
 }
 
 impl ImageCache {
-    pub fn new() -> Self {
+    pub fn new(settings: &ImageCacheSettings) -> Self {
         Self {
-            cache: LruCache::new(200.try_into().unwrap()),
+            cache: LruCache::new(capacity(settings.raw)),
         }
     }
 
@@ -87,6 +149,14 @@ impl ImageCache {
     pub fn insert(&mut self, url: String, data: Vec<u8>) {
         self.cache.put(url, data);
     }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
 }
 
 // Thread-safe wrapper for the cache
@@ -95,12 +165,17 @@ pub type SharedImageCache = Arc<RwLock<ImageCache>>;
 // Cache for decoded images
 pub struct DecodedImageCache {
     cache: LruCache<String, DynamicImage>,
+    // Pixel data held, which is nearly all of a decoded image
+    bytes: usize,
+    budget: usize,
 }
 
 impl DecodedImageCache {
-    pub fn new() -> Self {
+    pub fn new(settings: &ImageCacheSettings) -> Self {
         Self {
-            cache: LruCache::new(100.try_into().unwrap()),
+            cache: LruCache::new(capacity(settings.decoded)),
+            bytes: 0,
+            budget: settings.decoded_budget_mb * 1024 * 1024,
         }
     }
 
@@ -109,50 +184,297 @@ impl DecodedImageCache {
     }
 
     pub fn insert(&mut self, url: String, image: DynamicImage) {
-        self.cache.put(url, image);
+        self.bytes += image.as_bytes().len();
+        if let Some((_, old)) = self.cache.push(url, image) {
+            self.bytes -= old.as_bytes().len();
+        }
+        // Same as SixelCache
+        while self.bytes > self.budget && self.cache.len() > 1 {
+            match self.cache.pop_lru() {
+                Some((_, old)) => self.bytes -= old.as_bytes().len(),
+                None => break,
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bytes
     }
 }
 
 // Thread-safe wrapper
 pub type SharedDecodedImageCache = Arc<RwLock<DecodedImageCache>>;
 
+// Lookups in one cache, for the debug overlay
+#[derive(Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounters {
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // "93% of 1204", or "-" before the first lookup
+    pub fn summary(&self) -> String {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let total = hits + self.misses.load(Ordering::Relaxed);
+        match total {
+            0 => "-".to_string(),
+            total => format!("{}% of {}", hits * 100 / total, total),
+        }
+    }
+}
+
 // Image downloader/manager
 pub struct ImageManager {
     client: reqwest::Client,
     pub raw_cache: SharedImageCache,
     pub decoded_cache: SharedDecodedImageCache,
     pub sixel_cache: SharedSixelCache,
+    // URL -> reason for images we downloaded but could not decode
+    pub decode_failures: Arc<RwLock<HashMap<String, String>>>,
+    // Set when a background decode or sixel encode finishes, so the app
+    // knows the screen is worth drawing again
+    ready: Arc<AtomicBool>,
+    raw_lookups: CacheCounters,
+    decoded_lookups: CacheCounters,
+    sixel_lookups: CacheCounters,
+    downloads_in_flight: AtomicUsize,
     picker: ratatui_image::picker::Picker,
+    // What the terminal reported before we forced Sixel, kept for :diagnose-images
+    detected_protocol: ratatui_image::picker::ProtocolType,
+    query_succeeded: bool,
+}
+
+// Snapshot of the terminal's graphics capabilities, shown by :diagnose-images
+pub struct ImageDiagnostics {
+    pub term: String,
+    pub term_program: String,
+    pub query_succeeded: bool,
+    pub detected_protocol: ratatui_image::picker::ProtocolType,
+    pub font_size: (u16, u16),
+    pub sixel_support: bool,
+    pub kitty_support: bool,
+    pub iterm2_support: bool,
+    pub test_render: std::result::Result<(), String>,
+    pub cache_sizes: (usize, usize, usize),
+    // Approximate bytes held by the decoded and sixel caches
+    pub cache_bytes: (usize, usize),
+    pub decode_failures: usize,
+}
+
+impl ImageDiagnostics {
+    pub fn lines(&self) -> Vec<String> {
+        let yes_no = |supported: bool| if supported { "yes" } else { "no" };
+        let mut lines = vec![
+            format!("Terminal:            {} (TERM={})", self.term_program, self.term),
+            format!(
+                "Capability query:    {}",
+                if self.query_succeeded { "answered" } else { "no response, using fallback font size" }
+            ),
+            format!("Detected protocol:   {:?}", self.detected_protocol),
+            "Active protocol:     Sixel (forced)".to_string(),
+            format!("Sixel support:       {}", yes_no(self.sixel_support)),
+            format!("Kitty graphics:      {}", yes_no(self.kitty_support)),
+            format!("iTerm2 inline:       {}", yes_no(self.iterm2_support)),
+            format!("Font cell size:      {}x{} px", self.font_size.0, self.font_size.1),
+            match &self.test_render {
+                Ok(()) => "Test render:         ok".to_string(),
+                Err(e) => format!("Test render:         failed ({})", e),
+            },
+            format!(
+                "Cached images:       {} raw · {} decoded (~{} MB) · {} sixel (~{} MB)",
+                self.cache_sizes.0,
+                self.cache_sizes.1,
+                self.cache_bytes.0 / (1024 * 1024),
+                self.cache_sizes.2,
+                self.cache_bytes.1 / (1024 * 1024),
+            ),
+            format!("Decode failures:     {}", self.decode_failures),
+            String::new(),
+        ];
+
+        if !self.sixel_support {
+            lines.push("Your terminal does not appear to support Sixel, so images will show as placeholders.".to_string());
+            lines.push("Try a Sixel-capable terminal such as WezTerm, foot, mlterm or xterm -ti vt340.".to_string());
+        } else if !self.query_succeeded {
+            lines.push("The terminal did not answer the font size query; images may be scaled incorrectly.".to_string());
+        } else if self.test_render.is_err() {
+            lines.push("Sixel encoding failed; check skyline.log for details.".to_string());
+        } else {
+            lines.push("Everything looks fine. Images that never load may be failing to download.".to_string());
+        }
+
+        lines
+    }
 }
 
 impl ImageManager {
-    pub fn new() -> Self {
-        let mut picker = ratatui_image::picker::Picker::from_query_stdio()
-            .unwrap_or_else(|_| ratatui_image::picker::Picker::from_fontsize((16, 32)));
+    pub fn new(cache_settings: &ImageCacheSettings) -> Self {
+        let (mut picker, query_succeeded) = match ratatui_image::picker::Picker::from_query_stdio() {
+            Ok(picker) => (picker, true),
+            Err(_) => (ratatui_image::picker::Picker::from_fontsize((16, 32)), false),
+        };
+        let detected_protocol = picker.protocol_type();
 
         picker.set_protocol_type(ratatui_image::picker::ProtocolType::Sixel);
         picker.set_background_color(Some(image::Rgb::<u8>([0, 0, 0])));
 
         Self {
             client: reqwest::Client::new(),
-            raw_cache: Arc::new(RwLock::new(ImageCache::new())),
-            decoded_cache: Arc::new(RwLock::new(DecodedImageCache::new())),
-            sixel_cache: Arc::new(RwLock::new(SixelCache::new())),
+            raw_cache: Arc::new(RwLock::new(ImageCache::new(cache_settings))),
+            decoded_cache: Arc::new(RwLock::new(DecodedImageCache::new(cache_settings))),
+            sixel_cache: Arc::new(RwLock::new(SixelCache::new(cache_settings))),
+            decode_failures: Arc::new(RwLock::new(HashMap::new())),
+            ready: Arc::new(AtomicBool::new(false)),
+            raw_lookups: CacheCounters::default(),
+            decoded_lookups: CacheCounters::default(),
+            sixel_lookups: CacheCounters::default(),
+            downloads_in_flight: AtomicUsize::new(0),
             picker,
+            detected_protocol,
+            query_succeeded,
+        }
+    }
+
+    // One-line result of the startup terminal query, for the splash screen
+    pub fn probe_summary(&self) -> String {
+        if self.query_succeeded {
+            format!("detected {:?}, using Sixel", self.detected_protocol)
+        } else {
+            "no response, using defaults".to_string()
+        }
+    }
+
+    pub fn diagnose(&self) -> ImageDiagnostics {
+        use ratatui_image::picker::ProtocolType;
+
+        let term = std::env::var("TERM").unwrap_or_default();
+        let term_program = std::env::var("TERM_PROGRAM").unwrap_or_else(|_| "unknown".to_string());
+        let term_lower = term.to_lowercase();
+        let program_lower = term_program.to_lowercase();
+
+        // The query result is authoritative when available, env vars fill the gaps
+        let kitty_support = self.detected_protocol == ProtocolType::Kitty
+            || std::env::var("KITTY_WINDOW_ID").is_ok()
+            || term_lower.contains("kitty")
+            || program_lower == "wezterm"
+            || program_lower == "ghostty";
+        let iterm2_support = self.detected_protocol == ProtocolType::Iterm2
+            || std::env::var("ITERM_SESSION_ID").is_ok()
+            || program_lower == "iterm.app"
+            || program_lower == "wezterm";
+        let sixel_support = self.detected_protocol == ProtocolType::Sixel
+            || program_lower == "wezterm"
+            || program_lower == "iterm.app"
+            || term_lower.contains("foot")
+            || term_lower.contains("mlterm")
+            || term_lower.contains("sixel");
+
+        let cache_sizes = (
+            self.raw_cache.try_read().map(|c| c.len()).unwrap_or(0),
+            self.decoded_cache.try_read().map(|c| c.len()).unwrap_or(0),
+            self.sixel_cache.try_read().map(|c| c.len()).unwrap_or(0),
+        );
+        let cache_bytes = (
+            self.decoded_cache.try_read().map(|c| c.bytes()).unwrap_or(0),
+            self.sixel_cache.try_read().map(|c| c.bytes()).unwrap_or(0),
+        );
+
+        ImageDiagnostics {
+            term,
+            term_program,
+            query_succeeded: self.query_succeeded,
+            detected_protocol: self.detected_protocol,
+            font_size: self.picker.font_size(),
+            sixel_support,
+            kitty_support,
+            iterm2_support,
+            test_render: self.test_render(),
+            cache_sizes,
+            cache_bytes,
+            decode_failures: self.decode_failures.try_read().map(|f| f.len()).unwrap_or(0),
+        }
+    }
+
+    // Encode and draw a small solid image the same way post images are drawn
+    fn test_render(&self) -> std::result::Result<(), String> {
+        let mut picker = ratatui_image::picker::Picker::from_fontsize(self.picker.font_size());
+        picker.set_protocol_type(ratatui_image::picker::ProtocolType::Sixel);
+        picker.set_background_color(Some(image::Rgb::<u8>([0, 0, 0])));
+
+        let test_image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(32, 32, image::Rgb([0, 128, 255])));
+        let area = Rect::new(0, 0, 4, 2);
+
+        match picker.new_protocol(test_image, area, ratatui_image::Resize::Fit(None)) {
+            Ok(protocol) => {
+                if !matches!(protocol, protocol::Protocol::Sixel(_)) {
+                    return Err("picker did not produce a Sixel protocol".to_string());
+                }
+                let mut buf = Buffer::empty(area);
+                Image::new(&protocol).render(area, &mut buf);
+                Ok(())
+            }
+            Err(e) => Err(format!("{:?}", e)),
         }
     }
 
+    // Cache sizes, hit rates and downloads, for the debug overlay. Cheap
+    // enough to run every frame, unlike diagnose
+    pub fn stats_lines(&self) -> Vec<String> {
+        let mb = |bytes: usize| bytes / (1024 * 1024);
+        let raw = self.raw_cache.try_read().map(|c| c.len().to_string()).unwrap_or_else(|_| "?".to_string());
+        let decoded = self.decoded_cache
+            .try_read()
+            .map(|c| format!("{} (~{} MB)", c.len(), mb(c.bytes())))
+            .unwrap_or_else(|_| "?".to_string());
+        let sixel = self.sixel_cache
+            .try_read()
+            .map(|c| format!("{} (~{} MB)", c.len(), mb(c.bytes())))
+            .unwrap_or_else(|_| "?".to_string());
+        vec![
+            format!("Raw images     {} · hits {}", raw, self.raw_lookups.summary()),
+            format!("Decoded        {} · hits {}", decoded, self.decoded_lookups.summary()),
+            format!("Sixels         {} · hits {}", sixel, self.sixel_lookups.summary()),
+            format!("Downloading    {}", self.downloads_in_flight.load(Ordering::Relaxed)),
+        ]
+    }
+
+    // Whether any image became ready to draw since the last call
+    pub fn take_ready(&self) -> bool {
+        self.ready.swap(false, Ordering::Relaxed)
+    }
+
     // get_image for downloading
     pub async fn get_image(&self, url: &str) -> Result<Vec<u8>> {
         {
             let mut cache = self.raw_cache.write().await;
-            if let Some(data) = cache.get(url) {
+            let cached = cache.get(url);
+            self.raw_lookups.record(cached.is_some());
+            if let Some(data) = cached {
                 return Ok(data.clone());
             }
         }
 
-        let response = self.client.get(url).send().await?;
-        let image_data = response.bytes().await?.to_vec();
+        self.downloads_in_flight.fetch_add(1, Ordering::Relaxed);
+        let download = async {
+            let response = self.client.get(url).send().await?;
+            anyhow::Ok(response.bytes().await?.to_vec())
+        }.await;
+        self.downloads_in_flight.fetch_sub(1, Ordering::Relaxed);
+        let image_data = download?;
 
         self.raw_cache
             .write()
@@ -167,8 +489,10 @@ impl ImageManager {
 
         // Try cache first
         if let Ok(mut cache) = self.sixel_cache.try_write() {
-            if let Some(sixel) = cache.get(&key).cloned() {
-                return Some(sixel);
+            let cached = cache.get(&key).cloned();
+            self.sixel_lookups.record(cached.is_some());
+            if cached.is_some() {
+                return cached;
             }
         }
 
@@ -176,6 +500,7 @@ impl ImageManager {
         if let Ok(mut cache) = self.decoded_cache.try_write() {
             if let Some(decoded) = cache.get(url).cloned() {
                 let sixel_cache = self.sixel_cache.clone();
+                let ready = self.ready.clone();
                 let font_size = self.picker.font_size();
 
                 tokio::spawn(async move {
@@ -187,8 +512,12 @@ impl ImageManager {
                     match picker.new_protocol(decoded, area, ratatui_image::Resize::Fit(Some(ratatui_image::FilterType::Triangle))) {
                         Ok(protocol) => {
                             if let protocol::Protocol::Sixel(sixel) = protocol {
+                                // About a byte for each pixel of the area
+                                let bytes = area.width as usize * font_size.0 as usize
+                                    * area.height as usize * font_size.1 as usize;
                                 if let Ok(mut cache) = sixel_cache.try_write() {
-                                    cache.insert(key, sixel);
+                                    cache.insert(key, sixel, bytes);
+                                    ready.store(true, Ordering::Relaxed);
                                 }
                             }
                         }
@@ -203,23 +532,77 @@ impl ImageManager {
 
     pub async fn get_decoded_image(&self, url: &str) -> Result<Option<DynamicImage>> {
         // Check decoded cache first
-        if let Some(decoded) = self.decoded_cache.write().await.get(url) {
-            return Ok(Some(decoded.clone()));
+        let cached = self.decoded_cache.write().await.get(url).cloned();
+        self.decoded_lookups.record(cached.is_some());
+        if cached.is_some() {
+            return Ok(cached);
         }
 
         // If not in decoded cache, try to load and decode
-        if let Ok(raw_data) = self.get_image(url).await {
-            if let Ok(decoded) = load_from_memory(&raw_data) {
+        let raw_data = match self.get_image(url).await {
+            Ok(raw_data) => raw_data,
+            Err(e) => {
+                info!("Failed to load image for {}: {}", url, e);
+                return Ok(None);
+            }
+        };
+
+        let decoded = match Self::decode(&raw_data) {
+            Ok(decoded) => Ok(decoded),
+            // Formats we can't decode (e.g. AVIF) can be re-requested as JPEG
+            Err(reason) => match Self::jpeg_fallback_url(url) {
+                Some(fallback_url) => {
+                    info!("Could not decode {} ({}), trying {}", url, reason, fallback_url);
+                    match self.get_image(&fallback_url).await {
+                        Ok(fallback_data) => Self::decode(&fallback_data).map_err(|_| reason),
+                        Err(_) => Err(reason),
+                    }
+                }
+                None => Err(reason),
+            },
+        };
+
+        match decoded {
+            Ok(decoded) => {
                 self.decoded_cache
                     .write()
                     .await
                     .insert(url.to_string(), decoded.clone());
-                return Ok(Some(decoded));
+                self.ready.store(true, Ordering::Relaxed);
+                Ok(Some(decoded))
+            }
+            Err(reason) => {
+                info!("Failed to decode image for {}: {}", url, reason);
+                self.decode_failures.write().await.insert(url.to_string(), reason);
+                Ok(None)
             }
         }
+    }
+
+    // Decode raw bytes, describing the detected format on failure
+    fn decode(raw_data: &[u8]) -> std::result::Result<DynamicImage, String> {
+        load_from_memory(raw_data).map_err(|e| {
+            let format = image::guess_format(raw_data)
+                .map(|format| format!("{:?}", format).to_uppercase())
+                .unwrap_or_else(|_| "unknown format".to_string());
+            format!("{}: {}", format, e)
+        })
+    }
+
+    // Bluesky's CDN serves any image as JPEG when asked with the @jpeg suffix
+    fn jpeg_fallback_url(url: &str) -> Option<String> {
+        if !url.contains("cdn.bsky.app") {
+            return None;
+        }
+        let (base, format) = url.rsplit_once('@')?;
+        if format == "jpeg" {
+            return None;
+        }
+        Some(format!("{}@jpeg", base))
+    }
 
-        info!("Failed to load/decode image for {}", url);
-        Ok(None)
+    pub fn decode_failure(&self, url: &str) -> Option<String> {
+        self.decode_failures.try_read().ok()?.get(url).cloned()
     }
 }
 
@@ -0,0 +1,71 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+// A simple scrollable view for multi-line reports (diagnostics, status pages, etc)
+pub struct InfoView {
+    pub title: String,
+    pub lines: Vec<Line<'static>>,
+    pub scroll_offset: usize,
+    last_known_height: u16,
+}
+
+impl InfoView {
+    pub fn new(title: impl Into<String>, lines: Vec<Line<'static>>) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            scroll_offset: 0,
+            last_known_height: 0,
+        }
+    }
+
+    pub fn from_strings(title: impl Into<String>, lines: Vec<String>) -> Self {
+        Self::new(title, lines.into_iter().map(Line::from).collect())
+    }
+
+    pub fn scroll_down(&mut self) {
+        let visible = self.last_known_height as usize;
+        if self.scroll_offset + visible < self.lines.len() {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn page_size(&self) -> usize {
+        (self.last_known_height as usize).max(1)
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.scroll_offset
+    }
+}
+
+impl Widget for &mut InfoView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title(self.title.clone());
+
+        let inner_area = block.inner(area);
+        self.last_known_height = inner_area.height;
+        block.render(area, buf);
+
+        let visible_lines: Vec<Line> = self.lines
+            .iter()
+            .skip(self.scroll_offset)
+            .take(inner_area.height as usize)
+            .cloned()
+            .collect();
+
+        Paragraph::new(visible_lines).render(inner_area, buf);
+    }
+}
@@ -0,0 +1,105 @@
+use atrium_api::{app::bsky::graph::defs::ListView, types::string::Did};
+use crate::client::bluesky_client::MODERATION_LIST;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+// Our lists drawn over an author's profile, ticked where they're on one.
+// Enter adds them to the selected list or takes them off it
+pub struct ListPicker {
+    pub did: Did,
+    pub handle: String,
+    pub lists: Vec<ListView>,
+    // The listitem record for each list they're on, in step with `lists`
+    pub items: Vec<Option<String>>,
+    pub selected: usize,
+}
+
+impl ListPicker {
+    pub fn new(did: Did, handle: String, lists: Vec<ListView>, items: Vec<Option<String>>) -> Self {
+        Self { did, handle, lists, items, selected: 0 }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.selected + 1 < self.lists.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    // The highlighted list and their listitem record on it, if any
+    pub fn selected(&self) -> Option<(&ListView, Option<&str>)> {
+        let list = self.lists.get(self.selected)?;
+        Some((list, self.items[self.selected].as_deref()))
+    }
+
+    pub fn set_selected_item(&mut self, record: Option<String>) {
+        if let Some(item) = self.items.get_mut(self.selected) {
+            *item = record;
+        }
+    }
+
+    // Centred in `area`, a row per list with the key hints under them
+    fn area(&self, area: Rect) -> Rect {
+        let width = area.width.min(60);
+        let height = area.height.min(self.lists.len() as u16 + 4);
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        }
+    }
+}
+
+impl Widget for &ListPicker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = self.area(area);
+        Clear.render(area, buf);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Lists for @{}", self.handle));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Keep the selection in sight when there are more lists than rows
+        let rows = inner.height.saturating_sub(1) as usize;
+        let first = (self.selected + 1).saturating_sub(rows);
+        let mut lines: Vec<Line> = self.lists
+            .iter()
+            .zip(&self.items)
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .map(|(i, (list, item))| {
+                let tick = if item.is_some() { "[x]" } else { "[ ]" };
+                let style = if i == self.selected {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![Span::styled(format!("{} {}", tick, list.name), style)];
+                if list.purpose == MODERATION_LIST {
+                    spans.push(Span::styled(" (moderation)", Style::default().fg(Color::DarkGray)));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        let key = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+        lines.push(Line::from(vec![
+            Span::styled("Enter", key),
+            Span::raw(" add / remove   "),
+            Span::styled("Esc", key),
+            Span::raw(" close"),
+        ]));
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
@@ -9,3 +9,13 @@ pub mod author_profile;
 pub mod author_feed;
 pub mod post_composer;
 pub mod login;
+pub mod info_view;
+pub mod splash;
+pub mod profile_list;
+pub mod trending;
+pub mod avatar_preview;
+pub mod post_detail;
+pub mod confirm;
+pub mod toasts;
+pub mod skeleton;
+pub mod list_picker;
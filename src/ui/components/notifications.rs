@@ -1,5 +1,5 @@
 // In src/ui/components/notifications.rs
-use std::{collections::{HashMap, VecDeque}, sync::Arc};
+use std::{collections::{HashMap, HashSet, VecDeque}, sync::Arc};
 use atrium_api::{app::bsky::{feed::defs::PostViewData, notification::list_notifications::NotificationData}, types::LimitedNonZeroU8};
 use ratatui::{
     buffer::Buffer,
@@ -7,15 +7,27 @@ use ratatui::{
     style::{Color, Style},
     widgets::{Block, Borders, Widget},
 };
-use crate::{client::api::API, ui::views::{View, ViewStack}};
+use crate::{client::{author_alerts::ALERT_REASON, bluesky_client::BlueskyClient}, ui::{emoji, views::{View, ViewStack}}};
 use anyhow::Result;
 
 use super::{images::ImageManager, post_list::{PostList, PostListBase}};
 
+// A line in the view: one notification, or a run of likes, reposts or
+// follows about the same thing shown as one until it's expanded
+pub struct NotificationRow {
+    // Indices into `notifications`, newest first
+    members: Vec<usize>,
+}
+
 pub struct NotificationView {
     pub notifications: VecDeque<NotificationData>,
     pub notification_heights: HashMap<String, u16>,
     pub image_manager: Arc<ImageManager>,
+    rows: VecDeque<NotificationRow>,
+    // Group keys the user has opened up into individual rows
+    expanded: HashSet<String>,
+    // Text of the posts likes, reposts, replies and quotes are about, by URI
+    subject_previews: HashMap<String, String>,
     base: PostListBase,
 }
 
@@ -25,22 +37,153 @@ impl NotificationView {
             notifications: VecDeque::new(),
             notification_heights: HashMap::new(),
             image_manager,
+            rows: VecDeque::new(),
+            expanded: HashSet::new(),
+            subject_previews: HashMap::new(),
             base: PostListBase::new(),
         }
     }
 
-    pub async fn load_notifications(&mut self, api: &mut crate::client::api::API) -> anyhow::Result<()> {
-        let params = atrium_api::app::bsky::notification::list_notifications::Parameters {
-            data: atrium_api::app::bsky::notification::list_notifications::ParametersData {
-                cursor: None,
-                limit: Some(LimitedNonZeroU8::MAX),
-                seen_at: None,
-                priority: None,
-            },
-            extra_data: ipld_core::ipld::Ipld::Null,
+    // Notifications with the same key next to each other are grouped. Replies,
+    // mentions and quotes each have their own text, so they stay separate
+    fn group_key(notification: &NotificationData) -> Option<String> {
+        match notification.reason.as_str() {
+            "like" | "repost" => Some(format!("{} {}", notification.reason, notification.reason_subject.as_deref()?)),
+            "follow" => Some("follow".to_string()),
+            _ => None,
+        }
+    }
+
+    // Called whenever `notifications` changes
+    fn rebuild_rows(&mut self) {
+        self.rows.clear();
+        let mut start = 0;
+        while start < self.notifications.len() {
+            let key = Self::group_key(&self.notifications[start]);
+            let mut end = start + 1;
+            if key.is_some() {
+                while end < self.notifications.len() && Self::group_key(&self.notifications[end]) == key {
+                    end += 1;
+                }
+            }
+            let expanded = key.is_some_and(|key| self.expanded.contains(&key));
+            if end - start > 1 && !expanded {
+                self.rows.push_back(NotificationRow { members: (start..end).collect() });
+            } else {
+                self.rows.extend((start..end).map(|index| NotificationRow { members: vec![index] }));
+            }
+            start = end;
+        }
+
+        let last = self.rows.len().saturating_sub(1);
+        self.base.selected_index = self.base.selected_index.min(last);
+        self.base.scroll_offset = self.base.scroll_offset.min(self.base.selected_index);
+    }
+
+    fn subject_uri(notification: &NotificationData) -> Option<&str> {
+        match notification.reason.as_str() {
+            "like" | "repost" | "reply" | "quote" => notification.reason_subject.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn subject_preview(&self, notification: &NotificationData) -> Option<&str> {
+        self.subject_previews.get(Self::subject_uri(notification)?).map(String::as_str)
+    }
+
+    // Fetch the posts we haven't seen yet in one batch. A preview that can't be
+    // loaded is just left off
+    async fn load_subject_previews(&mut self, api: &impl BlueskyClient) {
+        let mut uris: Vec<String> = self.notifications
+            .iter()
+            .filter_map(Self::subject_uri)
+            .filter(|uri| !self.subject_previews.contains_key(*uri))
+            .map(str::to_string)
+            .collect();
+        uris.sort();
+        uris.dedup();
+        if uris.is_empty() {
+            return;
+        }
+
+        match api.get_posts(&uris).await {
+            Ok(posts) => {
+                for post in posts {
+                    let text = PostListBase::get_post_text(&post).unwrap_or_default();
+                    self.subject_previews.insert(post.data.uri.clone(), emoji::sanitize(&text).replace('\n', " "));
+                }
+                // Rows with a preview are a line taller
+                self.notification_heights.clear();
+            }
+            Err(e) => log::info!("Could not load notification subjects: {}", e),
+        }
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    // The row showing the notification with this URI, grouped or not
+    pub fn row_of(&self, uri: &str) -> Option<usize> {
+        let index = self.notifications.iter().position(|notification| notification.uri == uri)?;
+        self.rows.iter().position(|row| row.members.contains(&index))
+    }
+
+    // The newest notification of the selected row
+    pub fn selected_notification(&self) -> Option<&NotificationData> {
+        let row = self.rows.get(self.base.selected_index)?;
+        self.notifications.get(row.members[0])
+    }
+
+    // What an in-view search looks through for each row: who, why, and the
+    // post it's about
+    pub fn row_search_texts(&self) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut text = String::new();
+                for notification in row.members.iter().filter_map(|&index| self.notifications.get(index)) {
+                    text.push_str(notification.author.handle.as_str());
+                    text.push(' ');
+                }
+                if let Some(first) = row.members.first().and_then(|&index| self.notifications.get(index)) {
+                    text.push_str(&first.reason);
+                    text.push(' ');
+                    text.push_str(self.subject_preview(first).unwrap_or_default());
+                }
+                text.to_lowercase()
+            })
+            .collect()
+    }
+
+    // Expand the selected group into a row per notification, or fold the
+    // group the selected row belongs to back up. None if it isn't part of one
+    pub fn toggle_group(&mut self) -> Option<bool> {
+        let row = self.rows.get(self.base.selected_index)?;
+        let member = row.members[0];
+        let key = Self::group_key(&self.notifications[member])?;
+        let expanding = row.members.len() > 1;
+        if expanding {
+            self.expanded.insert(key);
+        } else if !self.expanded.remove(&key) {
+            return None;
+        }
+        self.rebuild_rows();
+        if let Some(index) = self.rows.iter().position(|row| row.members.contains(&member)) {
+            self.select_index(index);
+        }
+        Some(expanding)
+    }
+
+    pub async fn load_notifications(&mut self, api: &mut impl BlueskyClient) -> anyhow::Result<()> {
+        let params = atrium_api::app::bsky::notification::list_notifications::ParametersData {
+            cursor: None,
+            limit: Some(LimitedNonZeroU8::MAX),
+            seen_at: None,
+            priority: None,
         };
 
-        match api.agent.api.app.bsky.notification.list_notifications(params).await {
+        match api.list_notifications(params).await {
             Ok(response) => {
                 self.notifications.clear();
                 for notification in &response.notifications {
@@ -48,9 +191,11 @@ impl NotificationView {
                 }
                 self.base.selected_index = 0;
                 self.base.scroll_offset = 0;
+                self.rebuild_rows();
+                self.load_subject_previews(&*api).await;
                 Ok(())
             }
-            Err(e) => Err(e.into())
+            Err(e) => Err(e)
         }
     }
 
@@ -62,6 +207,7 @@ impl NotificationView {
             "reply" => Color::Yellow,
             "mention" => Color::Cyan,
             "quote" => Color::Magenta,
+            ALERT_REASON => Color::LightBlue,
             _ => Color::White,
         }
     }
@@ -74,11 +220,13 @@ impl NotificationView {
             "reply" => "💬",
             "mention" => "@",
             "quote" => "💭",
+            ALERT_REASON => "🔔",
             _ => "📨",
         }
     }
 
-    fn format_notification(&self, notification: &NotificationData) -> String {
+    // `others` is how many more accounts did the same, for a grouped row
+    fn format_notification(&self, notification: &NotificationData, others: usize) -> String {
         let icon = self.get_notification_icon(&notification.reason);
         let action = match notification.reason.as_str() {
             "like" => "liked your post",
@@ -87,59 +235,89 @@ impl NotificationView {
             "reply" => "replied to your post",
             "mention" => "mentioned you",
             "quote" => "quoted your post",
+            ALERT_REASON => "posted",
             _ => "interacted with you",
         };
         
-        format!(
-            "{} @{} {}",
-            icon,
-            notification.author.handle.to_string(),
-            action
-        )
+        let who = match others {
+            0 => format!("@{}", notification.author.handle.as_str()),
+            1 => format!("@{} and 1 other", notification.author.handle.as_str()),
+            others => format!("@{} and {} others", notification.author.handle.as_str(), others),
+        };
+        format!("{} {} {}", icon, who, action)
     }
+
     pub fn get_notification(&self) -> NotificationData {
-        let selected_idx = self.selected_index();
-        return self.notifications[selected_idx].clone();
+        let row = &self.rows[self.selected_index()];
+        self.notifications[row.members[0]].clone()
+    }
+
+    // Author alerts are made client-side, so they're merged back in after every load
+    pub fn merge_alerts(&mut self, alerts: &VecDeque<NotificationData>) {
+        for alert in alerts.iter().rev() {
+            if !self.notifications.iter().any(|n| n.uri == alert.uri) {
+                self.notifications.push_front(alert.clone());
+            }
+        }
+        self.rebuild_rows();
+    }
+
+    // The post a notification is about, for opening it directly
+    pub fn selected_post_uri(&self) -> Option<String> {
+        let notification = self.selected_notification()?;
+        match notification.reason.as_str() {
+            "like" | "repost" => notification.reason_subject.clone(),
+            "reply" | "mention" | "quote" | ALERT_REASON => Some(notification.uri.clone()),
+            _ => None,
+        }
     }
 
     pub async fn handle_new_notification(
         &mut self,
         _uri: String,
-        api: &API,
+        api: &impl BlueskyClient,
     ) -> Result<()> {
         // Use existing API call to get fresh notifications
-        let params = atrium_api::app::bsky::notification::list_notifications::Parameters {
-            data: atrium_api::app::bsky::notification::list_notifications::ParametersData {
-                cursor: None,
-                limit: Some(LimitedNonZeroU8::MIN),  // Just get latest
-                seen_at: None,
-                priority: None,
-            },
-            extra_data: ipld_core::ipld::Ipld::Null,
+        let params = atrium_api::app::bsky::notification::list_notifications::ParametersData {
+            cursor: None,
+            limit: Some(LimitedNonZeroU8::MIN),  // Just get latest
+            seen_at: None,
+            priority: None,
         };
 
-        match api.agent.api.app.bsky.notification.list_notifications(params).await {
+        match api.list_notifications(params).await {
             Ok(response) => {
                 if let Some(new_notification) = response.notifications.first() {
                     // Only add if it's actually new
                     if !self.notifications.iter().any(|n| n.uri == new_notification.data.uri) {
                         self.notifications.push_front(new_notification.data.clone());
-                        self.notification_heights.insert(new_notification.data.uri.clone(), 3);
+                        self.rebuild_rows();
+                        self.load_subject_previews(api).await;
                     }
                 }
                 return Ok(())
             }
-            Err(e) =>return Err(e.into())
+            Err(e) =>return Err(e)
         }
     }
 }
 
+impl NotificationView {
+    // Rows take their height from their newest notification
+    fn row_height(&self, row: &NotificationRow) -> u16 {
+        self.notification_heights
+            .get(&self.notifications[row.members[0]].uri)
+            .copied()
+            .unwrap_or(3)
+    }
+}
+
 impl PostList for NotificationView {
     fn get_total_height_before_scroll(&self) -> u16 {
-        self.notifications
+        self.rows
             .iter()
             .take(self.base.scroll_offset)
-            .filter_map(|notif| self.notification_heights.get(&notif.uri))
+            .map(|row| self.row_height(row))
             .sum()
     }
 
@@ -147,11 +325,8 @@ impl PostList for NotificationView {
         let mut total_height = 0;
         let mut last_visible = self.base.scroll_offset;
 
-        for (i, notification) in self.notifications.iter().enumerate().skip(self.base.scroll_offset) {
-            let height = self.notification_heights
-                .get(&notification.uri)
-                .copied()
-                .unwrap_or(3);
+        for (i, row) in self.rows.iter().enumerate().skip(self.base.scroll_offset) {
+            let height = self.row_height(row);
 
             if total_height + height > area_height {
                 break;
@@ -168,17 +343,18 @@ impl PostList for NotificationView {
         let notifications_to_calculate: Vec<_> = self.notifications
             .iter()
             .filter(|notif| !self.notification_heights.contains_key(&notif.uri))
-            .cloned()
+            .map(|notif| (notif.uri.clone(), self.subject_preview(notif).is_some()))
             .collect();
 
-        for notification in notifications_to_calculate {
-            // Each notification takes 3 lines: content, status, and padding
-            self.notification_heights.insert(notification.uri, 3);
+        for (uri, has_preview) in notifications_to_calculate {
+            // Each notification takes 3 lines: content, status, and padding,
+            // plus one for a preview of the post it's about
+            self.notification_heights.insert(uri, if has_preview { 4 } else { 3 });
         }
     }
 
     fn scroll_down(&mut self) {
-        if self.base.selected_index >= self.notifications.len().saturating_sub(1) {
+        if self.base.selected_index >= self.rows.len().saturating_sub(1) {
             return;
         }
 
@@ -186,12 +362,9 @@ impl PostList for NotificationView {
         let mut y_position = 0;
 
         // Calculate if we need to adjust scroll_offset
-        for (i, notification) in self.notifications.iter().enumerate().skip(self.base.scroll_offset) {
+        for (i, row) in self.rows.iter().enumerate().skip(self.base.scroll_offset) {
             if i == next_index {
-                let height = self.notification_heights
-                    .get(&notification.uri)
-                    .copied()
-                    .unwrap_or(3);
+                let height = self.row_height(row);
                 
                 // If the next selection would be off screen, increment scroll offset
                 if y_position + height > self.base.last_known_height {
@@ -200,10 +373,7 @@ impl PostList for NotificationView {
                 break;
             }
             
-            y_position += self.notification_heights
-                .get(&notification.uri)
-                .copied()
-                .unwrap_or(3);
+            y_position += self.row_height(row);
         }
 
         self.base.selected_index = next_index;
@@ -224,13 +394,42 @@ impl PostList for NotificationView {
 
 
     fn needs_more_content(&self) -> bool {
-        self.selected_index() > self.notifications.len().saturating_sub(5)
+        self.selected_index() > self.rows.len().saturating_sub(5)
     }
 
     fn selected_index(&self) -> usize {
         self.base.selected_index
     }
 
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        let notifications = &self.notifications;
+        let heights = &self.notification_heights;
+        self.base.select_index(
+            index,
+            &self.rows,
+            |row| heights
+                .get(&notifications[row.members[0]].uri)
+                .copied()
+                .unwrap_or(3)
+        );
+    }
+
     // This allows us to get the author from a notification when 'a' is pressed
     fn get_post(&self, _index: usize) -> Option<PostViewData> {
         // Since we need to return a PostViewData but have NotificationData,
@@ -250,17 +449,16 @@ impl Widget for &mut NotificationView {
         block.render(area, buf);
 
         self.base.last_known_height = area.height;
+        self.base.last_layout.clear();
         let mut current_y = inner_area.y;
 
-        for (i, notification) in self.notifications
+        for (i, row) in self.rows
             .iter()
             .enumerate()
             .skip(self.base.scroll_offset)
         {
-            let height = self.notification_heights
-                .get(&notification.uri)
-                .copied()
-                .unwrap_or(3);
+            let notification = &self.notifications[row.members[0]];
+            let height = self.row_height(row);
 
             let remaining_height = inner_area.height.saturating_sub(current_y - inner_area.y);
             if remaining_height == 0 {
@@ -273,6 +471,7 @@ impl Widget for &mut NotificationView {
                 width: inner_area.width,
                 height: remaining_height.min(height),
             };
+            self.base.last_layout.push((i, notification_area));
 
             // Create selection background
             if i == self.base.selected_index {
@@ -291,7 +490,7 @@ impl Widget for &mut NotificationView {
             }
 
             // Render notification content
-            let formatted = self.format_notification(notification);
+            let formatted = self.format_notification(notification, row.members.len() - 1);
             let content_style = Style::default()
                 .fg(if i == self.base.selected_index {
                     Color::White
@@ -312,14 +511,37 @@ impl Widget for &mut NotificationView {
                 content_style
             );
 
-            // Add unread indicator
-            if !notification.is_read {
+            // Add unread indicator, and for a group a reminder it can be opened
+            let unread = row.members.iter().any(|&member| !self.notifications[member].is_read);
+            let status = match (unread, row.members.len() > 1) {
+                (true, true) => "● New · z to expand",
+                (true, false) => "● New",
+                (false, true) => "z to expand",
+                (false, false) => "",
+            };
+            if !status.is_empty() {
                 buf.set_string(
                     notification_area.x + 1,
                     notification_area.y + 1,
-                    "● New",
+                    status,
                     Style::default()
-                        .fg(Color::Yellow)
+                        .fg(if unread { Color::Yellow } else { Color::DarkGray })
+                        .bg(if i == self.base.selected_index {
+                            Color::DarkGray
+                        } else {
+                            Color::Reset
+                        })
+                );
+            }
+
+            if let Some(preview) = self.subject_preview(notification).filter(|_| height > 3) {
+                buf.set_stringn(
+                    notification_area.x + 3,
+                    notification_area.y + 2,
+                    format!("↳ {}", preview),
+                    notification_area.width.saturating_sub(4) as usize,
+                    Style::default()
+                        .fg(if i == self.base.selected_index { Color::Gray } else { Color::DarkGray })
                         .bg(if i == self.base.selected_index {
                             Color::DarkGray
                         } else {
@@ -330,6 +552,8 @@ impl Widget for &mut NotificationView {
 
             current_y = current_y.saturating_add(height);
         }
+
+        self.base.render_scrollbar(area, buf, self.rows.len());
     }
 }
 // Update ViewStack implementation to include notifications view state
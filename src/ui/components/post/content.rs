@@ -1,18 +1,36 @@
 use atrium_api::{app::bsky::feed::defs::PostViewData, types::Unknown};
 use ipld_core::ipld::Ipld;
-use ratatui::{buffer::Buffer, layout::Rect, widgets::{Paragraph, Widget, Wrap}};
+use ratatui::{buffer::Buffer, layout::Rect, style::{Color, Style}, text::{Line, Span}, widgets::{Paragraph, Widget}};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::ui::emoji;
 
 use super::types::{PostComponent, PostContext, PostState};
 
+// Markers drawn where a long unbroken line (URL, code) continues off screen
+const MORE_LEFT: &str = "‹";
+const MORE_RIGHT: &str = "›";
+
 pub struct PostContent {
     text: String,
+    h_offset: usize,
     context: PostContext,
+    // Lowercased in-view search to pick out in the text
+    highlight: Option<String>,
 }
 
 impl PostContent {
     pub fn new(post: &PostViewData, context: PostContext) -> Self {
         let text = Self::extract_text_content(post);
-        Self { text, context }
+        Self { text, h_offset: 0, context, highlight: None }
+    }
+
+    pub fn set_highlight(&mut self, query: Option<String>) {
+        self.highlight = query;
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
     }
 
     fn extract_text_content(post: &PostViewData) -> String {
@@ -30,30 +48,136 @@ impl PostContent {
         }
     }
 
-    fn calculate_height(&self, width: u16) -> u16 {
+    // Wrap text on word boundaries without splitting long words, so a long URL
+    // stays on one (truncated) line instead of spilling over several. Widths
+    // are measured with unicode-width, as ratatui does when it draws the
+    // line, so wide CJK characters and emoji take the columns they really use
+    pub fn wrap_lines(text: &str, usable_width: usize) -> Vec<String> {
+        let usable_width = usable_width.max(1);
+        let mut lines = Vec::new();
+        for paragraph in text.split('\n') {
+            let mut line = String::new();
+            let mut line_width = 0;
+            for word in paragraph.split_whitespace() {
+                let word_width = word.width();
+                if line.is_empty() {
+                    line.push_str(word);
+                    line_width = word_width;
+                } else if line_width + 1 + word_width <= usable_width {
+                    line.push(' ');
+                    line.push_str(word);
+                    line_width += 1 + word_width;
+                } else {
+                    lines.push(std::mem::take(&mut line));
+                    line.push_str(word);
+                    line_width = word_width;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+
+    // Rows `text` takes in an area `width` wide, e.g. a post's inner area.
+    // The height math for lists goes through here too, so it can't drift
+    // from what render draws
+    pub fn text_height(text: &str, width: u16) -> u16 {
         // Account for borders and padding (2 chars on each side)
-        let usable_width = width.saturating_sub(4);
-        
-        // Calculate how many characters fit per line
-        let chars_per_line = if usable_width > 0 {
-            usable_width as usize
+        let usable_width = width.saturating_sub(4) as usize;
+        Self::wrap_lines(text, usable_width).len() as u16
+    }
+
+    fn calculate_height(&self, width: u16) -> u16 {
+        Self::text_height(&emoji::sanitize(&self.text), width)
+    }
+
+    pub fn scroll_horizontal(&mut self, delta: isize) {
+        let longest = self.text
+            .split_whitespace()
+            .map(|word| word.chars().count())
+            .max()
+            .unwrap_or(0);
+        let new_offset = self.h_offset as isize + delta;
+        self.h_offset = new_offset.clamp(0, longest.saturating_sub(1) as isize) as usize;
+    }
+
+    // Cut a line to the visible width, adding markers for the hidden parts
+    fn clip_line(line: &str, width: usize, offset: usize) -> Line<'static> {
+        if line.width() <= width || width < 3 {
+            return Line::from(line.to_string());
+        }
+
+        let marker_style = Style::default().fg(Color::Yellow);
+        let mut spans = Vec::new();
+        let mut available = width;
+
+        let offset = offset.min(line.chars().count().saturating_sub(1));
+        if offset > 0 {
+            spans.push(Span::styled(MORE_LEFT, marker_style));
+            available -= 1;
+        }
+
+        let rest: String = line.chars().skip(offset).collect();
+        if rest.width() <= available {
+            spans.push(Span::raw(rest));
         } else {
-            1
-        };
-        
-        let wrapped_lines = textwrap::fill(&self.text, chars_per_line)
-            .lines()
-            .count();
-        
-        wrapped_lines as u16
+            let mut visible = String::new();
+            let mut used = 0;
+            for c in rest.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if used + char_width > available - 1 {
+                    break;
+                }
+                used += char_width;
+                visible.push(c);
+            }
+            spans.push(Span::raw(visible));
+            spans.push(Span::styled(MORE_RIGHT, marker_style));
+        }
+
+        Line::from(spans)
+    }
+
+    // A line with every case-insensitive match of `query` picked out. Gives
+    // up, leaving the line plain, on text whose lowercase form is a different
+    // length, since the match positions wouldn't line up
+    fn highlight_line(line: &str, query: &str) -> Line<'static> {
+        let lowered = line.to_lowercase();
+        if query.is_empty() || lowered.len() != line.len() {
+            return Line::from(line.to_string());
+        }
+
+        let match_style = Style::default().fg(Color::Black).bg(Color::Yellow);
+        let mut spans = Vec::new();
+        let mut last = 0;
+        for (start, _) in lowered.match_indices(query) {
+            let end = start + query.len();
+            if start < last || !line.is_char_boundary(start) || !line.is_char_boundary(end) {
+                continue;
+            }
+            spans.push(Span::raw(line[last..start].to_string()));
+            spans.push(Span::styled(line[start..end].to_string(), match_style));
+            last = end;
+        }
+        spans.push(Span::raw(line[last..].to_string()));
+        Line::from(spans)
     }
 }
 
 impl PostComponent for PostContent {
     fn render(&mut self, area: Rect, buf: &mut Buffer, _state: &PostState) {
-        let paragraph = Paragraph::new(self.text.clone())
-            .wrap(Wrap { trim: true });
-        paragraph.render(area, buf);
+        let usable_width = area.width.saturating_sub(4) as usize;
+        let lines: Vec<Line> = Self::wrap_lines(&emoji::sanitize(&self.text), usable_width)
+            .iter()
+            .map(|line| match &self.highlight {
+                // Lines scrolled sideways keep their markers instead
+                Some(query) if line.width() <= usable_width => {
+                    Self::highlight_line(line, query)
+                }
+                _ => Self::clip_line(line, usable_width, self.h_offset),
+            })
+            .collect();
+        Paragraph::new(lines).render(area, buf);
     }
 
     fn height(&self, area: Rect) -> u16 {
@@ -7,6 +7,9 @@ use ratatui::{
     widgets::{Paragraph, Widget},
 };
 use atrium_api::{app::bsky::feed::defs::PostViewData, types::{Unknown, string::Datetime}};
+use ipld_core::ipld::Ipld;
+
+use crate::ui::emoji;
 
 use super::types::{PostComponent, PostContext, PostState};
 
@@ -15,6 +18,9 @@ pub struct PostHeader {
     author_handle: String,
     timestamp: Datetime,
     is_reply: bool,
+    reply_parent_uri: Option<String>,
+    // Handle of the parent's author, filled in from the feed/thread or resolved later
+    reply_to: Option<String>,
     following_status: FollowingStatus,
     context: PostContext,
 }
@@ -35,6 +41,8 @@ impl PostHeader {
             // Convert the API's Datetime to chrono's DateTime
             timestamp: post.indexed_at.clone(),
             is_reply: Self::check_is_reply(post),
+            reply_parent_uri: Self::extract_reply_parent_uri(post),
+            reply_to: None,
             following_status: Self::determine_following_status(post),
             context,
         }
@@ -48,6 +56,47 @@ impl PostHeader {
         }
     }
 
+    fn extract_reply_parent_uri(post: &PostViewData) -> Option<String> {
+        let Unknown::Object(record) = &post.record else {
+            return None;
+        };
+        let Ipld::Map(reply) = &**record.get("reply")? else {
+            return None;
+        };
+        let Some(Ipld::Map(parent)) = reply.get("parent") else {
+            return None;
+        };
+        match parent.get("uri") {
+            Some(Ipld::String(uri)) => Some(uri.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn reply_parent_uri(&self) -> Option<&str> {
+        self.reply_parent_uri.as_deref()
+    }
+
+    pub fn author_handle(&self) -> &str {
+        &self.author_handle
+    }
+
+    pub fn reply_to(&self) -> Option<&str> {
+        self.reply_to.as_deref()
+    }
+
+    pub fn set_reply_to(&mut self, handle: String) {
+        self.reply_to = Some(handle);
+    }
+
+    // The author's name and whether we follow them may have changed since.
+    // The reply line stays as it was
+    pub fn update(&mut self, post: &PostViewData) {
+        let author = &post.author;
+        self.author_display_name = author.display_name.clone().unwrap_or_else(|| author.handle.to_string());
+        self.author_handle = author.handle.to_string();
+        self.following_status = Self::determine_following_status(post);
+    }
+
     fn determine_following_status(post: &PostViewData) -> FollowingStatus {
         if let Some(viewer) = &post.author.viewer {
             if viewer.data.following.is_some() {
@@ -92,7 +141,7 @@ impl PostHeader {
         
         // Author info
         spans.push(Span::styled(
-            self.author_display_name.clone(),
+            emoji::sanitize(&self.author_display_name).into_owned(),
             Style::default().add_modifier(Modifier::BOLD),
         ));
         spans.push(Span::raw(" @".to_string()));
@@ -101,7 +150,13 @@ impl PostHeader {
         // Reply indicator
         if self.is_reply {
             spans.push(Span::styled(" · ".to_string(), Style::default().fg(Color::DarkGray)));
-            spans.push(Span::styled("✉️".to_string(), Style::default()));
+            match &self.reply_to {
+                Some(handle) => {
+                    spans.push(Span::styled("replying to ".to_string(), Style::default().fg(Color::DarkGray)));
+                    spans.push(Span::styled(format!("@{}", handle), Style::default().fg(Color::Cyan)));
+                }
+                None => spans.push(Span::styled("✉️".to_string(), Style::default())),
+            }
         }
 
         // Timestamp
@@ -42,6 +42,7 @@ impl PostImages {
     fn render_single_image(
         image: &ViewImage,
         sixel: Option<&ratatui_image::protocol::sixel::Sixel>,
+        decode_failure: Option<String>,
         area: Rect,
         buf: &mut Buffer,
     ) {
@@ -60,6 +61,13 @@ impl PostImages {
         if let Some(sixel) = sixel {
             let protocol = ratatui_image::protocol::Protocol::Sixel(sixel.clone());
             ratatui_image::Image::new(&protocol).render(image_area, buf);
+        } else if let Some(reason) = decode_failure {
+            Paragraph::new(vec![
+                Line::from(Span::styled("⚠ Can't display image", Style::default().fg(Color::Red))),
+                Line::from(Span::styled(reason, Style::default().fg(Color::DarkGray))),
+            ])
+            .wrap(ratatui::widgets::Wrap { trim: true })
+            .render(image_area, buf);
         } else {
             buf.set_string(
                 image_area.x,
@@ -113,7 +121,8 @@ impl PostComponent for PostImages {
         // Then get references to the data we need
         if let Some(first_image) = self.images.first() {
             if let Some(first_sixel) = self.cached_sixels.first() {
-                Self::render_single_image(first_image, first_sixel.as_ref(), inner_area, buf);
+                let decode_failure = self.context.image_manager.decode_failure(&first_image.thumb);
+                Self::render_single_image(first_image, first_sixel.as_ref(), decode_failure, inner_area, buf);
             }
         }
     }
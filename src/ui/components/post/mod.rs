@@ -1,35 +1,48 @@
-use atrium_api::app::bsky::{embed::{images::ViewImage, record::ViewRecordRefs, record_with_media::ViewMediaRefs}, feed::defs::{PostView, PostViewData, PostViewEmbedRefs}};
+use atrium_api::app::bsky::{embed::{images::ViewImage, record::{ViewRecordData, ViewRecordRefs}, record_with_media::ViewMediaRefs}, feed::defs::{FeedViewPost, PostView, PostViewData, PostViewEmbedRefs, ReplyRefParentRefs}};
 use avatar::PostAvatar;
 use content::PostContent;
 use header::PostHeader;
 use images::PostImages;
 use quoted_post::QuotedPost;
-use ratatui::{buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, style::{Color, Style}, widgets::{Block, Borders, StatefulWidget, Widget}};
+use reason::PostReason;
+use ratatui::{buffer::Buffer, layout::{Constraint, Direction, Layout, Rect}, style::{Color, Style}, text::{Line, Span}, widgets::{Block, Borders, StatefulWidget, Widget}};
 use stats::PostStats;
+use std::sync::Arc;
 use types::{PostComponent, PostContext, PostState};
 
+use crate::ui::emoji;
+
 pub mod avatar;
 pub mod content;
 pub mod header;
 pub mod images;
 pub mod quoted_post;
+pub mod reason;
 pub mod stats;
 pub mod types;
 
 pub struct Post {
     // components: Vec<Box<dyn PostComponent>>,
+    reason: Option<PostReason>,
+    // Label of the collapse rule hiding the post behind a placeholder
+    collapsed: Option<String>,
     header: Box<PostHeader>,
     avatar: Option<Box<PostAvatar>>,
-    content: Box<dyn PostComponent>,
+    content: Box<PostContent>,
     quoted_post: Option<Box<QuotedPost>>,
     images: Option<Box<PostImages>>,
-    stats: Box<dyn PostComponent>,
+    stats: Box<PostStats>,
     context: PostContext,
-    uri: String,
+    // The same allocation the view's list of posts holds
+    post: Arc<PostView>,
+    // The in-view search this post matches, lowercased
+    highlight: Option<String>,
+    // Likes, reposts and replies for the one-line compact view
+    compact_counts: String,
 }
 
 impl Post {
-    pub fn new(post: PostView, context: PostContext) -> Self {
+    pub fn new(post: Arc<PostView>, context: PostContext) -> Self {
         let mut quoted_post = None;
         let mut images = None;
         let mut avatar = None;
@@ -54,10 +67,11 @@ impl Post {
         }
 
         let stats = Box::new(PostStats::new(&post.data, context.clone()));
-
-        let uri = post.data.uri;
+        let compact_counts = Self::compact_counts(&post.data);
 
         Self {
+            reason: None,
+            collapsed: None,
             header,
             avatar,
             content,
@@ -65,48 +79,158 @@ impl Post {
             images,
             stats,
             context,
-            uri,
+            post,
+            highlight: None,
+            compact_counts,
+        }
+    }
+
+    // Patch in a fresh copy of the same post, e.g. after a like: the counts,
+    // our like and repost, and the author line. Avatar and images are left
+    // alone, so nothing is fetched again
+    pub fn update(&mut self, post: Arc<PostView>) {
+        self.header.update(&post.data);
+        self.stats.update(&post.data);
+        self.compact_counts = Self::compact_counts(&post.data);
+        self.post = post;
+    }
+
+    fn compact_counts(post: &PostViewData) -> String {
+        format!(
+            "♥{} ↻{} ↩{}",
+            post.like_count.unwrap_or(0),
+            post.repost_count.unwrap_or(0),
+            post.reply_count.unwrap_or(0),
+        )
+    }
+
+    pub fn with_reason(mut self, reason: Option<PostReason>) -> Self {
+        self.reason = reason;
+        self
+    }
+
+    pub fn set_reason(&mut self, reason: Option<PostReason>) {
+        self.reason = reason;
+    }
+
+    // Borders plus the placeholder line
+    pub const COLLAPSED_HEIGHT: u16 = 3;
+
+    pub fn with_collapsed(mut self, rule: Option<String>) -> Self {
+        self.collapsed = rule;
+        self
+    }
+
+    pub fn set_collapsed(&mut self, rule: Option<String>) {
+        self.collapsed = rule;
+    }
+
+    // Mark the post as matching an in-view search for `query`, or clear it
+    pub fn set_highlight(&mut self, query: Option<String>) {
+        self.content.set_highlight(query.clone());
+        self.highlight = query;
+    }
+
+    pub fn with_reply_to(mut self, handle: Option<String>) -> Self {
+        if let Some(handle) = handle {
+            self.header.set_reply_to(handle);
+        }
+        self
+    }
+
+    // Feed items carry the parent post, so the handle is usually free
+    pub fn reply_parent_handle(feed_post: &FeedViewPost) -> Option<String> {
+        match &feed_post.reply.as_ref()?.parent {
+            atrium_api::types::Union::Refs(ReplyRefParentRefs::PostView(parent)) => {
+                Some(parent.author.handle.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    // Tags written in the post's text, without the #, in order and without repeats
+    pub fn hashtags(text: &str) -> Vec<String> {
+        static HASHTAG: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+        let hashtag = HASHTAG.get_or_init(|| regex::Regex::new(r"(?:^|\s)[#＃](\w[\w-]*)").unwrap());
+        let mut tags: Vec<String> = Vec::new();
+        for captures in hashtag.captures_iter(text) {
+            let tag = captures[1].trim_end_matches('-').to_string();
+            // #1 or #2024 is a number, not a tag
+            if tag.chars().all(|c| c.is_ascii_digit()) || tags.contains(&tag) {
+                continue;
+            }
+            tags.push(tag);
+        }
+        tags
+    }
+
+    pub fn reply_parent_uri(&self) -> Option<&str> {
+        self.header.reply_parent_uri()
+    }
+
+    // DID of the parent's author when this is a reply whose handle we don't know yet
+    pub fn unresolved_reply_did(&self) -> Option<&str> {
+        if self.header.reply_to().is_some() {
+            return None;
+        }
+        self.reply_parent_uri()?
+            .strip_prefix("at://")?
+            .split('/')
+            .next()
+            .filter(|did| did.starts_with("did:"))
+    }
+
+    pub fn set_reply_to(&mut self, handle: String) {
+        self.header.set_reply_to(handle);
+    }
+
+    pub fn reply_to(&self) -> Option<&str> {
+        self.header.reply_to()
+    }
+
+    // The quoted post as it's embedded, borrowed. For measuring a post
+    // without copying the quote out
+    pub fn quoted_record(post: &PostView) -> Option<&ViewRecordData> {
+        let Some(atrium_api::types::Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordView(record_view))) = &post.data.embed else {
+            return None;
+        };
+        match &record_view.data.record {
+            atrium_api::types::Union::Refs(ViewRecordRefs::ViewRecord(view_record)) => Some(&view_record.data),
+            atrium_api::types::Union::Refs(_) => None,
+            atrium_api::types::Union::Unknown(unknown_data) => {
+                log::warn!("Unknown data from extract_quoted_post_data: {:?}", unknown_data);
+                None
+            }
         }
     }
+
     pub fn extract_quoted_post_data(post: &PostView) -> Option<PostViewData> {
-        if let Some(embed) = &post.data.embed {
-            match embed {
-                atrium_api::types::Union::Refs(refs) => {
-                    if let PostViewEmbedRefs::AppBskyEmbedRecordView(record_view) = refs {
-                        return match &record_view.data.record {
-                            atrium_api::types::Union::Refs(refs) => {
-                                if let ViewRecordRefs::ViewRecord(view_record) = refs {
-                                    return Some(
-                                        PostViewData {
-                                            author: view_record.author.clone(),
-                                            cid: view_record.cid.clone(),
-                                            embed: None,
-                                            indexed_at: view_record.indexed_at.clone(),
-                                            labels: view_record.labels.clone(),
-                                            like_count: view_record.like_count,
-                                            quote_count: view_record.quote_count,
-                                            record: view_record.value.clone(),
-                                            reply_count: view_record.reply_count,
-                                            repost_count: view_record.repost_count,
-                                            threadgate: None,
-                                            uri: view_record.uri.clone(),
-                                            viewer: None,
-                                        }
-                                    );
-                                }
-                                None
-                            },
-                            atrium_api::types::Union::Unknown(unknown_data) => {
-                                log::warn!("Unknown data from extract_quoted_post_data: {:?}", unknown_data);
-                                None
-                            },
-                        };
-                    }
-                }
-                atrium_api::types::Union::Unknown(_) => {}
+        Self::quoted_record(post).map(|view_record| PostViewData {
+            author: view_record.author.clone(),
+            cid: view_record.cid.clone(),
+            embed: None,
+            indexed_at: view_record.indexed_at.clone(),
+            labels: view_record.labels.clone(),
+            like_count: view_record.like_count,
+            quote_count: view_record.quote_count,
+            record: view_record.value.clone(),
+            reply_count: view_record.reply_count,
+            repost_count: view_record.repost_count,
+            threadgate: None,
+            uri: view_record.uri.clone(),
+            viewer: None,
+        })
+    }
+
+    // Whether extract_images_from_post would find any, without cloning them
+    pub fn has_images(post: &PostView) -> bool {
+        match &post.data.embed {
+            Some(atrium_api::types::Union::Refs(PostViewEmbedRefs::AppBskyEmbedImagesView(_))) => true,
+            Some(atrium_api::types::Union::Refs(PostViewEmbedRefs::AppBskyEmbedRecordWithMediaView(record_with_media))) => {
+                matches!(&record_with_media.media, atrium_api::types::Union::Refs(ViewMediaRefs::AppBskyEmbedImagesView(_)))
             }
+            _ => false,
         }
-        None
     }
 
     pub fn extract_images_from_post(post: &PostView) -> Option<Vec<ViewImage>> {
@@ -137,11 +261,61 @@ impl Post {
     }
 
     pub fn get_uri(&self) -> &String {
-        return &self.uri;
+        return &self.post.data.uri;
+    }
+
+    pub fn post(&self) -> &Arc<PostView> {
+        &self.post
+    }
+
+    pub fn scroll_content_horizontal(&mut self, delta: isize) {
+        self.content.scroll_horizontal(delta);
     }
     pub fn has_avatar(&self) -> bool {
         return self.avatar.is_some();
     }
+
+    // Density::Compact: the post on one line, counts on the right
+    fn render_compact(&self, area: Rect, buf: &mut Buffer, state: &PostState) {
+        let area = Rect { height: 1, ..area };
+        if state.selected {
+            buf.set_style(area, Style::default().bg(Color::DarkGray));
+        }
+
+        let marker = match &self.reason {
+            Some(PostReason::Repost { .. }) => "🔁 ",
+            Some(PostReason::Pinned) => "📌 ",
+            None => "",
+        };
+        let text = match &self.collapsed {
+            Some(rule) => format!("⊘ filtered: {}", rule),
+            None => self.content.text().split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+        let text_style = if self.collapsed.is_some() {
+            Style::default().fg(Color::DarkGray)
+        } else if self.highlight.is_some() {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+
+        let counts_width = Line::raw(self.compact_counts.as_str()).width() as u16;
+        let line = Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Green)),
+            Span::styled(format!("@{} ", self.header.author_handle()), Style::default().fg(Color::Cyan)),
+            Span::styled(emoji::sanitize(&text).into_owned(), text_style),
+        ]);
+        line.render(Rect { width: area.width.saturating_sub(counts_width + 1), ..area }, buf);
+        if area.width > counts_width {
+            buf.set_stringn(
+                area.right() - counts_width,
+                area.y,
+                &self.compact_counts,
+                counts_width as usize,
+                Style::default().fg(Color::DarkGray),
+            );
+        }
+    }
 }
 
 impl StatefulWidget for &mut Post {
@@ -151,18 +325,45 @@ impl StatefulWidget for &mut Post {
         if area.height == 0 {
             return;
         }
+        if state.compact {
+            self.render_compact(area, buf, state);
+            return;
+        }
 
         let block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(
-                if state.selected { Color::Blue } else { Color::White }
+                if state.selected {
+                    Color::Blue
+                } else if self.highlight.is_some() {
+                    Color::Yellow
+                } else {
+                    Color::White
+                }
             ));
 
         let inner_area = block.inner(area);
         block.render(area, buf);
 
+        if let Some(rule) = &self.collapsed {
+            Line::from(Span::styled(
+                format!("⊘ filtered: {} (z to show)", rule),
+                Style::default().fg(Color::DarkGray),
+            )).render(inner_area, buf);
+            return;
+        }
+
         let mut current_y = inner_area.y;
         let max_y = inner_area.y + inner_area.height;
+
+        if let Some(reason) = &mut self.reason {
+            if inner_area.height > 1 {
+                let reason_area = Rect { y: current_y, height: 1, ..inner_area };
+                reason.render(reason_area, buf, state);
+                current_y += 1;
+            }
+        }
+
         let has_avatar = self.has_avatar();
         let horizontal_areas = if has_avatar {
             Layout::default()
@@ -0,0 +1,71 @@
+use atrium_api::{app::bsky::feed::defs::{FeedViewPost, FeedViewPostReasonRefs}, types::Union};
+use ratatui::{buffer::Buffer, layout::Rect, style::{Color, Style}, text::{Line, Span}, widgets::Widget};
+
+use super::types::{PostComponent, PostState};
+
+// Why a post showed up in a feed, rendered as a line above the header
+#[derive(Clone, Debug, PartialEq)]
+pub enum PostReason {
+    // Everyone whose repost brought the post in, first seen first
+    Repost { by_handles: Vec<String> },
+    Pinned,
+}
+
+// Handles named on a repost line before the rest are counted
+const MAX_NAMED_REPOSTERS: usize = 2;
+
+impl PostReason {
+    pub fn from_feed_post(feed_post: &FeedViewPost) -> Option<Self> {
+        match &feed_post.reason {
+            Some(Union::Refs(FeedViewPostReasonRefs::ReasonRepost(repost))) => Some(PostReason::Repost {
+                by_handles: vec![repost.by.handle.to_string()],
+            }),
+            Some(Union::Refs(FeedViewPostReasonRefs::ReasonPin(_))) => Some(PostReason::Pinned),
+            _ => None,
+        }
+    }
+
+    // Fold another repost of the same post into this one
+    pub fn merge(&mut self, other: &PostReason) {
+        if let (PostReason::Repost { by_handles }, PostReason::Repost { by_handles: others }) = (self, other) {
+            for handle in others {
+                if !by_handles.contains(handle) {
+                    by_handles.push(handle.clone());
+                }
+            }
+        }
+    }
+
+    fn to_line(&self) -> Line<'static> {
+        match self {
+            PostReason::Repost { by_handles } => {
+                let mut spans = vec![Span::styled("🔁 Reposted by ", Style::default().fg(Color::DarkGray))];
+                for (i, handle) in by_handles.iter().take(MAX_NAMED_REPOSTERS).enumerate() {
+                    if i > 0 {
+                        spans.push(Span::styled(", ", Style::default().fg(Color::DarkGray)));
+                    }
+                    spans.push(Span::styled(format!("@{}", handle), Style::default().fg(Color::Green)));
+                }
+                let others = by_handles.len().saturating_sub(MAX_NAMED_REPOSTERS);
+                if others > 0 {
+                    spans.push(Span::styled(
+                        format!(" and {} {}", others, if others == 1 { "other" } else { "others" }),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                Line::from(spans)
+            }
+            PostReason::Pinned => Line::from(Span::styled("📌 Pinned", Style::default().fg(Color::Yellow))),
+        }
+    }
+}
+
+impl PostComponent for PostReason {
+    fn render(&mut self, area: Rect, buf: &mut Buffer, _state: &PostState) {
+        self.to_line().render(area, buf);
+    }
+
+    fn height(&self, _area: Rect) -> u16 {
+        1
+    }
+}
@@ -24,6 +24,15 @@ impl PostStats {
         }
     }
 
+    // Take the counts and our like/repost from a fresh copy of the post
+    pub fn update(&mut self, post: &PostViewData) {
+        self.likes = post.like_count.unwrap_or(0) as u32;
+        self.reposts = post.repost_count.unwrap_or(0) as u32;
+        self.replies = post.reply_count.unwrap_or(0) as u32;
+        self.has_liked = Self::check_liked(post);
+        self.has_reposted = Self::check_reposted(post);
+    }
+
     pub fn check_liked(post: &PostViewData) -> bool {
         post.viewer
             .as_ref()
@@ -5,6 +5,8 @@ use crate::ui::components::images::ImageManager;
 
 pub struct PostState {
     pub selected: bool,
+    // Draw the post as a single line, see Density::Compact
+    pub compact: bool,
 }
 
 pub trait PostComponent {
@@ -6,12 +6,15 @@ use ratatui::{
     text::{Line, Span},
 };
 
+use crate::client::outbox::OutboxId;
+
 const CHARACTER_LIMIT: usize = 300;
 
 pub struct PostComposer {
     pub content: String,
     pub cursor_position: usize,
     pub reply_to: Option<String>, // URI of post being replied to
+    pub outbox_id: Option<OutboxId>, // Queued post being edited
 }
 
 pub struct PostComposerState {
@@ -24,6 +27,7 @@ impl PostComposer {
             content: String::new(),
             cursor_position: 0,
             reply_to,
+            outbox_id: None,
         }
     }
 
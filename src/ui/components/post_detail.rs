@@ -0,0 +1,281 @@
+use atrium_api::{app::bsky::feed::defs::{PostView, PostViewEmbedRefs}, types::{Union, Unknown}};
+use chrono::{DateTime, FixedOffset, Local};
+use ipld_core::ipld::Ipld;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use std::sync::Arc;
+
+use crate::{client::dag_cbor, ui::emoji};
+
+use super::{
+    images::ImageManager,
+    post::{images::PostImages, types::{PostComponent, PostContext, PostState}, Post},
+    post_list::PostListBase,
+};
+
+// Same as the image area in a list
+const IMAGE_HEIGHT: usize = 15;
+
+enum Section {
+    // A paragraph, wrapped to the width when drawn. Empty for a blank line
+    Text(String, Style),
+    Image(usize),
+}
+
+// One post on its own page: all of its text and images, the links, mentions
+// and tags in it, its labels and exact times. Opened with Enter
+pub struct PostDetailView {
+    pub post: PostView,
+    sections: Vec<Section>,
+    images: Vec<PostImages>,
+    pub scroll_offset: usize,
+    last_known_height: u16,
+    // Lines everything took at the last draw, images included
+    line_count: usize,
+}
+
+impl PostDetailView {
+    pub fn new(post: PostView, image_manager: Arc<ImageManager>) -> Self {
+        let context = PostContext { image_manager, indent_level: 0 };
+        // PostImages shows one image, so each gets its own
+        let images = Post::extract_images_from_post(&post)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|image| PostImages::new(vec![image], context.clone()))
+            .collect();
+        let mut detail = Self {
+            post,
+            sections: Vec::new(),
+            images,
+            scroll_offset: 0,
+            last_known_height: 0,
+            line_count: 0,
+        };
+        detail.sections = detail.build_sections();
+        detail
+    }
+
+    // New counts or viewer state, e.g. after a like. Images stay as they are
+    pub fn set_post(&mut self, post: PostView) {
+        self.post = post;
+        self.sections = self.build_sections();
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    pub fn scroll_down(&mut self) {
+        if self.scroll_offset + (self.last_known_height as usize) < self.line_count {
+            self.scroll_offset += 1;
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(1);
+    }
+
+    pub fn page_size(&self) -> usize {
+        (self.last_known_height as usize).max(1)
+    }
+
+    fn build_sections(&self) -> Vec<Section> {
+        let post = &self.post;
+        let gray = Style::default().fg(Color::Gray);
+        let heading = Style::default().add_modifier(Modifier::BOLD);
+        let text = |text: String, style: Style| Section::Text(text, style);
+        let blank = || Section::Text(String::new(), Style::default());
+
+        let display_name = post.author.display_name.as_deref().unwrap_or(post.author.handle.as_str());
+        let mut sections = vec![
+            text(format!("{} @{}", emoji::sanitize(display_name), post.author.handle.as_str()), heading.fg(Color::Cyan)),
+        ];
+        if let Some(created) = self.record_str("createdAt") {
+            sections.push(text(format!("Posted   {}", Self::exact_time(created)), gray));
+        }
+        sections.push(text(format!("Indexed  {}", Self::exact_time(post.indexed_at.as_str())), gray));
+        if let Some(parent) = self.record_path(&["reply", "parent", "uri"]) {
+            sections.push(text(format!("Reply to {}", parent), gray));
+        }
+        sections.push(blank());
+
+        // Keep the author's line breaks
+        for paragraph in self.record_str("text").unwrap_or_default().split('\n') {
+            sections.push(text(emoji::sanitize(paragraph).into_owned(), Style::default()));
+        }
+
+        for i in 0..self.images.len() {
+            sections.push(blank());
+            sections.push(Section::Image(i));
+        }
+
+        if let Some(quoted) = Post::extract_quoted_post_data(post) {
+            sections.push(blank());
+            sections.push(text(format!("Quoting @{}", quoted.author.handle.as_str()), heading));
+            let quoted_text = PostListBase::get_post_text(&quoted.clone().into()).unwrap_or_default();
+            sections.push(text(emoji::sanitize(&quoted_text).into_owned(), gray));
+            sections.push(text(quoted.uri.clone(), Style::default().fg(Color::DarkGray)));
+        }
+
+        let links = self.links();
+        if !links.is_empty() {
+            sections.push(blank());
+            sections.push(text("Links".to_string(), heading));
+            sections.extend(links.into_iter().map(|link| text(link, Style::default().fg(Color::Blue))));
+        }
+
+        let labels = self.labels();
+        if !labels.is_empty() {
+            sections.push(blank());
+            sections.push(text("Labels".to_string(), heading));
+            sections.extend(labels.into_iter().map(|label| text(label, Style::default().fg(Color::Yellow))));
+        }
+
+        sections.push(blank());
+        sections.push(text(format!(
+            "♥ {} likes · ↻ {} reposts · ↩ {} replies · ❝ {} quotes",
+            post.like_count.unwrap_or(0),
+            post.repost_count.unwrap_or(0),
+            post.reply_count.unwrap_or(0),
+            post.quote_count.unwrap_or(0),
+        ), gray));
+        sections.push(text(post.uri.clone(), Style::default().fg(Color::DarkGray)));
+        sections.push(text(format!("cid {}", post.cid.as_ref()), Style::default().fg(Color::DarkGray)));
+        sections
+    }
+
+    fn record_field(&self, key: &str) -> Option<&Ipld> {
+        match &self.post.record {
+            Unknown::Object(map) => map.get(key).map(|value| &**value),
+            _ => None,
+        }
+    }
+
+    fn record_str(&self, key: &str) -> Option<&str> {
+        match self.record_field(key)? {
+            Ipld::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn record_path(&self, path: &[&str]) -> Option<&str> {
+        dag_cbor::lookup_str(self.record_field(path[0])?, &path[1..])
+    }
+
+    // Local time to the second with its offset, falling back to the raw string
+    fn exact_time(raw: &str) -> String {
+        match DateTime::<FixedOffset>::parse_from_rfc3339(raw) {
+            Ok(time) => time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            Err(_) => raw.to_string(),
+        }
+    }
+
+    // Each facet as "text → target", then the link card if there is one
+    fn links(&self) -> Vec<String> {
+        let text = self.record_str("text").unwrap_or_default();
+        let mut links = Vec::new();
+        if let Some(Ipld::List(facets)) = self.record_field("facets") {
+            for facet in facets {
+                let start = dag_cbor::lookup(facet, &["index", "byteStart"]);
+                let end = dag_cbor::lookup(facet, &["index", "byteEnd"]);
+                let covered = match (start, end) {
+                    (Some(Ipld::Integer(start)), Some(Ipld::Integer(end))) => {
+                        text.get(*start as usize..*end as usize).unwrap_or_default()
+                    }
+                    _ => "",
+                };
+                let Some(Ipld::List(features)) = dag_cbor::lookup(facet, &["features"]) else {
+                    continue;
+                };
+                for feature in features {
+                    let target = match dag_cbor::lookup_str(feature, &["$type"]) {
+                        Some("app.bsky.richtext.facet#link") => dag_cbor::lookup_str(feature, &["uri"]),
+                        Some("app.bsky.richtext.facet#mention") => dag_cbor::lookup_str(feature, &["did"]),
+                        Some("app.bsky.richtext.facet#tag") => dag_cbor::lookup_str(feature, &["tag"]),
+                        _ => None,
+                    };
+                    if let Some(target) = target {
+                        links.push(format!("{} → {}", emoji::sanitize(covered), target));
+                    }
+                }
+            }
+        }
+        if let Some(Union::Refs(PostViewEmbedRefs::AppBskyEmbedExternalView(external))) = &self.post.embed {
+            links.push(format!("Card: {} → {}", emoji::sanitize(&external.external.title), external.external.uri));
+        }
+        links
+    }
+
+    // The post's labels, then its author's
+    fn labels(&self) -> Vec<String> {
+        let post_labels = self.post.labels.iter().flatten().map(|label| ("post", label));
+        let author_labels = self.post.author.labels.iter().flatten().map(|label| ("author", label));
+        post_labels
+            .chain(author_labels)
+            .map(|(on, label)| {
+                let negated = if label.neg == Some(true) { " (removed)" } else { "" };
+                format!("{} on the {} from {}{}", label.val, on, label.src.as_str(), negated)
+            })
+            .collect()
+    }
+}
+
+impl Widget for &mut PostDetailView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::White))
+            .title("📄 Post (j/k to scroll, v for its thread)");
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        self.last_known_height = inner_area.height;
+
+        // Lay everything out as lines first, with blank lines where images go
+        let width = (inner_area.width as usize).max(1);
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        let mut image_rows = Vec::new();
+        for section in &self.sections {
+            match section {
+                Section::Text(text, _) if text.is_empty() => lines.push(Line::default()),
+                // Long URLs are split rather than cut off
+                Section::Text(text, style) => lines.extend(
+                    textwrap::wrap(text, textwrap::Options::new(width).break_words(true))
+                        .into_iter()
+                        .map(|line| Line::styled(line.into_owned(), *style)),
+                ),
+                Section::Image(index) => {
+                    image_rows.push((lines.len(), *index));
+                    lines.extend(std::iter::repeat_n(Line::default(), IMAGE_HEIGHT));
+                }
+            }
+        }
+        self.line_count = lines.len();
+        self.scroll_offset = self.scroll_offset.min(self.line_count.saturating_sub(inner_area.height as usize));
+
+        let visible: Vec<Line> = lines
+            .into_iter()
+            .skip(self.scroll_offset)
+            .take(inner_area.height as usize)
+            .collect();
+        Paragraph::new(visible).render(inner_area, buf);
+
+        // An image is only drawn once all of it is on screen
+        let bottom = self.scroll_offset + inner_area.height as usize;
+        for (row, index) in image_rows {
+            if row < self.scroll_offset || row + IMAGE_HEIGHT > bottom {
+                continue;
+            }
+            let image_area = Rect {
+                y: inner_area.y + (row - self.scroll_offset) as u16,
+                height: IMAGE_HEIGHT as u16,
+                ..inner_area
+            };
+            self.images[index].render(image_area, buf, &PostState { selected: false, compact: false });
+        }
+    }
+}
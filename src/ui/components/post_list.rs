@@ -1,7 +1,16 @@
 // In src/ui/components/post_list.rs
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use atrium_api::app::bsky::feed::defs::{PostView, PostViewData};
-use ratatui::layout::Rect;
+use lru::LruCache;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Margin, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState, StatefulWidget},
+};
+
+use super::post::content::PostContent;
+use crate::ui::emoji;
 
 // A trait for components that manage a scrollable list of posts
 pub trait PostList {
@@ -12,11 +21,164 @@ pub trait PostList {
     fn scroll_up(&mut self);
     fn needs_more_content(&self) -> bool;
     fn selected_index(&self) -> usize;
+    fn select_index(&mut self, index: usize);
     fn get_post(&self, index: usize) -> Option<PostViewData>;
+    // Index of the first item on screen
+    fn scroll_offset(&self) -> usize;
+    fn set_scroll_offset(&mut self, offset: usize);
+    // Rows the list had on screen when it was last drawn
+    fn viewport_height(&self) -> u16;
+    // Where each item was drawn last time, for mouse clicks
+    fn last_layout(&self) -> &[(usize, Rect)];
+
+    // The item drawn under a screen position, if any
+    fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        self.last_layout()
+            .iter()
+            .find(|(_, area)| area.contains(ratatui::layout::Position { x: column, y: row }))
+            .map(|(index, _)| *index)
+    }
+
+    // Select `index` with it the same distance below the top of the view as
+    // `rows_from_top`, e.g. to put things back after a reload
+    fn restore_position(&mut self, index: usize, rows_from_top: usize) {
+        self.select_index(index);
+        let selected = self.selected_index();
+        self.set_scroll_offset(selected.saturating_sub(rows_from_top));
+    }
 
     fn get_selected_post(&self) -> Option<PostViewData> {
         self.get_post(self.selected_index())
     }
+
+    // How many items fit on screen from the current scroll position, for
+    // paging. At least one, so paging always moves
+    fn page_size(&self) -> usize {
+        let last_visible = self.get_last_visible_index(self.viewport_height());
+        (last_visible + 1).saturating_sub(self.scroll_offset()).max(1)
+    }
+
+    // Several steps at once, for counts like 5j. Stops at either end
+    fn scroll_down_by(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.scroll_down();
+        }
+    }
+
+    fn scroll_up_by(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.scroll_up();
+        }
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct HeightCacheKey {
+    uri: String,
+    width: u16,
+}
+
+// What calculate_post_height gave for a post at a width, for every view.
+// Posts don't change once written, so the same post in another view or
+// back at an earlier width isn't measured again
+pub struct HeightCache {
+    cache: LruCache<HeightCacheKey, u16>,
+}
+
+impl Default for HeightCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HeightCache {
+    pub fn new() -> Self {
+        Self {
+            cache: LruCache::new(2000.try_into().unwrap()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+pub type SharedHeightCache = Arc<Mutex<HeightCache>>;
+
+// A view's post heights by URI, all at one width, with reposts, collapsed
+// posts and the like already counted. Wrapping changes with the width, so a
+// new width (e.g. the terminal was resized) starts them over
+pub struct PostHeights {
+    width: u16,
+    heights: HashMap<String, u16>,
+    measured: SharedHeightCache,
+}
+
+impl PostHeights {
+    pub fn new(measured: SharedHeightCache) -> Self {
+        Self {
+            width: 0,
+            heights: HashMap::new(),
+            measured,
+        }
+    }
+
+    // calculate_post_height, unless some view has already measured the post
+    // at this width
+    pub fn measure(&self, post: &PostView, width: u16) -> u16 {
+        let key = HeightCacheKey { uri: post.uri.clone(), width };
+        let mut measured = self.measured.lock().unwrap();
+        if let Some(height) = measured.cache.get(&key) {
+            return *height;
+        }
+        let height = PostListBase::calculate_post_height(post, width);
+        measured.cache.put(key, height);
+        height
+    }
+
+    // Drop the heights of posts the view no longer has. Only worth the walk
+    // once there are more heights than posts
+    pub fn retain_loaded<'a>(&mut self, uris: impl ExactSizeIterator<Item = &'a str>) {
+        if self.heights.len() <= uris.len() {
+            return;
+        }
+        let loaded: std::collections::HashSet<&str> = uris.collect();
+        self.heights.retain(|uri, _| loaded.contains(uri.as_str()));
+    }
+
+    // Measure at `width` from now on. True if that threw the old heights away
+    pub fn set_width(&mut self, width: u16) -> bool {
+        if width == self.width {
+            return false;
+        }
+        self.width = width;
+        self.heights.clear();
+        true
+    }
+
+    pub fn get(&self, uri: &str) -> Option<&u16> {
+        self.heights.get(uri)
+    }
+
+    pub fn contains_key(&self, uri: &str) -> bool {
+        self.heights.contains_key(uri)
+    }
+
+    pub fn insert(&mut self, uri: String, height: u16) {
+        self.heights.insert(uri, height);
+    }
+
+    pub fn remove(&mut self, uri: &str) {
+        self.heights.remove(uri);
+    }
+
+    pub fn clear(&mut self) {
+        self.heights.clear();
+    }
 }
 
 // Shared data structure that both Feed and Thread can use
@@ -24,6 +186,10 @@ pub struct PostListBase {
     pub selected_index: usize,
     pub scroll_offset: usize,
     pub last_known_height: u16,
+    // (item index, area) for each item drawn on the last render
+    pub last_layout: Vec<(usize, Rect)>,
+    // Posts drawn one line each, see Density::Compact
+    pub compact: bool,
 }
 
 impl PostListBase {
@@ -32,6 +198,8 @@ impl PostListBase {
             selected_index: 0,
             scroll_offset: 0,
             last_known_height: 0,
+            last_layout: Vec::new(),
+            compact: false,
         }
     }
 
@@ -44,27 +212,18 @@ impl PostListBase {
         height += 1;  // Header line
         height += 1;  // Stats line
         
-        // Calculate main content height based on available width
+        // The post's border leaves content this much room, and a quote's
+        // border two columns less again, as Post and QuotedPost lay them out
+        let inner_width = available_width.saturating_sub(2);
+        let quote_inner_width = inner_width.saturating_sub(2);
+
+        // Long unbroken words are truncated rather than wrapped, see PostContent
         if let Some(text) = Self::get_post_text(post) {
-            // Account for borders and padding (2 chars on each side)
-            let usable_width = available_width.saturating_sub(4);
-            
-            // Calculate how many characters fit per line
-            let chars_per_line = if usable_width > 0 {
-                usable_width as usize
-            } else {
-                1
-            };
-            
-            let wrapped_lines = textwrap::fill(&text, chars_per_line)
-                .lines()
-                .count();
-            
-            height += wrapped_lines as u16;
+            height += PostContent::text_height(&emoji::sanitize(&text), inner_width);
         }
 
-        // Handle quoted posts if present
-        if let Some(quoted_post) = super::post::Post::extract_quoted_post_data(post) {
+        // Handle quoted posts if present. Quotes are drawn without their images
+        if let Some(quoted_post) = super::post::Post::quoted_record(post) {
             // Add borders for quote block
             height += 2;  // Top and bottom borders of quote
 
@@ -72,33 +231,16 @@ impl PostListBase {
             height += 1;
 
             // Calculate quoted text height
-            if let Some(quoted_text) = Self::get_post_text(&quoted_post.clone().into()) {
-                // Reduce width for quote indentation (4 chars for borders and indent)
-                let quote_width = available_width.saturating_sub(6);
-                let chars_per_line = if quote_width > 0 {
-                    quote_width as usize
-                } else {
-                    1
-                };
-
-                let wrapped_lines = textwrap::fill(&quoted_text, chars_per_line)
-                    .lines()
-                    .count();
-                
-                height += wrapped_lines as u16;
+            if let Some(quoted_text) = Self::record_text(&quoted_post.value) {
+                height += PostContent::text_height(&emoji::sanitize(&quoted_text), quote_inner_width);
             }
 
             // Add height for quoted post stats
             height += 1;
-
-            // If quoted post has images, add image height
-            if super::post::Post::extract_images_from_post(&quoted_post.into()).is_some() {
-                height += 15;  // Fixed height for image area
-            }
         }
         
         // Add height for main post images if present
-        if super::post::Post::extract_images_from_post(post).is_some() {
+        if super::post::Post::has_images(post) {
             height += 15;  // Fixed height for image area
         }
         
@@ -107,10 +249,14 @@ impl PostListBase {
 
     // Helper to get post text - moved from Feed
     pub fn get_post_text(post: &PostView) -> Option<String> {
+        Self::record_text(&post.data.record)
+    }
+
+    fn record_text(record: &atrium_api::types::Unknown) -> Option<String> {
         use atrium_api::types::Unknown;
         use ipld_core::ipld::Ipld;
         
-        match &post.data.record {
+        match record {
             Unknown::Object(map) => match map.get("text") {
                 Some(data_model) => match &**data_model {
                     Ipld::String(text) => Some(text.clone()),
@@ -162,6 +308,41 @@ impl PostListBase {
         self.selected_index = next_index;
     }
 
+    // Jump the selection to an arbitrary index, scrolling just enough to show it
+    pub fn select_index<T>(
+        &mut self,
+        index: usize,
+        posts: &VecDeque<T>,
+        get_height: impl Fn(&T) -> u16,
+    ) {
+        if posts.is_empty() {
+            return;
+        }
+        let index = index.min(posts.len() - 1);
+        self.selected_index = index;
+
+        if index < self.scroll_offset {
+            self.scroll_offset = index;
+            return;
+        }
+
+        // Walk backwards from the target to find the first post that still fits above it
+        let mut first_visible = index;
+        let mut used_height = get_height(&posts[index]) as u32;
+        while first_visible > 0 {
+            let height = get_height(&posts[first_visible - 1]) as u32;
+            if used_height + height > self.last_known_height as u32 {
+                break;
+            }
+            used_height += height;
+            first_visible -= 1;
+        }
+
+        if self.scroll_offset < first_visible {
+            self.scroll_offset = first_visible;
+        }
+    }
+
     pub fn handle_scroll_up(&mut self) {
         if self.selected_index == 0 {
             return;
@@ -173,5 +354,20 @@ impl PostListBase {
             self.scroll_offset = self.selected_index;
         }
     }
-    
+
+    // Scrollbar down the right border of `area`, the thumb following the
+    // selection through the `item_count` items loaded. Call after drawing the
+    // items, and left off when they all fit
+    pub fn render_scrollbar(&self, area: Rect, buf: &mut Buffer, item_count: usize) {
+        if self.last_layout.len() >= item_count {
+            return;
+        }
+        let mut state = ScrollbarState::new(item_count)
+            .position(self.selected_index)
+            .viewport_content_length(self.last_layout.len());
+        Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .render(area.inner(Margin { vertical: 1, horizontal: 0 }), buf, &mut state);
+    }
 }
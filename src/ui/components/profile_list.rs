@@ -0,0 +1,186 @@
+use std::collections::VecDeque;
+use atrium_api::app::bsky::{actor::defs::ProfileView, feed::defs::PostViewData};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{client::bluesky_client::BlueskyClient, ui::emoji};
+
+use super::post_list::{PostList, PostListBase};
+
+// Name line plus a line of bio
+const ROW_HEIGHT: u16 = 2;
+
+// Which accounts the list shows
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ProfileListSource {
+    Likers { uri: String },
+    Reposters { uri: String },
+}
+
+impl ProfileListSource {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ProfileListSource::Likers { .. } => "❤️ Liked by",
+            ProfileListSource::Reposters { .. } => "🔁 Reposted by",
+        }
+    }
+}
+
+pub struct ProfileListView {
+    pub source: ProfileListSource,
+    pub profiles: VecDeque<ProfileView>,
+    pub cursor: Option<String>,
+    base: PostListBase,
+}
+
+impl ProfileListView {
+    pub fn new(source: ProfileListSource) -> Self {
+        Self {
+            source,
+            profiles: VecDeque::new(),
+            cursor: None,
+            base: PostListBase::new(),
+        }
+    }
+
+    async fn fetch_page(&self, api: &impl BlueskyClient, cursor: Option<String>) -> Result<(Vec<ProfileView>, Option<String>)> {
+        match &self.source {
+            ProfileListSource::Likers { uri } => api.get_likers(uri, cursor).await,
+            ProfileListSource::Reposters { uri } => api.get_reposters(uri, cursor).await,
+        }
+    }
+
+    pub async fn load_initial(&mut self, api: &impl BlueskyClient) -> Result<()> {
+        let (profiles, cursor) = self.fetch_page(api, None).await?;
+        self.profiles = profiles.into();
+        self.cursor = cursor;
+        self.base.selected_index = 0;
+        self.base.scroll_offset = 0;
+        Ok(())
+    }
+
+    pub async fn load_more(&mut self, api: &impl BlueskyClient) -> Result<()> {
+        if self.cursor.is_none() {
+            return Ok(());
+        }
+        let (profiles, cursor) = self.fetch_page(api, self.cursor.clone()).await?;
+        self.profiles.extend(profiles);
+        self.cursor = cursor;
+        Ok(())
+    }
+
+    pub fn get_selected_profile(&self) -> Option<&ProfileView> {
+        self.profiles.get(self.base.selected_index)
+    }
+}
+
+impl PostList for ProfileListView {
+    fn get_total_height_before_scroll(&self) -> u16 {
+        self.base.scroll_offset as u16 * ROW_HEIGHT
+    }
+
+    fn get_last_visible_index(&self, area_height: u16) -> usize {
+        let visible = (area_height / ROW_HEIGHT).max(1) as usize;
+        (self.base.scroll_offset + visible - 1).min(self.profiles.len().saturating_sub(1))
+    }
+
+    fn ensure_post_heights(&mut self, _area: Rect) {}
+
+    fn scroll_down(&mut self) {
+        if self.profiles.is_empty() {
+            return;
+        }
+        self.base.handle_scroll_down(&self.profiles, |_| ROW_HEIGHT);
+    }
+
+    fn scroll_up(&mut self) {
+        self.base.handle_scroll_up();
+    }
+
+    fn needs_more_content(&self) -> bool {
+        self.selected_index() > self.profiles.len().saturating_sub(5)
+    }
+
+    fn selected_index(&self) -> usize {
+        self.base.selected_index
+    }
+
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.base.select_index(index, &self.profiles, |_| ROW_HEIGHT);
+    }
+
+    // Profiles aren't posts
+    fn get_post(&self, _index: usize) -> Option<PostViewData> {
+        None
+    }
+}
+
+impl Widget for &mut ProfileListView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = format!("{} ({}{})", self.source.title(), self.profiles.len(), if self.cursor.is_some() { "+" } else { "" });
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        self.base.last_known_height = inner_area.height;
+        self.base.last_layout.clear();
+
+        if self.profiles.is_empty() {
+            Paragraph::new(Span::styled("Nobody yet", Style::default().fg(Color::DarkGray)))
+                .render(inner_area, buf);
+            return;
+        }
+
+        let mut current_y = inner_area.y;
+        for (i, profile) in self.profiles.iter().enumerate().skip(self.base.scroll_offset) {
+            if current_y + ROW_HEIGHT > inner_area.y + inner_area.height {
+                break;
+            }
+            let row_area = Rect { y: current_y, height: ROW_HEIGHT, ..inner_area };
+            self.base.last_layout.push((i, row_area));
+            let selected = i == self.base.selected_index;
+            let row_style = if selected {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+
+            let display_name = profile.display_name.clone().unwrap_or_else(|| profile.handle.to_string());
+            let bio = profile.description.as_deref().unwrap_or("").replace('\n', " ");
+            let lines = vec![
+                Line::from(vec![
+                    Span::styled(emoji::sanitize(&display_name).into_owned(), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" @{}", profile.handle.as_str()), Style::default().fg(Color::Cyan)),
+                ]),
+                Line::from(Span::styled(emoji::sanitize(&bio).into_owned(), Style::default().fg(Color::Gray))),
+            ];
+            Paragraph::new(lines).style(row_style).render(row_area, buf);
+
+            current_y += ROW_HEIGHT;
+        }
+    }
+}
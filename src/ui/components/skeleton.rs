@@ -0,0 +1,64 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Widget},
+};
+
+// Borders, header, two lines of text and stats, the size of a short post
+const POST_HEIGHT: u16 = 6;
+// Text line widths in percent of the post's inside, varied from one post to
+// the next so the column doesn't look like a grid
+const LINE_WIDTHS: [(u16, u16); 4] = [(90, 55), (70, 35), (85, 65), (60, 25)];
+const BAR: &str = "░";
+
+fn percent(width: u16, percent: u16) -> u16 {
+    (width as u32 * percent as u32 / 100) as u16
+}
+
+// Greyed out post frames filling a list while its first posts load, so the
+// real ones arrive in roughly the same place
+pub struct SkeletonPosts;
+
+impl SkeletonPosts {
+    fn bar(buf: &mut Buffer, x: u16, y: u16, width: u16) {
+        buf.set_string(x, y, BAR.repeat(width as usize), Style::default().fg(Color::DarkGray));
+    }
+}
+
+impl Widget for SkeletonPosts {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let style = Style::default().fg(Color::DarkGray);
+        let mut y = area.y;
+        for &(first, second) in LINE_WIDTHS.iter().cycle() {
+            if y >= area.bottom() {
+                break;
+            }
+            let post_area = Rect { y, height: POST_HEIGHT.min(area.bottom() - y), ..area };
+            let block = Block::default().borders(Borders::ALL).border_style(style);
+            let inner = block.inner(post_area);
+            block.render(post_area, buf);
+
+            // Display name and handle, the text, then the counts
+            let rows: [&[u16]; 4] = [
+                &[inner.width / 4, inner.width / 5],
+                &[percent(inner.width, first)],
+                &[percent(inner.width, second)],
+                &[3, 3, 3, 3],
+            ];
+            for (row, widths) in rows.iter().enumerate().take(inner.height as usize) {
+                let mut x = inner.x;
+                for &width in widths.iter() {
+                    let width = width.min(inner.right().saturating_sub(x));
+                    Self::bar(buf, x, inner.y + row as u16, width);
+                    // The stats are spread out like the real ones
+                    x += width + if row == 3 { 6 } else { 1 };
+                    if x >= inner.right() {
+                        break;
+                    }
+                }
+            }
+            y += POST_HEIGHT;
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+const LOGO: &str = r#"     _          _ _
+ ___| | ___   _| (_)_ __   ___
+/ __| |/ / | | | | | '_ \ / _ \
+\__ \   <| |_| | | | | | |  __/
+|___/_|\_\\__, |_|_|_| |_|\___|
+          |___/"#;
+
+const SKYLINE: &str = "▁▂▅▇▂▁▃▆█▃▁▂▄▇▅▂▁▃▅▂▁▆█▄▁▂▃▇▅▁";
+
+pub enum StepStatus {
+    Running,
+    Done(String),
+    Failed(String),
+}
+
+pub struct SplashStep {
+    pub label: String,
+    pub status: StepStatus,
+}
+
+// Shown while App::run restores the session and fetches the first page
+pub struct Splash {
+    pub steps: Vec<SplashStep>,
+}
+
+impl Default for Splash {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Splash {
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    pub fn begin(&mut self, label: impl Into<String>) {
+        self.steps.push(SplashStep {
+            label: label.into(),
+            status: StepStatus::Running,
+        });
+    }
+
+    pub fn finish(&mut self, detail: impl Into<String>) {
+        if let Some(step) = self.steps.last_mut() {
+            step.status = StepStatus::Done(detail.into());
+        }
+    }
+
+    pub fn fail(&mut self, detail: impl Into<String>) {
+        if let Some(step) = self.steps.last_mut() {
+            step.status = StepStatus::Failed(detail.into());
+        }
+    }
+
+    fn step_line(step: &SplashStep) -> Line<'static> {
+        let (icon, icon_style, detail) = match &step.status {
+            StepStatus::Running => ("…", Style::default().fg(Color::Yellow), String::new()),
+            StepStatus::Done(detail) => ("✓", Style::default().fg(Color::Green), detail.clone()),
+            StepStatus::Failed(detail) => ("✗", Style::default().fg(Color::Red), detail.clone()),
+        };
+        let mut spans = vec![
+            Span::styled(format!("{} ", icon), icon_style),
+            Span::raw(step.label.clone()),
+        ];
+        if !detail.is_empty() {
+            spans.push(Span::styled(format!(" · {}", detail), Style::default().fg(Color::DarkGray)));
+        }
+        Line::from(spans)
+    }
+}
+
+impl Widget for &Splash {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("🌆 Skyline");
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+
+        let logo_height = LOGO.lines().count() as u16;
+        let steps_height = self.steps.len() as u16;
+        let content_height = logo_height + 3 + steps_height;
+        let top_padding = inner_area.height.saturating_sub(content_height) / 2;
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(top_padding),
+                Constraint::Length(logo_height),
+                Constraint::Length(1),  // Skyline silhouette
+                Constraint::Length(2),  // Tagline
+                Constraint::Min(steps_height),
+            ])
+            .split(inner_area);
+
+        // Pad every logo line to the same width so centering keeps them aligned
+        let logo_width = LOGO.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let logo: Vec<Line> = LOGO
+            .lines()
+            .map(|line| Line::from(Span::styled(
+                format!("{:<width$}", line, width = logo_width),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            )))
+            .collect();
+        Paragraph::new(logo).alignment(Alignment::Center).render(chunks[1], buf);
+
+        Paragraph::new(Line::from(Span::styled(SKYLINE, Style::default().fg(Color::Blue))))
+            .alignment(Alignment::Center)
+            .render(chunks[2], buf);
+
+        Paragraph::new(Line::from(Span::styled(
+            "A terminal client for Bluesky",
+            Style::default().fg(Color::Gray),
+        )))
+        .alignment(Alignment::Center)
+        .render(chunks[3], buf);
+
+        // Left-align the steps inside a centered column
+        let steps: Vec<Line> = self.steps.iter().map(Splash::step_line).collect();
+        let steps_width = steps.iter().map(|line| line.width() as u16).max().unwrap_or(0);
+        let steps_area = Rect {
+            x: chunks[4].x + chunks[4].width.saturating_sub(steps_width) / 2,
+            width: steps_width.min(chunks[4].width),
+            ..chunks[4]
+        };
+        Paragraph::new(steps).render(steps_area, buf);
+    }
+}
@@ -1,34 +1,54 @@
 // In src/ui/components/thread.rs
-use std::{collections::{HashMap, HashSet, VecDeque}, sync::Arc};
+use std::{cmp::Ordering, collections::{HashMap, HashSet, VecDeque}, sync::Arc};
 use atrium_api::{app::bsky::feed::{
-    defs::{PostViewData, ThreadViewPostParentRefs, ThreadViewPostRepliesItem}, get_post_thread::OutputThreadRefs
-}, types::Unknown};
+    defs::{PostView, PostViewData, ThreadViewPost, ThreadViewPostParentRefs, ThreadViewPostRepliesItem}, get_post_thread::OutputThreadRefs
+}, types::{LimitedU16, Union, Unknown}};
 use log::info;
 use ratatui::{
     buffer::Buffer, layout::Rect, style::{Color, Style}, widgets::{Block, Borders, StatefulWidget, Widget}
 };
 
+use crate::{client::bluesky_client::BlueskyClient, ui::filters::{FilterPipeline, ViewKind}};
+
 use super::{
     images::ImageManager,
     post::{types::{PostContext, PostState}, Post},
-    post_list::{PostList, PostListBase}
+    post_list::{PostHeights, PostList, PostListBase, SharedHeightCache}
 };
 
 use anyhow::Result;
 
+// Deeper replies keep their place in the tree but stop moving right
+const MAX_VISUAL_INDENT: u16 = 8;
+
 #[derive(Debug, Clone)]
 pub struct ThreadRelationships {
     visible_posts: HashSet<String>,
     indent_levels: HashMap<String, u16>,
     post_to_parent: HashMap<String, String>,
+    // Replies whose branches are folded away; survives rebuilding the relationships
+    collapsed: HashSet<String>,
+    // Collapsed post -> how many replies it is hiding
+    hidden_counts: HashMap<String, usize>,
+    // Hidden reply -> the collapsed post hiding it
+    hidden_under: HashMap<String, String>,
+    // Replies with no sibling or load more row after them
+    last_children: HashSet<String>,
+    // Rows that are the last child of their parent, placeholders and load more included
+    last_child_rows: HashSet<usize>,
 }
 
 impl ThreadRelationships {
-    fn new() -> Self {
+    fn new(collapsed: HashSet<String>) -> Self {
         Self {
             visible_posts: HashSet::new(),
             indent_levels: HashMap::new(),
             post_to_parent: HashMap::new(),
+            collapsed,
+            hidden_counts: HashMap::new(),
+            hidden_under: HashMap::new(),
+            last_children: HashSet::new(),
+            last_child_rows: HashSet::new(),
         }
     }
 
@@ -36,6 +56,15 @@ impl ThreadRelationships {
         self.indent_levels.get(uri).copied().unwrap_or(0)
     }
 
+    // Columns a post is shifted right by when rendered
+    pub fn x_offset(&self, uri: &str) -> u16 {
+        Self::indent_to_x_offset(self.get_indent_level(uri))
+    }
+
+    fn indent_to_x_offset(indent_level: u16) -> u16 {
+        indent_level.min(MAX_VISUAL_INDENT) * 2
+    }
+
     fn mark_visible(&mut self, post_uri: &str, parent_uri: Option<&str>, indent_level: u16) {
         self.visible_posts.insert(post_uri.to_string());
         self.indent_levels.insert(post_uri.to_string(), indent_level);
@@ -44,33 +73,222 @@ impl ThreadRelationships {
         }
     }
 
+    // Place a reply in the tree, hidden if any of its ancestors is collapsed
+    fn mark_reply(&mut self, post_uri: &str, parent_uri: &str, indent_level: u16) {
+        let hiding_post = if self.collapsed.contains(parent_uri) && self.is_visible(parent_uri) {
+            Some(parent_uri.to_string())
+        } else {
+            self.hidden_under.get(parent_uri).cloned()
+        };
+
+        match hiding_post {
+            Some(hiding_post) => {
+                self.indent_levels.insert(post_uri.to_string(), indent_level);
+                self.post_to_parent.insert(post_uri.to_string(), parent_uri.to_string());
+                *self.hidden_counts.entry(hiding_post.clone()).or_insert(0) += 1;
+                self.hidden_under.insert(post_uri.to_string(), hiding_post);
+            }
+            None => self.mark_visible(post_uri, Some(parent_uri), indent_level),
+        }
+    }
+
+    fn is_in_tree(&self, uri: &str) -> bool {
+        self.indent_levels.contains_key(uri)
+    }
+
     fn is_visible(&self, uri: &str) -> bool {
         self.visible_posts.contains(uri)
     }
+
+    fn has_replies(&self, uri: &str) -> bool {
+        self.post_to_parent.values().any(|parent| parent == uri)
+    }
+
+    // Number of replies folded under a collapsed post
+    pub fn hidden_count(&self, uri: &str) -> usize {
+        self.hidden_counts.get(uri).copied().unwrap_or(0)
+    }
+
+    fn mark_last_children(&mut self, rows: &VecDeque<ThreadRow>, posts: &VecDeque<Arc<PostView>>) {
+        // Parent -> index of its last row so far
+        let mut last_rows: HashMap<&str, usize> = HashMap::new();
+        for (row_index, row) in rows.iter().enumerate() {
+            let parent_uri = match row {
+                ThreadRow::Post(index) => posts
+                    .get(*index)
+                    .and_then(|post| self.post_to_parent.get(post.uri.as_str())),
+                ThreadRow::LoadMore { parent_uri, .. } => Some(parent_uri),
+                ThreadRow::Placeholder { parent_uri, .. } => parent_uri.as_ref(),
+                ThreadRow::LoadEarlier { .. } => None,
+            };
+            if let Some(parent_uri) = parent_uri {
+                last_rows.insert(parent_uri.as_str(), row_index);
+            }
+        }
+        self.last_child_rows = last_rows.into_values().collect();
+        self.last_children = self.last_child_rows
+            .iter()
+            .filter_map(|row_index| match rows.get(*row_index) {
+                Some(ThreadRow::Post(index)) => posts.get(*index).map(|post| post.uri.clone()),
+                _ => None,
+            })
+            .collect();
+    }
+
+    pub fn is_last_child_row(&self, row_index: usize) -> bool {
+        self.last_child_rows.contains(&row_index)
+    }
+
+    // Draw the tree lines in the gutter left of a row at `indent`: a ├─ or └─
+    // into the row itself, and a │ for every ancestor with replies still to come.
+    // `area` is the row's full width and height
+    fn render_guides(&self, buf: &mut Buffer, area: Rect, indent: u16, is_last: bool, parent_uri: &str) {
+        let (x, y, lines) = (area.x, area.y, area.height);
+        let column = indent.min(MAX_VISUAL_INDENT);
+        if column == 0 {
+            return;
+        }
+        let style = Style::default().fg(Color::DarkGray);
+        let column_x = |level: u16| x + (level - 1) * 2;
+
+        for line in 0..lines {
+            let guide = match (line, is_last) {
+                (0, true) => "└─",
+                (0, false) => "├─",
+                (_, true) => continue,
+                (_, false) => "│",
+            };
+            buf.set_string(column_x(column), y + line, guide, style);
+        }
+
+        let mut ancestor = parent_uri;
+        loop {
+            let level = self.get_indent_level(ancestor);
+            if level == 0 {
+                break;
+            }
+            // Past the indent cap, deeper ancestors share the row's own column
+            if level < column && !self.last_children.contains(ancestor) {
+                for line in 0..lines {
+                    buf.set_string(column_x(level), y + line, "│", style);
+                }
+            }
+            match self.post_to_parent.get(ancestor) {
+                Some(parent) => ancestor = parent,
+                None => break,
+            }
+        }
+    }
+}
+
+// One selectable line of the thread, in display order
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThreadRow {
+    // Index into Thread::posts and Thread::rendered_posts
+    Post(usize),
+    // Replies to `parent_uri` the server left out of the response
+    LoadMore { parent_uri: String, remaining: i64 },
+    // Parents above `uri`, the topmost post we have, that the server left out
+    LoadEarlier { uri: String },
+    // A deleted or blocked post, either a reply to `parent_uri` or the gap at
+    // the top of the parent chain
+    Placeholder { uri: String, kind: PlaceholderKind, parent_uri: Option<String> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderKind {
+    Deleted,
+    Blocked,
+}
+
+impl PlaceholderKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PlaceholderKind::Deleted => "[post deleted]",
+            PlaceholderKind::Blocked => "[blocked author]",
+        }
+    }
+}
+
+// Order for sibling replies; without one we keep the server's order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplySort {
+    Likes,
+    Newest,
+    Oldest,
+}
+
+impl ReplySort {
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg.to_lowercase().as_str() {
+            "likes" => Some(ReplySort::Likes),
+            "newest" => Some(ReplySort::Newest),
+            "oldest" => Some(ReplySort::Oldest),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReplySort::Likes => "most liked",
+            ReplySort::Newest => "newest",
+            ReplySort::Oldest => "oldest",
+        }
+    }
+
+    fn compare(&self, a: &PostViewData, b: &PostViewData) -> Ordering {
+        match self {
+            ReplySort::Likes => b.like_count.unwrap_or(0).cmp(&a.like_count.unwrap_or(0)),
+            // Timestamps from the appview are all UTC, so they sort as strings
+            ReplySort::Newest => b.indexed_at.as_str().cmp(a.indexed_at.as_str()),
+            ReplySort::Oldest => a.indexed_at.as_str().cmp(b.indexed_at.as_str()),
+        }
+    }
 }
+
 pub struct Thread {
     // pub posts: VecDeque<ThreadViewPost>,
-    pub posts: VecDeque<PostViewData>,
+    // Shared with the matching rendered_posts entry
+    pub posts: VecDeque<Arc<PostView>>,
     pub rendered_posts: Vec<Post>,
-    pub post_heights: HashMap<String, u16>,
+    // Rebuilt from posts by update_relationships; selection and scrolling index into this
+    pub rows: VecDeque<ThreadRow>,
+    pub post_heights: PostHeights,
     pub status_line: Option<String>,
     pub anchor_uri: String,  // URI of the focused post
     pub cached_relationships: Option<ThreadRelationships>,
     pub image_manager: Arc<ImageManager>,
+    pub filters: FilterPipeline,
+    pub reply_sort: Option<ReplySort>,
+    // Post URI -> replies it has that we haven't fetched yet
+    more_replies: HashMap<String, i64>,
+    // Set once a fetch for earlier context turns up nothing
+    earlier_context_exhausted: bool,
+    // Deleted or blocked post the parent chain stops at
+    parent_gap: Option<(String, PlaceholderKind)>,
+    // Post URI -> its deleted or blocked replies
+    reply_placeholders: HashMap<String, Vec<(String, PlaceholderKind)>>,
     base: PostListBase,
 }
 
 
 impl Thread {
-    pub fn new(thread_data: OutputThreadRefs, image_manager: Arc<ImageManager>) -> Self {
+    pub fn new(thread_data: OutputThreadRefs, image_manager: Arc<ImageManager>, height_cache: SharedHeightCache) -> Self {
         info!("Creating new thread");
         let mut thread = Self {
             posts: VecDeque::new(),
             rendered_posts: Vec::new(),
-            post_heights: HashMap::new(),
+            rows: VecDeque::new(),
+            post_heights: PostHeights::new(height_cache),
             status_line: Some("".to_string()),
             anchor_uri: String::new(),
             image_manager,
+            filters: FilterPipeline::for_view(ViewKind::Thread),
+            reply_sort: None,
+            more_replies: HashMap::new(),
+            earlier_context_exhausted: false,
+            parent_gap: None,
+            reply_placeholders: HashMap::new(),
             base: PostListBase::new(),
             cached_relationships: None,
         };
@@ -78,17 +296,109 @@ impl Thread {
         info!("About to process thread data");
         let _ = thread.process_thread_data(thread_data);
         thread.update_relationships();
+        thread.select_anchor();
         info!("After processing, anchor_uri: {}", thread.anchor_uri);
         thread
     }
 
+    // Same as Feed::set_compact
+    pub fn set_compact(&mut self, compact: bool) {
+        if self.base.compact == compact {
+            return;
+        }
+        self.base.compact = compact;
+        self.post_heights.clear();
+        self.base.scroll_offset = self.base.selected_index;
+    }
+
+    // Replace the thread with a fresh copy from the server
+    pub fn reload(&mut self, thread_data: OutputThreadRefs) {
+        self.posts.clear();
+        self.rendered_posts.clear();
+        self.more_replies.clear();
+        self.earlier_context_exhausted = false;
+        self.parent_gap = None;
+        self.reply_placeholders.clear();
+        self.filters.reset_counts();
+        let _ = self.process_thread_data(thread_data);
+        self.apply_reply_sort();
+        self.update_relationships();
+        self.select_anchor();
+    }
+
+    pub fn set_reply_sort(&mut self, sort: ReplySort) {
+        let selected_uri = self.selected_post_index()
+            .and_then(|index| self.posts.get(index))
+            .map(|post| post.uri.clone());
+        self.reply_sort = Some(sort);
+        self.apply_reply_sort();
+        self.update_relationships();
+        if let Some(row) = selected_uri.and_then(|uri| self.row_of(&uri)) {
+            self.select_index(row);
+        }
+    }
+
+    // Reorder posts so every post's replies follow it in the chosen order,
+    // leaving the parent chain and anchor where they are
+    fn apply_reply_sort(&mut self) {
+        let Some(sort) = self.reply_sort else {
+            return;
+        };
+        let Some(anchor_index) = self.posts.iter().position(|post| post.uri == self.anchor_uri) else {
+            return;
+        };
+
+        let mut children: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, post) in self.posts.iter().enumerate().skip(anchor_index + 1) {
+            if let Some(parent_uri) = Self::get_parent_uri_from_record(post) {
+                children.entry(parent_uri).or_default().push(index);
+            }
+        }
+        for siblings in children.values_mut() {
+            siblings.sort_by(|a, b| sort.compare(&self.posts[*a], &self.posts[*b]));
+        }
+
+        // Depth first from the anchor
+        let mut order: Vec<usize> = (0..=anchor_index).collect();
+        let mut stack: Vec<usize> = children
+            .get(self.anchor_uri.as_str())
+            .map(|replies| replies.iter().rev().copied().collect())
+            .unwrap_or_default();
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            if let Some(replies) = children.get(self.posts[index].uri.as_str()) {
+                stack.extend(replies.iter().rev());
+            }
+        }
+
+        // Anything not hanging off the anchor keeps its place at the end
+        let placed: HashSet<usize> = order.iter().copied().collect();
+        order.extend((0..self.posts.len()).filter(|index| !placed.contains(index)));
+
+        let mut posts: Vec<Option<Arc<PostView>>> = self.posts.drain(..).map(Some).collect();
+        let mut rendered_posts: Vec<Option<Post>> = self.rendered_posts.drain(..).map(Some).collect();
+        for index in order {
+            if let (Some(post), Some(rendered_post)) = (posts[index].take(), rendered_posts[index].take()) {
+                self.posts.push_back(post);
+                self.rendered_posts.push(rendered_post);
+            }
+        }
+    }
+
     pub fn update_relationships(&mut self) {
-        let mut relationships = ThreadRelationships::new();
-        
+        let selected_uri = self.selected_post_index()
+            .and_then(|index| self.posts.get(index))
+            .map(|post| post.uri.clone());
+        let collapsed = self.cached_relationships
+            .take()
+            .map(|rels| rels.collapsed)
+            .unwrap_or_default();
+        let mut relationships = ThreadRelationships::new(collapsed);
+
         // First pass: build parent relationships and mark anchor post
         let mut parent_chain = Vec::new();
         let mut current_uri = self.anchor_uri.clone();
-        
+
         // Build chain from anchor post to root
         while let Some(post) = self.find_post_by_uri(&current_uri) {
             parent_chain.push(post.uri.clone());
@@ -108,37 +418,171 @@ impl Thread {
             }
         }
 
-        // Second pass: handle direct replies to anchor post
-        if let Some(anchor_post) = self.find_post_by_uri(&self.anchor_uri) {
-            let anchor_indent = relationships.get_indent_level(&self.anchor_uri);
-            
-            for post in &self.posts {
-                if let Some(parent_uri) = Self::get_parent_uri_from_record(post) {
-                    if parent_uri == anchor_post.uri {
-                        // Only show direct replies to anchor post
-                        relationships.mark_visible(&post.uri, Some(&parent_uri), anchor_indent + 1);
-                    }
+        // Second pass: the reply tree below the anchor. Posts are stored depth
+        // first, so a reply's parent has always been placed before it
+        for post in &self.posts {
+            if relationships.is_in_tree(&post.uri) {
+                continue;
+            }
+            if let Some(parent_uri) = Self::get_parent_uri_from_record(post) {
+                if relationships.is_in_tree(&parent_uri) && !self.is_above_anchor(&parent_uri, &parent_chain) {
+                    let indent = relationships.get_indent_level(&parent_uri) + 1;
+                    relationships.mark_reply(&post.uri, &parent_uri, indent);
                 }
             }
         }
 
+        // The top of the chain is still a reply when the server stopped short of
+        // the root, unless what it stopped at is deleted or blocked
+        let earlier_context = parent_chain
+            .last()
+            .filter(|_| !self.earlier_context_exhausted && self.parent_gap.is_none())
+            .and_then(|topmost| self.find_post_by_uri(topmost))
+            .filter(|topmost| Self::get_parent_uri_from_record(topmost).is_some())
+            .map(|topmost| topmost.uri.clone());
+
+        self.rows = self.build_rows(&relationships);
+        if let Some((uri, kind)) = self.parent_gap.clone() {
+            self.rows.push_front(ThreadRow::Placeholder { uri, kind, parent_uri: None });
+        }
+        if let Some(uri) = earlier_context {
+            self.rows.push_front(ThreadRow::LoadEarlier { uri });
+        }
+        relationships.mark_last_children(&self.rows, &self.posts);
         self.cached_relationships = Some(relationships);
+
+        // Keep the same post selected as rows come and go around it
+        let row = selected_uri.and_then(|uri| self.row_of(&uri));
+        self.base.selected_index = row.unwrap_or(self.base.selected_index).min(self.rows.len().saturating_sub(1));
+        self.base.scroll_offset = self.base.scroll_offset.min(self.base.selected_index);
+    }
+
+    // Visible posts in order. After the last reply we have for a post come
+    // placeholders for its deleted or blocked replies, then a "load more" row
+    // if the server cut it short
+    fn build_rows(&self, relationships: &ThreadRelationships) -> VecDeque<ThreadRow> {
+        let mut rows = VecDeque::new();
+        // (parent indent, its trailing rows) waiting for the branch to end
+        let mut pending: Vec<(u16, Vec<ThreadRow>)> = Vec::new();
+
+        for (index, post) in self.posts.iter().enumerate() {
+            if !relationships.is_visible(&post.uri) {
+                continue;
+            }
+            let indent = relationships.get_indent_level(&post.uri);
+            while pending.last().is_some_and(|(pending_indent, _)| indent <= *pending_indent) {
+                if let Some((_, trailing)) = pending.pop() {
+                    rows.extend(trailing);
+                }
+            }
+
+            rows.push_back(ThreadRow::Post(index));
+            if relationships.collapsed.contains(post.uri.as_str()) {
+                continue;
+            }
+            let mut trailing: Vec<ThreadRow> = self.reply_placeholders
+                .get(post.uri.as_str())
+                .into_iter()
+                .flatten()
+                .map(|(uri, kind)| ThreadRow::Placeholder {
+                    uri: uri.clone(),
+                    kind: *kind,
+                    parent_uri: Some(post.uri.to_string()),
+                })
+                .collect();
+            if let Some(remaining) = self.more_replies.get(post.uri.as_str()) {
+                trailing.push(ThreadRow::LoadMore { parent_uri: post.uri.to_string(), remaining: *remaining });
+            }
+            if !trailing.is_empty() {
+                pending.push((indent, trailing));
+            }
+        }
+        while let Some((_, trailing)) = pending.pop() {
+            rows.extend(trailing);
+        }
+        rows
+    }
+
+    // Parents of the anchor only ever show the branch leading to it
+    fn is_above_anchor(&self, uri: &str, parent_chain: &[String]) -> bool {
+        uri != self.anchor_uri && parent_chain.iter().any(|parent| parent == uri)
     }
 
     fn find_post_by_uri(&self, uri: &str) -> Option<&PostViewData> {
-        self.posts.iter().find(|p| p.uri == uri)
+        self.posts.iter().find(|p| p.uri == uri).map(|post| &post.data)
+    }
+
+    // Row showing the post with this URI, if it isn't folded away
+    pub fn row_of(&self, uri: &str) -> Option<usize> {
+        self.rows.iter().position(|row| match row {
+            ThreadRow::Post(index) => self.posts.get(*index).is_some_and(|post| post.uri == uri),
+            _ => false,
+        })
+    }
+
+    // Index into posts of the selected row, if it's a post
+    pub fn selected_post_index(&self) -> Option<usize> {
+        match self.rows.get(self.base.selected_index) {
+            Some(ThreadRow::Post(index)) => Some(*index),
+            _ => None,
+        }
+    }
+
+    pub fn is_action_selected(&self) -> bool {
+        matches!(
+            self.rows.get(self.base.selected_index),
+            Some(ThreadRow::LoadMore { .. } | ThreadRow::LoadEarlier { .. })
+        )
+    }
+
+    // Run the load more or load earlier row under the cursor, returning a status message
+    pub async fn activate_selected_row(&mut self, api: &impl BlueskyClient) -> Result<String> {
+        match self.rows.get(self.base.selected_index) {
+            Some(ThreadRow::LoadMore { .. }) => Ok(match self.load_more_replies(api).await? {
+                0 => "No more replies to load".to_string(),
+                added => format!("Loaded {} replies", added),
+            }),
+            Some(ThreadRow::LoadEarlier { .. }) => Ok(match self.load_earlier_context(api).await? {
+                0 => "No earlier posts to load".to_string(),
+                added => format!("Loaded {} earlier posts", added),
+            }),
+            _ => Ok(String::new()),
+        }
+    }
+
+    fn select_anchor(&mut self) {
+        if let Some(row) = self.row_of(&self.anchor_uri) {
+            self.base.selected_index = row;
+        }
+    }
+
+    // Select the topmost post we have. Returns false if it isn't the real root
+    // because the server stopped short of it
+    pub fn jump_to_root(&mut self) -> bool {
+        let Some(row) = self.rows.iter().position(|row| matches!(row, ThreadRow::Post(_))) else {
+            return false;
+        };
+        self.select_index(row);
+        !self.rows.iter().take(row).any(|row| matches!(row, ThreadRow::LoadEarlier { .. } | ThreadRow::Placeholder { .. }))
+    }
+
+    pub fn jump_to_anchor(&mut self) {
+        if let Some(row) = self.row_of(&self.anchor_uri) {
+            self.select_index(row);
+        }
     }
 
     pub fn process_thread_data(&mut self, thread_data: OutputThreadRefs) -> Result<()> {
         match thread_data {
             OutputThreadRefs::AppBskyFeedDefsThreadViewPost(post) => {
                 self.anchor_uri = post.post.uri.to_string();
-                
+
                 // Process parent chain first
                 if let Some(parent) = &post.parent {
                     match parent {
-                        atrium_api::types::Union::Refs(parent_refs) => {
-                            self.process_parent_thread(parent_refs)?;
+                        Union::Refs(parent_refs) => {
+                            let mut position = self.posts.len();
+                            self.process_parent_thread(parent_refs, &mut position)?;
                         },
                         _ => {}
                     }
@@ -146,23 +590,11 @@ impl Thread {
 
                 // Add anchor post
                 self.add_post(post.post.data.clone());
+                self.record_missing_replies(&post);
 
-                // Process direct replies only
                 if let Some(replies) = &post.replies {
-                    for reply in replies {
-                        match reply {
-                            atrium_api::types::Union::Refs(reply_refs) => {
-                                match reply_refs {
-                                    ThreadViewPostRepliesItem::ThreadViewPost(reply_post) => {
-                                        // Only add the direct reply, not its replies
-                                        self.add_post(reply_post.post.data.clone());
-                                    },
-                                    _ => {}
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
+                    let mut position = self.posts.len();
+                    self.add_replies(&mut position, &post.post.uri, replies);
                 }
 
                 Ok(())
@@ -171,10 +603,212 @@ impl Thread {
         }
     }
 
+    // Add a reply tree depth first at `position`, so each branch follows its parent.
+    // The focused post and its parents are always shown, only replies are filtered,
+    // and a hidden reply takes its branch with it. Deleted and blocked replies
+    // to `parent_uri` are kept as placeholders. Returns how many posts were added
+    fn add_replies(&mut self, position: &mut usize, parent_uri: &str, replies: &[Union<ThreadViewPostRepliesItem>]) -> usize {
+        let mut added = 0;
+        for reply in replies {
+            let reply_post = match reply {
+                Union::Refs(ThreadViewPostRepliesItem::ThreadViewPost(reply_post)) => reply_post,
+                Union::Refs(ThreadViewPostRepliesItem::NotFoundPost(post)) => {
+                    self.add_reply_placeholder(parent_uri, &post.uri, PlaceholderKind::Deleted);
+                    continue;
+                }
+                Union::Refs(ThreadViewPostRepliesItem::BlockedPost(post)) => {
+                    self.add_reply_placeholder(parent_uri, &post.uri, PlaceholderKind::Blocked);
+                    continue;
+                }
+                Union::Unknown(_) => continue,
+            };
+            if self.find_post_by_uri(&reply_post.post.uri).is_some() {
+                continue;
+            }
+            if !self.filters.allows(&reply_post.post.data, None) {
+                continue;
+            }
+            self.insert_post(*position, reply_post.post.data.clone());
+            *position += 1;
+            added += 1;
+            self.record_missing_replies(reply_post);
+            if let Some(nested) = &reply_post.replies {
+                added += self.add_replies(position, &reply_post.post.uri, nested);
+            }
+        }
+        added
+    }
+
+    fn add_reply_placeholder(&mut self, parent_uri: &str, uri: &str, kind: PlaceholderKind) {
+        let placeholders = self.reply_placeholders.entry(parent_uri.to_string()).or_default();
+        if !placeholders.iter().any(|(existing, _)| existing == uri) {
+            placeholders.push((uri.to_string(), kind));
+        }
+    }
+
+    // The server stops at a depth and reply limit, note what it left out
+    fn record_missing_replies(&mut self, node: &ThreadViewPost) {
+        let returned = node.replies.as_ref().map(|replies| replies.len()).unwrap_or(0) as i64;
+        let remaining = node.post.reply_count.unwrap_or(0) - returned;
+        if remaining > 0 {
+            self.more_replies.insert(node.post.uri.clone(), remaining);
+        } else {
+            self.more_replies.remove(node.post.uri.as_str());
+        }
+    }
+
+    // Fetch the sub-thread under the selected "load more" row and splice its
+    // replies in after the branch we already have. Returns how many were added
+    async fn load_more_replies(&mut self, api: &impl BlueskyClient) -> Result<usize> {
+        let Some(ThreadRow::LoadMore { parent_uri, .. }) = self.rows.get(self.base.selected_index).cloned() else {
+            return Ok(0);
+        };
+
+        let params = atrium_api::app::bsky::feed::get_post_thread::ParametersData {
+            uri: parent_uri.clone(),
+            depth: Some(LimitedU16::MAX),
+            parent_height: Some(LimitedU16::try_from(0).map_err(|e| anyhow::anyhow!(e))?),
+        };
+        let response = api.get_post_thread(params).await?;
+        let Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(node)) = &response.data.thread else {
+            self.more_replies.remove(&parent_uri);
+            self.update_relationships();
+            return Ok(0);
+        };
+
+        let start = self.subtree_end(&parent_uri);
+        let mut position = start;
+        let added = match &node.replies {
+            Some(replies) => self.add_replies(&mut position, &parent_uri, replies),
+            None => 0,
+        };
+
+        // Replies that are deleted or blocked still count, so stop offering
+        // more once a fetch turns up nothing new
+        if added > 0 {
+            self.record_missing_replies(node);
+        } else {
+            self.more_replies.remove(&parent_uri);
+        }
+        let first_new = self.posts.get(start).filter(|_| added > 0).map(|post| post.uri.clone());
+        self.apply_reply_sort();
+        self.update_relationships();
+
+        if let Some(first_new) = first_new {
+            if let Some(row) = self.row_of(&first_new) {
+                self.select_index(row);
+            }
+        }
+        Ok(added)
+    }
+
+    // Re-anchor on the topmost post we have, asking only for its parents, and
+    // put them in front of the chain. Returns how many were added
+    async fn load_earlier_context(&mut self, api: &impl BlueskyClient) -> Result<usize> {
+        let Some(ThreadRow::LoadEarlier { uri }) = self.rows.get(self.base.selected_index).cloned() else {
+            return Ok(0);
+        };
+
+        let params = atrium_api::app::bsky::feed::get_post_thread::ParametersData {
+            uri,
+            depth: Some(LimitedU16::try_from(0).map_err(|e| anyhow::anyhow!(e))?),
+            parent_height: Some(LimitedU16::MAX),
+        };
+        let response = api.get_post_thread(params).await?;
+
+        let mut position = 0;
+        if let Union::Refs(OutputThreadRefs::AppBskyFeedDefsThreadViewPost(node)) = &response.data.thread {
+            if let Some(Union::Refs(parent_refs)) = &node.parent {
+                self.process_parent_thread(parent_refs, &mut position)?;
+            }
+        }
+        let added = position;
+
+        if added == 0 {
+            self.earlier_context_exhausted = true;
+        }
+        // Land on the closest of the new parents so the reading position doesn't jump
+        let closest_new = added.checked_sub(1).and_then(|index| self.posts.get(index)).map(|post| post.uri.clone());
+        self.update_relationships();
+        if let Some(row) = closest_new.and_then(|uri| self.row_of(&uri)) {
+            self.select_index(row);
+        }
+        Ok(added)
+    }
+
+    // Index just past the last reply under `uri`
+    fn subtree_end(&self, uri: &str) -> usize {
+        let Some(start) = self.posts.iter().position(|post| post.uri == uri) else {
+            return self.posts.len();
+        };
+        let mut end = start + 1;
+        while end < self.posts.len() && self.is_descendant(&self.posts[end], uri) {
+            end += 1;
+        }
+        end
+    }
+
+    fn is_descendant(&self, post: &PostViewData, ancestor_uri: &str) -> bool {
+        let mut current = Self::get_parent_uri_from_record(post);
+        while let Some(parent_uri) = current {
+            if parent_uri == ancestor_uri {
+                return true;
+            }
+            current = self.find_post_by_uri(&parent_uri).and_then(Self::get_parent_uri_from_record);
+        }
+        false
+    }
+
     pub fn selected_index(&self) -> usize {
         return self.base.selected_index;
     }
 
+    // Fold or unfold the replies under the selected post. Returns how many
+    // replies were folded away, or None if it has no replies to fold
+    pub fn toggle_collapsed(&mut self) -> Option<usize> {
+        let uri = self.posts.get(self.selected_post_index()?)?.uri.clone();
+        let relationships = self.cached_relationships.as_mut()?;
+        if !relationships.collapsed.remove(&uri) {
+            if !relationships.has_replies(&uri) {
+                return None;
+            }
+            relationships.collapsed.insert(uri.clone());
+        }
+        self.update_relationships();
+
+        // Posts above the anchor only lead to it, there is nothing under them to fold
+        let relationships = self.cached_relationships.as_mut()?;
+        let hidden = relationships.hidden_count(&uri);
+        if relationships.collapsed.contains(&uri) && hidden == 0 {
+            relationships.collapsed.remove(&uri);
+            return None;
+        }
+        Some(hidden)
+    }
+
+    // Lines a row takes up, including the "+ N more replies" line under a collapsed post
+    fn row_height(
+        posts: &VecDeque<Arc<PostView>>,
+        post_heights: &PostHeights,
+        relationships: Option<&ThreadRelationships>,
+        row: &ThreadRow,
+    ) -> u16 {
+        match row {
+            ThreadRow::Post(index) => {
+                let Some(post) = posts.get(*index) else {
+                    return 0;
+                };
+                let height = post_heights.get(post.uri.as_str()).copied().unwrap_or(6);
+                if relationships.is_some_and(|rels| rels.hidden_count(&post.uri) > 0) {
+                    height + 1
+                } else {
+                    height
+                }
+            }
+            ThreadRow::LoadMore { .. } | ThreadRow::LoadEarlier { .. } | ThreadRow::Placeholder { .. } => 1,
+        }
+    }
+
     // Helper to get the parent URI directly from the record field
     fn get_parent_uri_from_record(post: &PostViewData) -> Option<String> {
         if let Unknown::Object(record) = &post.record {
@@ -197,15 +831,16 @@ impl Thread {
         None
     }
 
-    fn process_parent_thread(&mut self, parent_refs: &ThreadViewPostParentRefs) -> Result<()> {
+    // Insert the chain root first at `position`, moving it past each post added
+    fn process_parent_thread(&mut self, parent_refs: &ThreadViewPostParentRefs, position: &mut usize) -> Result<()> {
         match parent_refs {
             ThreadViewPostParentRefs::ThreadViewPost(post) => {
                 if let Some(parent_parent) = &post.parent {
                     match parent_parent {
-                        atrium_api::types::Union::Refs(parent_parent_refs) => {
-                            self.process_parent_thread(parent_parent_refs)?;
+                        Union::Refs(parent_parent_refs) => {
+                            self.process_parent_thread(parent_parent_refs, position)?;
                         },
-                        atrium_api::types::Union::Unknown(unknown_data) => {
+                        Union::Unknown(unknown_data) => {
                             return Err(anyhow::anyhow!(
                                 "Unknown parent's parent data type: {}, data: {:?}",
                                 unknown_data.r#type,
@@ -214,50 +849,58 @@ impl Thread {
                         }
                     }
                 }
-                self.add_post(post.post.data.clone());
+                if self.find_post_by_uri(&post.post.uri).is_none() {
+                    self.insert_post(*position, post.post.data.clone());
+                    *position += 1;
+                }
             }
-            ThreadViewPostParentRefs::NotFoundPost(_) => {
-                // Optionally add a placeholder for not found posts
-                self.status_line = Some("Parent post not found".to_string());
+            // Nothing above these comes back, so they cap the chain
+            ThreadViewPostParentRefs::NotFoundPost(post) => {
+                self.parent_gap = Some((post.uri.clone(), PlaceholderKind::Deleted));
             }
-            ThreadViewPostParentRefs::BlockedPost(_) => {
-                // Optionally add a placeholder for blocked posts
-                self.status_line = Some("Parent post is blocked".to_string());
+            ThreadViewPostParentRefs::BlockedPost(post) => {
+                self.parent_gap = Some((post.uri.clone(), PlaceholderKind::Blocked));
             }
         }
         Ok(())
     }
-    
+
     fn add_post(&mut self, post: PostViewData) {
+        self.insert_post(self.posts.len(), post);
+    }
+
+    fn insert_post(&mut self, position: usize, post: PostViewData) {
         let uri = post.uri.to_string();
-        
+
         // Get indent level from relationships
         let indent_level = self.cached_relationships
             .as_ref()
             .map(|rels| rels.get_indent_level(&uri))
             .unwrap_or(0);
-    
+
         // Create context with proper indentation
         let context = PostContext {
             image_manager: self.image_manager.clone(),
             indent_level,
         };
-    
-        self.rendered_posts.push(Post::new(post.clone().into(), context));
-        self.posts.push_back(post);
-        
-        if uri == self.anchor_uri {
-            self.base.selected_index = self.posts.len() - 1;
-        }
+
+        // Parents are added before their replies, so the parent's author is usually known
+        let reply_to = Self::get_parent_uri_from_record(&post)
+            .and_then(|parent_uri| self.find_post_by_uri(&parent_uri))
+            .map(|parent| parent.author.handle.to_string());
+
+        let post = Arc::new(PostView::from(post));
+        self.rendered_posts.insert(position, Post::new(Arc::clone(&post), context).with_reply_to(reply_to));
+        self.posts.insert(position, post);
     }
 }
 
 impl PostList for Thread {
     fn get_total_height_before_scroll(&self) -> u16 {
-        self.posts
+        self.rows
             .iter()
             .take(self.base.scroll_offset)
-            .filter_map(|post| self.post_heights.get(&post.uri.to_string()))
+            .map(|row| Self::row_height(&self.posts, &self.post_heights, self.cached_relationships.as_ref(), row))
             .sum()
     }
 
@@ -265,11 +908,8 @@ impl PostList for Thread {
         let mut total_height = 0;
         let mut last_visible = self.base.scroll_offset;
 
-        for (i, post) in self.posts.iter().enumerate().skip(self.base.scroll_offset) {
-            let height = self.post_heights
-                .get(&post.uri.to_string())
-                .copied()
-                .unwrap_or(6);
+        for (i, row) in self.rows.iter().enumerate().skip(self.base.scroll_offset) {
+            let height = Self::row_height(&self.posts, &self.post_heights, self.cached_relationships.as_ref(), row);
 
             if total_height + height > area_height {
                 break;
@@ -283,25 +923,40 @@ impl PostList for Thread {
     }
 
     fn ensure_post_heights(&mut self, area: Rect) {
+        let resized = self.post_heights.set_width(area.width);
+        self.post_heights.retain_loaded(self.posts.iter().map(|post| post.uri.as_str()));
         let posts_to_calculate: Vec<_> = self.posts
             .iter()
-            .filter(|post| !self.post_heights.contains_key(&post.uri.to_string()))
+            .filter(|post| !self.post_heights.contains_key(&post.uri))
             .cloned()
             .collect();
 
         for post in posts_to_calculate {
-            let height = PostListBase::calculate_post_height(&post.clone().into(), area.width);
+            // Indented replies wrap in less space
+            let x_offset = self.cached_relationships
+                .as_ref()
+                .map(|rels| rels.x_offset(&post.uri))
+                .unwrap_or(0);
+            let height = match self.base.compact {
+                true => 1,
+                false => self.post_heights.measure(&post, area.width.saturating_sub(x_offset)),
+            };
             self.post_heights.insert(post.uri.to_string(), height);
         }
+
+        // Same as Feed
+        if resized {
+            self.select_index(self.base.selected_index);
+        }
     }
 
     fn scroll_down(&mut self) {
+        if self.rows.is_empty() {
+            return;
+        }
         self.base.handle_scroll_down(
-            &self.posts,
-            |post| self.post_heights
-                .get(&post.uri.to_string())
-                .copied()
-                .unwrap_or(6)
+            &self.rows,
+            |row| Self::row_height(&self.posts, &self.post_heights, self.cached_relationships.as_ref(), row)
         );
     }
 
@@ -309,23 +964,51 @@ impl PostList for Thread {
         self.base.handle_scroll_up();
     }
     fn needs_more_content(&self) -> bool {
-        self.selected_index() > self.posts.len().saturating_sub(5)
+        self.selected_index() > self.rows.len().saturating_sub(5)
     }
 
     fn selected_index(&self) -> usize {
         self.base.selected_index
     }
 
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.base.select_index(
+            index,
+            &self.rows,
+            |row| Self::row_height(&self.posts, &self.post_heights, self.cached_relationships.as_ref(), row)
+        );
+    }
+
     fn get_post(&self, index: usize) -> Option<PostViewData> {
-        self.posts.get(index).cloned()
+        match self.rows.get(index) {
+            Some(ThreadRow::Post(post_index)) => self.posts.get(*post_index).map(|post| post.data.clone()),
+            _ => None,
+        }
     }
 }
 
 impl Widget for &mut Thread {
     fn render(self, area: Rect, buf: &mut Buffer) {
         self.base.last_known_height = area.height;
+        self.base.last_layout.clear();
         self.ensure_post_heights(area);
-        
+
         let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(
@@ -334,46 +1017,142 @@ impl Widget for &mut Thread {
         .title("🌆 Thread View");
 
         let inner_area = block.inner(area);
-    
+
         let relationships = self.cached_relationships.as_ref().unwrap();
         let mut current_y = inner_area.y;
 
         block.render(area, buf);
-        
-        for (i, post) in self.rendered_posts.iter_mut()
-            .enumerate()
-            .skip(self.base.scroll_offset)
-            .filter(|(_, post)| relationships.is_visible(&post.get_uri()))
-        {
-            let post_height = self.post_heights
-                .get(post.get_uri())
-                .copied()
-                .unwrap_or(6);
-            
+
+        for (i, row) in self.rows.iter().enumerate().skip(self.base.scroll_offset) {
             let remaining_height = inner_area.height.saturating_sub(current_y - inner_area.y);
             if remaining_height == 0 {
                 break;
             }
-            
-            let indent_level = relationships.get_indent_level(&post.get_uri());
-            let x_offset = indent_level * 2; // 2 spaces per indent level
-            
-            let post_area = Rect {
-                x: inner_area.x + x_offset,
-                y: current_y,
-                width: inner_area.width.saturating_sub(x_offset),
-                height: remaining_height.min(post_height),
-            };
-            
-            post.render(
-                post_area,
-                buf,
-                &mut PostState {
-                    selected: i == self.base.selected_index,
-                },
-            );
-            
-            current_y = current_y.saturating_add(post_height);
-        }
-    }   
+            let selected = i == self.base.selected_index;
+
+            match row {
+                ThreadRow::Post(index) => {
+                    let Some(post) = self.rendered_posts.get_mut(*index) else {
+                        continue;
+                    };
+                    let post_height = self.post_heights
+                        .get(post.get_uri())
+                        .copied()
+                        .unwrap_or(6);
+
+                    let x_offset = relationships.x_offset(post.get_uri());
+
+                    let uri = post.get_uri().clone();
+                    let hidden = relationships.hidden_count(&uri);
+                    if let Some(parent_uri) = relationships.post_to_parent.get(&uri) {
+                        let lines = (post_height + if hidden > 0 { 1 } else { 0 }).min(remaining_height);
+                        relationships.render_guides(
+                            buf,
+                            Rect { y: current_y, height: lines, ..inner_area },
+                            relationships.get_indent_level(&uri),
+                            relationships.is_last_child_row(i),
+                            parent_uri,
+                        );
+                    }
+
+                    let post_area = Rect {
+                        x: inner_area.x + x_offset,
+                        y: current_y,
+                        width: inner_area.width.saturating_sub(x_offset),
+                        height: remaining_height.min(post_height),
+                    };
+                    self.base.last_layout.push((i, post_area));
+
+                    post.render(
+                        post_area,
+                        buf,
+                        &mut PostState {
+                            selected,
+                            compact: self.base.compact,
+                        },
+                    );
+
+                    current_y = current_y.saturating_add(post_height);
+
+                    if hidden > 0 && current_y < inner_area.y + inner_area.height {
+                        let label = format!("+ {} more {}", hidden, if hidden == 1 { "reply" } else { "replies" });
+                        buf.set_stringn(
+                            post_area.x + 2,
+                            current_y,
+                            label,
+                            post_area.width.saturating_sub(2) as usize,
+                            Style::default().fg(Color::DarkGray),
+                        );
+                        current_y = current_y.saturating_add(1);
+                    }
+                }
+                ThreadRow::LoadEarlier { .. } => {
+                    self.base.last_layout.push((i, Rect { y: current_y, height: 1, ..inner_area }));
+                    let style = if selected {
+                        Style::default().fg(Color::Cyan).bg(Color::DarkGray)
+                    } else {
+                        Style::default().fg(Color::Cyan)
+                    };
+                    buf.set_stringn(
+                        inner_area.x,
+                        current_y,
+                        "↑ Load earlier posts in this conversation (Enter)",
+                        inner_area.width as usize,
+                        style,
+                    );
+                    current_y = current_y.saturating_add(1);
+                }
+                ThreadRow::LoadMore { parent_uri, remaining } => {
+                    self.base.last_layout.push((i, Rect { y: current_y, height: 1, ..inner_area }));
+                    // Sits where the missing replies would go
+                    let indent = relationships.get_indent_level(parent_uri) + 1;
+                    let x_offset = ThreadRelationships::indent_to_x_offset(indent);
+                    relationships.render_guides(buf, Rect { y: current_y, height: 1, ..inner_area }, indent, relationships.is_last_child_row(i), parent_uri);
+                    let style = if selected {
+                        Style::default().fg(Color::Cyan).bg(Color::DarkGray)
+                    } else {
+                        Style::default().fg(Color::Cyan)
+                    };
+                    let label = format!(
+                        "Load {} more {} (Enter)",
+                        remaining,
+                        if *remaining == 1 { "reply" } else { "replies" }
+                    );
+                    buf.set_stringn(
+                        inner_area.x + x_offset,
+                        current_y,
+                        label,
+                        inner_area.width.saturating_sub(x_offset) as usize,
+                        style,
+                    );
+                    current_y = current_y.saturating_add(1);
+                }
+                ThreadRow::Placeholder { kind, parent_uri, .. } => {
+                    self.base.last_layout.push((i, Rect { y: current_y, height: 1, ..inner_area }));
+                    let indent = parent_uri
+                        .as_deref()
+                        .map_or(0, |parent_uri| relationships.get_indent_level(parent_uri) + 1);
+                    let x_offset = ThreadRelationships::indent_to_x_offset(indent);
+                    if let Some(parent_uri) = parent_uri {
+                        relationships.render_guides(buf, Rect { y: current_y, height: 1, ..inner_area }, indent, relationships.is_last_child_row(i), parent_uri);
+                    }
+                    let style = if selected {
+                        Style::default().fg(Color::Gray).bg(Color::DarkGray)
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    };
+                    buf.set_stringn(
+                        inner_area.x + x_offset,
+                        current_y,
+                        kind.label(),
+                        inner_area.width.saturating_sub(x_offset) as usize,
+                        style,
+                    );
+                    current_y = current_y.saturating_add(1);
+                }
+            }
+        }
+
+        self.base.render_scrollbar(area, buf, self.rows.len());
+    }
 }
@@ -0,0 +1,104 @@
+use std::{collections::VecDeque, time::{Duration, Instant}};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap},
+};
+
+// More than this and the oldest goes early
+const MAX_TOASTS: usize = 4;
+const TOAST_WIDTH: u16 = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Error,
+}
+
+impl Severity {
+    fn color(self) -> Color {
+        match self {
+            Severity::Info => Color::Cyan,
+            Severity::Success => Color::Green,
+            Severity::Error => Color::Red,
+        }
+    }
+
+    // Errors stay up long enough to read a long message
+    fn lifetime(self) -> Duration {
+        match self {
+            Severity::Error => Duration::from_secs(8),
+            _ => Duration::from_secs(4),
+        }
+    }
+}
+
+struct Toast {
+    message: String,
+    severity: Severity,
+    expires: Instant,
+}
+
+// Short-lived messages stacked in the bottom right corner, newest lowest.
+// Each goes by itself, so an error doesn't sit in the status line until
+// something else happens to replace it
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            message: message.into(),
+            severity,
+            expires: Instant::now() + severity.lifetime(),
+        });
+    }
+
+    // Drop the ones whose time is up. True if that changed what's on screen
+    pub fn expire(&mut self) -> bool {
+        let before = self.toasts.len();
+        let now = Instant::now();
+        self.toasts.retain(|toast| toast.expires > now);
+        self.toasts.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    // Lines the message wraps to inside a toast's border
+    fn text_height(message: &str, width: u16) -> u16 {
+        let inner = width.saturating_sub(2).max(1) as usize;
+        textwrap::wrap(message, inner).len().max(1) as u16
+    }
+}
+
+impl Widget for &Toasts {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let width = TOAST_WIDTH.min(area.width);
+        let x = area.right().saturating_sub(width);
+        let mut bottom = area.bottom();
+        // Newest at the bottom, older ones above until the area runs out
+        for toast in self.toasts.iter().rev() {
+            let height = Toasts::text_height(&toast.message, width) + 2;
+            if bottom < area.y + height {
+                break;
+            }
+            bottom -= height;
+            let toast_area = Rect { x, y: bottom, width, height };
+            Clear.render(toast_area, buf);
+            let style = Style::default().fg(toast.severity.color());
+            Paragraph::new(toast.message.as_str())
+                .wrap(Wrap { trim: true })
+                .block(Block::default().borders(Borders::ALL).border_style(style))
+                .render(toast_area, buf);
+        }
+    }
+}
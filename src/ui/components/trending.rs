@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use atrium_api::app::bsky::feed::defs::PostViewData;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+use anyhow::Result;
+
+use crate::{client::bluesky_client::{BlueskyClient, TrendingTopic}, ui::emoji};
+
+use super::post_list::{PostList, PostListBase};
+
+// Topic line plus a line of description
+const ROW_HEIGHT: u16 = 2;
+
+// What's trending on Bluesky right now. Enter opens the selected topic
+pub struct TrendingView {
+    pub topics: VecDeque<TrendingTopic>,
+    base: PostListBase,
+}
+
+impl Default for TrendingView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TrendingView {
+    pub fn new() -> Self {
+        Self {
+            topics: VecDeque::new(),
+            base: PostListBase::new(),
+        }
+    }
+
+    pub async fn load(&mut self, api: &impl BlueskyClient) -> Result<()> {
+        self.topics = api.get_trending_topics().await?.into();
+        self.base.selected_index = 0;
+        self.base.scroll_offset = 0;
+        Ok(())
+    }
+
+    pub fn get_selected_topic(&self) -> Option<&TrendingTopic> {
+        self.topics.get(self.base.selected_index)
+    }
+}
+
+impl PostList for TrendingView {
+    fn get_total_height_before_scroll(&self) -> u16 {
+        self.base.scroll_offset as u16 * ROW_HEIGHT
+    }
+
+    fn get_last_visible_index(&self, area_height: u16) -> usize {
+        let visible = (area_height / ROW_HEIGHT).max(1) as usize;
+        (self.base.scroll_offset + visible - 1).min(self.topics.len().saturating_sub(1))
+    }
+
+    fn ensure_post_heights(&mut self, _area: Rect) {}
+
+    fn scroll_down(&mut self) {
+        if self.topics.is_empty() {
+            return;
+        }
+        self.base.handle_scroll_down(&self.topics, |_| ROW_HEIGHT);
+    }
+
+    fn scroll_up(&mut self) {
+        self.base.handle_scroll_up();
+    }
+
+    // The whole list comes in one call
+    fn needs_more_content(&self) -> bool {
+        false
+    }
+
+    fn selected_index(&self) -> usize {
+        self.base.selected_index
+    }
+
+    fn scroll_offset(&self) -> usize {
+        self.base.scroll_offset
+    }
+
+    fn set_scroll_offset(&mut self, offset: usize) {
+        self.base.scroll_offset = offset;
+    }
+
+    fn viewport_height(&self) -> u16 {
+        self.base.last_known_height
+    }
+
+    fn last_layout(&self) -> &[(usize, Rect)] {
+        &self.base.last_layout
+    }
+
+    fn select_index(&mut self, index: usize) {
+        self.base.select_index(index, &self.topics, |_| ROW_HEIGHT);
+    }
+
+    // Topics aren't posts
+    fn get_post(&self, _index: usize) -> Option<PostViewData> {
+        None
+    }
+}
+
+impl Widget for &mut TrendingView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("📈 Trending (Enter to open)");
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        self.base.last_known_height = inner_area.height;
+        self.base.last_layout.clear();
+
+        if self.topics.is_empty() {
+            Paragraph::new(Span::styled("Nothing trending right now", Style::default().fg(Color::DarkGray)))
+                .render(inner_area, buf);
+            return;
+        }
+
+        let mut current_y = inner_area.y;
+        for (i, topic) in self.topics.iter().enumerate().skip(self.base.scroll_offset) {
+            if current_y + ROW_HEIGHT > inner_area.y + inner_area.height {
+                break;
+            }
+            let row_area = Rect { y: current_y, height: ROW_HEIGHT, ..inner_area };
+            self.base.last_layout.push((i, row_area));
+            let row_style = if i == self.base.selected_index {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+
+            let description = topic.description.as_deref().unwrap_or("").replace('\n', " ");
+            let lines = vec![
+                Line::from(vec![
+                    Span::styled(format!("{}. ", i + 1), Style::default().fg(Color::DarkGray)),
+                    Span::styled(emoji::sanitize(topic.name()).into_owned(), Style::default().add_modifier(Modifier::BOLD)),
+                ]),
+                Line::from(Span::styled(emoji::sanitize(&description).into_owned(), Style::default().fg(Color::Gray))),
+            ];
+            Paragraph::new(lines).style(row_style).render(row_area, buf);
+
+            current_y += ROW_HEIGHT;
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use serde::{Deserialize, Serialize};
+
+// Many terminals disagree with unicode-width about how wide flags, ZWJ
+// sequences and emoji presentation selectors are, which shifts everything
+// after them on the line and corrupts post borders. Safe mode swaps those
+// sequences for narrow fallbacks before text is measured or drawn.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmojiMode {
+    // Decide from the terminal we're running in
+    #[default]
+    Auto,
+    // Render everything as-is
+    Native,
+    // Always substitute problematic sequences
+    Safe,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmojiSettings {
+    pub mode: EmojiMode,
+    // Exact sequences to replace, checked before the built-in rules
+    pub replacements: HashMap<String, String>,
+}
+
+// Terminals known to get wide emoji wrong (true) or right (false),
+// matched against $TERM_PROGRAM and then $TERM
+const TERMINAL_DEFAULTS: &[(&str, bool)] = &[
+    ("apple_terminal", true),
+    ("vscode", true),
+    ("linux", true),
+    ("screen", true),
+    ("tmux", true),
+    ("kitty", false),
+    ("wezterm", false),
+    ("iterm.app", false),
+    ("ghostty", false),
+    ("foot", false),
+];
+
+struct EmojiFallback {
+    enabled: bool,
+    replacements: Vec<(String, String)>,
+}
+
+static FALLBACK: OnceLock<RwLock<EmojiFallback>> = OnceLock::new();
+
+fn fallback() -> &'static RwLock<EmojiFallback> {
+    FALLBACK.get_or_init(|| RwLock::new(EmojiFallback { enabled: false, replacements: Vec::new() }))
+}
+
+pub fn terminal_needs_fallback() -> bool {
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default().to_lowercase();
+    let term = std::env::var("TERM").unwrap_or_default().to_lowercase();
+
+    for (name, needs_fallback) in TERMINAL_DEFAULTS {
+        if term_program == *name {
+            return *needs_fallback;
+        }
+    }
+    for (name, needs_fallback) in TERMINAL_DEFAULTS {
+        if term.starts_with(name) || term.contains(&format!("-{}", name)) {
+            return *needs_fallback;
+        }
+    }
+
+    // Unknown terminals are left alone
+    false
+}
+
+pub fn configure(settings: &EmojiSettings) {
+    let enabled = match settings.mode {
+        EmojiMode::Auto => terminal_needs_fallback(),
+        EmojiMode::Native => false,
+        EmojiMode::Safe => true,
+    };
+
+    // Longest sequences first so overlapping entries behave predictably
+    let mut replacements: Vec<(String, String)> = settings.replacements
+        .iter()
+        .map(|(from, to)| (from.clone(), to.clone()))
+        .collect();
+    replacements.sort_by_key(|(from, _)| std::cmp::Reverse(from.len()));
+
+    log::info!("Emoji fallback mode {:?}, enabled: {}", settings.mode, enabled);
+    if let Ok(mut state) = fallback().write() {
+        *state = EmojiFallback { enabled, replacements };
+    }
+}
+
+pub fn is_enabled() -> bool {
+    fallback().read().map(|state| state.enabled).unwrap_or(false)
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+fn is_tag(c: char) -> bool {
+    ('\u{E0020}'..='\u{E007F}').contains(&c)
+}
+
+const ZWJ: char = '\u{200D}';
+const VARIATION_SELECTOR_16: char = '\u{FE0F}';
+const WAVING_BLACK_FLAG: char = '\u{1F3F4}';
+
+pub fn sanitize(text: &str) -> Cow<'_, str> {
+    let Ok(state) = fallback().read() else {
+        return Cow::Borrowed(text);
+    };
+    if !state.enabled || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut text = text.to_string();
+    for (from, to) in &state.replacements {
+        if text.contains(from.as_str()) {
+            text = text.replace(from.as_str(), to);
+        }
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_regional_indicator(c) {
+            // A pair of regional indicators is a flag, spell out the country code
+            match chars.peek().copied() {
+                Some(next) if is_regional_indicator(next) => {
+                    chars.next();
+                    let letter = |r: char| char::from(b'A' + (r as u32 - 0x1F1E6) as u8);
+                    output.push('[');
+                    output.push(letter(c));
+                    output.push(letter(next));
+                    output.push(']');
+                }
+                _ => {}
+            }
+        } else if c == WAVING_BLACK_FLAG && chars.peek().is_some_and(|&n| is_tag(n)) {
+            // Subdivision flags (England, Scotland, ...) are built from tag characters
+            while chars.peek().is_some_and(|&n| is_tag(n)) {
+                chars.next();
+            }
+            output.push_str("[flag]");
+        } else if c == ZWJ {
+            // Keep the first emoji of a ZWJ sequence and drop the joined parts
+            chars.next();
+            while chars.peek().is_some_and(|&n| n == VARIATION_SELECTOR_16 || is_skin_tone(n)) {
+                chars.next();
+            }
+        } else if c == VARIATION_SELECTOR_16 || is_skin_tone(c) {
+            continue;
+        } else {
+            output.push(c);
+        }
+    }
+
+    Cow::Owned(output)
+}
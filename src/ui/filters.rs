@@ -0,0 +1,362 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{OnceLock, RwLock};
+use atrium_api::{app::bsky::feed::defs::PostViewData, types::Unknown};
+use ipld_core::ipld::Ipld;
+use serde::{Deserialize, Serialize};
+
+use super::components::post::reason::PostReason;
+
+// Every post-list view runs incoming posts through a FilterPipeline built
+// from these settings, so a hidden post never reaches the view at all.
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterRules {
+    // Case-insensitive words or phrases
+    pub muted_words: Vec<String>,
+    // Only show posts tagged with one of these languages; empty shows everything
+    pub languages: Vec<String>,
+    pub hide_reposts: bool,
+    // Posts that reply to another post. Never applied inside a thread
+    pub hide_replies: bool,
+    // Hide posts with fewer likes than this; 0 disables
+    pub min_likes: i64,
+}
+
+// Per-view replacements for individual rules; unset fields use the global rule
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterOverrides {
+    pub muted_words: Option<Vec<String>>,
+    pub languages: Option<Vec<String>>,
+    pub hide_reposts: Option<bool>,
+    pub hide_replies: Option<bool>,
+    pub min_likes: Option<i64>,
+}
+
+// Unlike the rules above, a match isn't hidden outright: it's folded behind
+// a one line placeholder that can be opened
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollapseRule {
+    // Case-insensitive keyword, or a regular expression when `regex` is set
+    pub pattern: String,
+    pub regex: bool,
+    // Shown on the placeholder instead of the pattern
+    pub label: Option<String>,
+    // ViewKind keys the rule applies to; empty means every feed
+    pub views: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterSettings {
+    #[serde(flatten)]
+    pub rules: FilterRules,
+    // Keyed by ViewKind::key, e.g. "thread"
+    pub overrides: HashMap<String, FilterOverrides>,
+    pub collapse: Vec<CollapseRule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewKind {
+    Timeline,
+    Likes,
+    AuthorFeed,
+    Thread,
+    Search,
+}
+
+impl ViewKind {
+    pub fn key(&self) -> &'static str {
+        match self {
+            ViewKind::Timeline => "timeline",
+            ViewKind::Likes => "likes",
+            ViewKind::AuthorFeed => "author_feed",
+            ViewKind::Thread => "thread",
+            ViewKind::Search => "search",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterKind {
+    MutedWord,
+    Language,
+    Repost,
+    Reply,
+    Threshold,
+}
+
+impl FilterKind {
+    const ALL: [FilterKind; 5] = [FilterKind::MutedWord, FilterKind::Language, FilterKind::Repost, FilterKind::Reply, FilterKind::Threshold];
+
+    fn label(&self) -> &'static str {
+        match self {
+            FilterKind::MutedWord => "Muted words",
+            FilterKind::Language => "Languages",
+            FilterKind::Repost => "Reposts",
+            FilterKind::Reply => "Replies",
+            FilterKind::Threshold => "Like threshold",
+        }
+    }
+}
+
+static SETTINGS: OnceLock<RwLock<FilterSettings>> = OnceLock::new();
+
+fn settings() -> &'static RwLock<FilterSettings> {
+    SETTINGS.get_or_init(|| RwLock::new(FilterSettings::default()))
+}
+
+pub fn configure(filter_settings: &FilterSettings) {
+    if let Ok(mut current) = settings().write() {
+        *current = filter_settings.clone();
+    }
+}
+
+fn rules_for(kind: ViewKind) -> FilterRules {
+    let Ok(settings) = settings().read() else {
+        return FilterRules::default();
+    };
+    let mut rules = settings.rules.clone();
+    if let Some(overrides) = settings.overrides.get(kind.key()) {
+        if let Some(muted_words) = &overrides.muted_words {
+            rules.muted_words = muted_words.clone();
+        }
+        if let Some(languages) = &overrides.languages {
+            rules.languages = languages.clone();
+        }
+        if let Some(hide_reposts) = overrides.hide_reposts {
+            rules.hide_reposts = hide_reposts;
+        }
+        if let Some(hide_replies) = overrides.hide_replies {
+            rules.hide_replies = hide_replies;
+        }
+        if let Some(min_likes) = overrides.min_likes {
+            rules.min_likes = min_likes;
+        }
+    }
+    rules.muted_words = rules.muted_words.iter().map(|word| word.to_lowercase()).collect();
+    rules
+}
+
+enum CollapseMatcher {
+    Keyword(String),
+    Regex(regex::Regex),
+}
+
+impl CollapseMatcher {
+    fn matches(&self, text: &str) -> bool {
+        match self {
+            CollapseMatcher::Keyword(keyword) => text.to_lowercase().contains(keyword.as_str()),
+            CollapseMatcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+// (label, matcher) for the collapse rules that apply to a view. A bad regex
+// is logged and skipped rather than stopping the rest from working
+fn collapse_rules_for(kind: ViewKind) -> Vec<(String, CollapseMatcher)> {
+    let Ok(settings) = settings().read() else {
+        return Vec::new();
+    };
+    settings.collapse
+        .iter()
+        .filter(|rule| rule.views.is_empty() || rule.views.iter().any(|view| view == kind.key()))
+        .filter_map(|rule| {
+            let matcher = if rule.regex {
+                match regex::Regex::new(&rule.pattern) {
+                    Ok(regex) => CollapseMatcher::Regex(regex),
+                    Err(e) => {
+                        log::warn!("Ignoring collapse rule {:?}: {}", rule.pattern, e);
+                        return None;
+                    }
+                }
+            } else {
+                CollapseMatcher::Keyword(rule.pattern.to_lowercase())
+            };
+            Some((rule.label.clone().unwrap_or_else(|| rule.pattern.clone()), matcher))
+        })
+        .collect()
+}
+
+// Posts a collapse rule matched in one view, and which of them have been opened
+#[derive(Default)]
+pub struct CollapsedPosts {
+    // URI -> label of the rule that matched
+    rules: HashMap<String, String>,
+    expanded: HashSet<String>,
+}
+
+impl CollapsedPosts {
+    pub fn insert(&mut self, uri: String, label: String) {
+        self.rules.insert(uri, label);
+    }
+
+    // The rule label while the post is folded away, None once opened or if no rule matched
+    pub fn rule_for(&self, uri: &str) -> Option<&String> {
+        self.rules.get(uri).filter(|_| !self.expanded.contains(uri))
+    }
+
+    // Open or re-fold a matched post. Returns whether it's now open, None if
+    // no rule matched it
+    pub fn toggle(&mut self, uri: &str) -> Option<bool> {
+        if !self.rules.contains_key(uri) {
+            return None;
+        }
+        if self.expanded.remove(uri) {
+            Some(false)
+        } else {
+            self.expanded.insert(uri.to_string());
+            Some(true)
+        }
+    }
+}
+
+fn record_field<'a>(post: &'a PostViewData, field: &str) -> Option<&'a Ipld> {
+    match &post.record {
+        Unknown::Object(map) => map.get(field).map(|data_model| &**data_model),
+        _ => None,
+    }
+}
+
+pub struct FilterPipeline {
+    view: ViewKind,
+    rules: FilterRules,
+    collapse: Vec<(String, CollapseMatcher)>,
+    hidden: HashMap<FilterKind, usize>,
+}
+
+impl FilterPipeline {
+    pub fn for_view(view: ViewKind) -> Self {
+        Self {
+            view,
+            rules: rules_for(view),
+            collapse: collapse_rules_for(view),
+            hidden: HashMap::new(),
+        }
+    }
+
+    // Label of the first collapse rule matching the post's text
+    pub fn collapse_rule(&self, post: &PostViewData) -> Option<String> {
+        let Some(Ipld::String(text)) = record_field(post, "text") else {
+            return None;
+        };
+        self.collapse
+            .iter()
+            .find(|(_, matcher)| matcher.matches(text))
+            .map(|(label, _)| label.clone())
+    }
+
+    // Check a post, counting it against the first filter that hides it
+    pub fn allows(&mut self, post: &PostViewData, reason: Option<&PostReason>) -> bool {
+        match self.matching_filter(post, reason) {
+            Some(kind) => {
+                *self.hidden.entry(kind).or_insert(0) += 1;
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn matching_filter(&self, post: &PostViewData, reason: Option<&PostReason>) -> Option<FilterKind> {
+        if !self.rules.muted_words.is_empty() {
+            if let Some(Ipld::String(text)) = record_field(post, "text") {
+                let text = text.to_lowercase();
+                if self.rules.muted_words.iter().any(|word| text.contains(word.as_str())) {
+                    return Some(FilterKind::MutedWord);
+                }
+            }
+        }
+
+        // Posts without language tags are kept, we can't tell what they are
+        if !self.rules.languages.is_empty() {
+            if let Some(Ipld::List(langs)) = record_field(post, "langs") {
+                let matches_language = langs.iter().any(|lang| match lang {
+                    Ipld::String(lang) => self.rules.languages.iter().any(|allowed| lang.starts_with(allowed.as_str())),
+                    _ => false,
+                });
+                if !langs.is_empty() && !matches_language {
+                    return Some(FilterKind::Language);
+                }
+            }
+        }
+
+        if self.rules.hide_reposts && matches!(reason, Some(PostReason::Repost { .. })) {
+            return Some(FilterKind::Repost);
+        }
+
+        if self.hides_replies() && record_field(post, "reply").is_some() {
+            return Some(FilterKind::Reply);
+        }
+
+        if self.rules.min_likes > 0 && post.like_count.unwrap_or(0) < self.rules.min_likes {
+            return Some(FilterKind::Threshold);
+        }
+
+        None
+    }
+
+    // A thread is all replies, so hiding them there would leave nothing
+    fn hides_replies(&self) -> bool {
+        self.rules.hide_replies && self.view != ViewKind::Thread
+    }
+
+    // For :filter, which changes a view's rules without touching settings.json
+    pub fn set_hide_reposts(&mut self, hide: bool) {
+        self.rules.hide_reposts = hide;
+    }
+
+    pub fn set_hide_replies(&mut self, hide: bool) {
+        self.rules.hide_replies = hide;
+    }
+
+    pub fn reset_counts(&mut self) {
+        self.hidden.clear();
+    }
+
+    fn is_active(&self, kind: FilterKind) -> bool {
+        match kind {
+            FilterKind::MutedWord => !self.rules.muted_words.is_empty(),
+            FilterKind::Language => !self.rules.languages.is_empty(),
+            FilterKind::Repost => self.rules.hide_reposts,
+            FilterKind::Reply => self.hides_replies(),
+            FilterKind::Threshold => self.rules.min_likes > 0,
+        }
+    }
+
+    fn describe(&self, kind: FilterKind) -> String {
+        match kind {
+            FilterKind::MutedWord => self.rules.muted_words.join(", "),
+            FilterKind::Language => format!("only {}", self.rules.languages.join(", ")),
+            FilterKind::Repost | FilterKind::Reply => "hidden".to_string(),
+            FilterKind::Threshold => format!("fewer than {} likes", self.rules.min_likes),
+        }
+    }
+
+    pub fn status_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("Filters for this view ({})", self.view.key()), String::new()];
+
+        for kind in FilterKind::ALL {
+            let hidden = self.hidden.get(&kind).copied().unwrap_or(0);
+            if self.is_active(kind) {
+                lines.push(format!("{:<16} {:<40} {} hidden", kind.label(), self.describe(kind), hidden));
+            } else {
+                lines.push(format!("{:<16} off", kind.label()));
+            }
+        }
+
+        for (label, _) in &self.collapse {
+            lines.push(format!("{:<16} {}", "Collapsed", label));
+        }
+
+        let total: usize = self.hidden.values().sum();
+        lines.push(String::new());
+        lines.push(format!("{} posts hidden since this view was loaded", total));
+        lines.push(format!(
+            "Configure under \"filters\" in settings.json; per-view overrides go in \"filters.overrides.{}\"",
+            self.view.key()
+        ));
+        lines
+    }
+}
@@ -0,0 +1,125 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+use super::components::info_view::InfoView;
+
+pub struct KeyHelp {
+    pub keys: &'static str,
+    pub action: &'static str,
+}
+
+pub struct CommandHelp {
+    pub name: &'static str,
+    pub args: &'static str,
+    pub action: &'static str,
+}
+
+// Every key handled outside command mode and the composer. Keep in step with
+// App::handle_input
+pub const KEYS: &[KeyHelp] = &[
+    KeyHelp { keys: "j / k", action: "Move down / up, a count first moves further (5j)" },
+    KeyHelp { keys: "PgDn / PgUp", action: "Move a screenful down / up" },
+    KeyHelp { keys: "Ctrl+D / Ctrl+U", action: "Move half a screenful down / up" },
+    KeyHelp { keys: "gg / G", action: "Jump to the first / last post" },
+    KeyHelp { keys: "gn", action: "Load new posts into the timeline" },
+    KeyHelp { keys: "gt / gT", action: "Next / previous tab" },
+    KeyHelp { keys: "< / >", action: "Scroll long lines in the selected post sideways" },
+    KeyHelp { keys: "v", action: "View thread (in notifications, the post it's about)" },
+    KeyHelp { keys: "V", action: "View the quoted post's thread" },
+    KeyHelp { keys: "t / T", action: "In a thread, jump to the root / back to the opened post" },
+    KeyHelp { keys: "z", action: "Fold or unfold replies, a filtered post or a notification group" },
+    KeyHelp { keys: "Enter", action: "Open the selected row: more replies, a profile, a trend, or the post on its own page" },
+    KeyHelp { keys: "n", action: "Notifications" },
+    KeyHelp { keys: "a", action: "Profile of the selected post's author" },
+    KeyHelp { keys: "A", action: "Your own profile" },
+    KeyHelp { keys: "M", action: "On a profile, add the author to or remove them from your lists" },
+    KeyHelp { keys: "L / R", action: "Who liked / reposted the selected post" },
+    KeyHelp { keys: "#", action: "Newest posts for the first hashtag in the selected post" },
+    KeyHelp { keys: "l", action: "Like / unlike" },
+    KeyHelp { keys: "r", action: "Repost / unrepost" },
+    KeyHelp { keys: "f", action: "Follow / unfollow the author" },
+    KeyHelp { keys: "y / Y", action: "Copy the post text / with author and time" },
+    KeyHelp { keys: "m<key>", action: "Mark the selected post" },
+    KeyHelp { keys: "'<key>", action: "Jump back to a mark" },
+    KeyHelp { keys: "/", action: "Search the loaded items in this view" },
+    KeyHelp { keys: "n / N", action: "While searching, the next / previous match" },
+    KeyHelp { keys: "Tab / Shift+Tab", action: "In deck mode, focus the next / previous column" },
+    KeyHelp { keys: ":", action: "Command mode" },
+    KeyHelp { keys: "?", action: "This help" },
+    KeyHelp { keys: "F12", action: "Cache and runtime stats, for when things are slow" },
+    KeyHelp { keys: "Esc", action: "Go back, or close a deck column at its first view" },
+    KeyHelp { keys: "q", action: "Quit" },
+];
+
+// Every command App::handle_command knows. Tab completion reads its names from here
+pub const COMMANDS: &[CommandHelp] = &[
+    CommandHelp { name: "post", args: "", action: "Write a new post" },
+    CommandHelp { name: "reply", args: "", action: "Reply to the selected post" },
+    CommandHelp { name: "delete", args: "", action: "Delete your selected post" },
+    CommandHelp { name: "like", args: "", action: "Like / unlike the selected post" },
+    CommandHelp { name: "repost", args: "", action: "Repost / unrepost the selected post" },
+    CommandHelp { name: "follow", args: "", action: "Follow / unfollow the selected post's author" },
+    CommandHelp { name: "refresh", args: "", action: "Refresh the current view" },
+    CommandHelp { name: "timeline", args: "", action: "Back to the timeline" },
+    CommandHelp { name: "notifications", args: "", action: "Notifications" },
+    CommandHelp { name: "feed", args: "[name]", action: "Switch the timeline's feed, or list saved feeds" },
+    CommandHelp { name: "tag", args: "<tag>", action: "Newest posts with a hashtag" },
+    CommandHelp { name: "trending", args: "", action: "What's trending on Bluesky" },
+    CommandHelp { name: "find", args: "[text]", action: "Search the loaded items in this view (what / starts)" },
+    CommandHelp { name: "goto", args: "<n>", action: "Select the nth item, as counted in the status line" },
+    CommandHelp { name: "likes", args: "", action: "Posts you've liked" },
+    CommandHelp { name: "profile", args: "[handle or DID]", action: "A profile (the selected author's by default)" },
+    CommandHelp { name: "open", args: "<url>", action: "Open a bsky.app link or at:// URI" },
+    CommandHelp { name: "likers", args: "", action: "Who liked the selected post" },
+    CommandHelp { name: "reposters", args: "", action: "Who reposted the selected post" },
+    CommandHelp { name: "sort", args: "likes|newest|oldest", action: "Reorder a thread's replies" },
+    CommandHelp { name: "filter", args: "reposts|replies on|off", action: "Show or hide reposts or replies" },
+    CommandHelp { name: "filters", args: "", action: "Filters active here and what they hid" },
+    CommandHelp { name: "alert", args: "[handle]", action: "Toggle new post alerts for an author" },
+    CommandHelp { name: "alerts", args: "", action: "Authors with alerts on" },
+    CommandHelp { name: "avatar", args: "<path>", action: "Preview and set a new avatar" },
+    CommandHelp { name: "outbox", args: "[edit N|cancel N|retry]", action: "Posts waiting for a connection" },
+    CommandHelp { name: "cancel", args: "", action: "Drop queued likes, reposts and follows" },
+    CommandHelp { name: "restore", args: "[discard]", action: "Reopen what was open before a crash" },
+    CommandHelp { name: "deck", args: "", action: "Open or close the columns beside the timeline" },
+    CommandHelp { name: "density", args: "compact|normal", action: "One line per post, or full posts" },
+    CommandHelp { name: "tabnew", args: "", action: "Open a new tab on the timeline" },
+    CommandHelp { name: "tabclose", args: "", action: "Close the current tab" },
+    CommandHelp { name: "reconnect", args: "", action: "Restart the realtime stream" },
+    CommandHelp { name: "diagnose-images", args: "", action: "Check terminal image support" },
+    CommandHelp { name: "errors", args: "", action: "Recent errors, with when and where they happened" },
+    CommandHelp { name: "listsub", args: "mute|block <list>", action: "Mute or block everyone on a moderation list" },
+    CommandHelp { name: "listunsub", args: "<list>", action: "Stop muting or blocking a list" },
+    CommandHelp { name: "listsubs", args: "", action: "Moderation lists you mute or block" },
+    CommandHelp { name: "newlist", args: "<name>", action: "Make a curated list" },
+    CommandHelp { name: "addtolist", args: "<list> [handle]", action: "Add someone (the selected author) to a list of yours" },
+    CommandHelp { name: "removefromlist", args: "<list> [handle]", action: "Take someone off a list of yours" },
+    CommandHelp { name: "alias", args: "", action: "Command aliases from settings.json" },
+    CommandHelp { name: "help", args: "", action: "This help" },
+    CommandHelp { name: "login", args: "<handle>", action: "Log in" },
+    CommandHelp { name: "logout", args: "", action: "Log out" },
+];
+
+// The `?` overlay: both tables, scrolled with j/k
+pub fn help_view() -> InfoView {
+    let heading = |text: &'static str| Line::from(Span::styled(text, Style::default().add_modifier(Modifier::BOLD)));
+    let row = |left: String, right: &'static str| Line::from(vec![
+        Span::styled(format!("  {:<28}", left), Style::default().fg(Color::Cyan)),
+        Span::raw(right),
+    ]);
+
+    let mut lines = vec![heading("Keys")];
+    lines.extend(KEYS.iter().map(|key| row(key.keys.to_string(), key.action)));
+    lines.push(Line::default());
+    lines.push(heading("Commands"));
+    lines.extend(COMMANDS.iter().map(|command| {
+        let usage = match command.args {
+            "" => format!(":{}", command.name),
+            args => format!(":{} {}", command.name, args),
+        };
+        row(usage, command.action)
+    }));
+    InfoView::new("❔ Help (j/k to scroll, Esc to close)", lines)
+}
@@ -1,13 +1,21 @@
-use crate::ui::App;
+use std::time::Instant;
+use crate::{client::{bluesky_client::BlueskyClient, update::ConnectionStatus}, config::Density, ui::App};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, Paragraph, StatefulWidget},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, StatefulWidget, Tabs},
     Frame,
 };
 
-use super::{components::{command_input::CommandInputState, post::types::PostState, post_composer::PostComposerState}, views::View};
+use super::{components::{command_input::CommandInputState, post::types::PostState, post_composer::PostComposerState, skeleton::SkeletonPosts}, task_manager::TaskManager, views::{View, ViewStack}};
+
+pub fn draw<C: BlueskyClient>(f: &mut Frame, app: &mut App<C>) {
+    if let Some(splash) = &app.splash {
+        f.render_widget(splash, f.area());
+        return;
+    }
 
-pub fn draw(f: &mut Frame, app: &mut App) {
     if !app.authenticated {
         // Show login view
         if let Some(login_view) = &app.login_view {
@@ -77,8 +85,26 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             .split(f.area())
     };
 
+    // With more than one tab, a row of tab labels above the content
+    let content = match app.tab_titles() {
+        Some(titles) => {
+            let tabs = Tabs::new(titles)
+                .select(app.tab_index)
+                .style(Style::default().fg(Color::DarkGray))
+                .highlight_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
+            f.render_widget(tabs, Rect { height: 1, ..chunks[0] });
+            Rect { y: chunks[0].y + 1, height: chunks[0].height.saturating_sub(1), ..chunks[0] }
+        }
+        None => chunks[0],
+    };
+
     // Main content rendering
     match app.view_stack.current_view() {
+        _ if app.avatar_preview.is_some() => {
+            if let Some(preview) = &app.avatar_preview {
+                f.render_widget(preview, content);
+            }
+        },
         View::Thread(thread) if app.composing => {
             // Your existing thread composing logic
             if let Some(_anchor_post) = thread.posts.iter()
@@ -89,10 +115,10 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                     .unwrap();
                 
                 let post_area = Rect {
-                    x: chunks[0].x,
-                    y: chunks[0].y,
-                    width: chunks[0].width,
-                    height: chunks[0].height,
+                    x: content.x,
+                    y: content.y,
+                    width: content.width,
+                    height: content.height,
                 };
 
                 rendered_post.render(
@@ -100,6 +126,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
                     f.buffer_mut(),
                     &mut PostState {
                         selected: false,
+                        compact: false,
                     },
                 );
             }
@@ -123,21 +150,42 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             if let Some(composer) = &app.post_composer {
                 f.render_stateful_widget(
                     composer,
-                    chunks[0],
+                    content,
                     &mut PostComposerState { is_active: true }
                 );
             }
         },
+        _ if !app.deck.is_empty() => draw_deck(f, app, content),
         _ => {
-            match app.view_stack.current_view() {
-                View::Timeline(feed) => f.render_widget(feed, chunks[0]),
-                View::Thread(thread) => f.render_widget(thread, chunks[0]),
-                View::AuthorFeed(author_feed) => f.render_widget(author_feed, chunks[0]),
-                View::Notifications(notification_view) => f.render_widget(notification_view, chunks[0]),
-            }
+            let compact = app.settings.density == Density::Compact;
+            draw_view(f, app.view_stack.current_view(), content, compact);
+            draw_breadcrumb(f, &app.view_stack, content);
+            draw_loading(f, &app.task_manager, app.view_stack.current_view(), content);
         }
     }
 
+    if let Some(help) = &mut app.help {
+        let area = centered(content, 80, 90);
+        f.render_widget(Clear, area);
+        f.render_widget(help, area);
+    }
+
+    if !app.toasts.is_empty() {
+        f.render_widget(&app.toasts, content);
+    }
+
+    if let Some(picker) = &app.list_picker {
+        f.render_widget(picker, content);
+    }
+
+    if let Some((dialog, _)) = &app.confirming {
+        f.render_widget(dialog, content);
+    }
+
+    if app.debug_overlay {
+        draw_debug_overlay(f, app, content);
+    }
+
     // Command input and status line rendering
     if app.command_mode {
         // Render debug borders around command input chunk
@@ -162,10 +210,166 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             .inner(chunks[2]);
         
         f.render_widget(
-            Paragraph::new(app.status_line.clone()),
+            Paragraph::new(status_text(app)).style(status_style(app)),
             status_area
         );
     } else {
-        f.render_widget(Paragraph::new(app.status_line.clone()), chunks[chunks.len() - 1]);
+        f.render_widget(Paragraph::new(status_text(app)).style(status_style(app)), chunks[chunks.len() - 1]);
+    }
+}
+
+// A rect `width_percent` by `height_percent` of `area`, in its middle
+// `compact` is the density setting, applied as the view is drawn so every
+// view follows it however it was opened
+fn draw_view(f: &mut Frame, view: &mut View, area: Rect, compact: bool) {
+    view.set_compact(compact);
+    match view {
+        View::Timeline(feed) => f.render_widget(feed, area),
+        View::Thread(thread) => f.render_widget(thread, area),
+        View::AuthorFeed(author_feed) => f.render_widget(author_feed, area),
+        View::Notifications(notification_view) => f.render_widget(notification_view, area),
+        View::Info(info_view) => f.render_widget(info_view, area),
+        View::ProfileList(profile_list) => f.render_widget(profile_list, area),
+        View::Trending(trending) => f.render_widget(trending, area),
+        View::PostDetail(detail) => f.render_widget(detail, area),
+    }
+}
+
+// While a thread or profile is on its way to the top of the stack, or the
+// view showing is still empty, skeleton posts stand in for it under a spinner
+fn draw_loading(f: &mut Frame, tasks: &TaskManager, view: &View, area: Rect) {
+    let label = match tasks.opening() {
+        Some(label) => label,
+        None if tasks.is_busy() && view.item_count() == 0 && !matches!(view, View::Info(_) | View::PostDetail(_)) => "Loading",
+        None => return,
+    };
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("{} {}...", tasks.spinner(), label));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    f.render_widget(SkeletonPosts, inner);
+}
+
+// "Timeline ▸ @alice ▸ Thread" over the right of the top border, once there's
+// somewhere for Esc to go back to. The oldest steps give way when it's too long
+fn draw_breadcrumb(f: &mut Frame, stack: &ViewStack, area: Rect) {
+    let mut steps = stack.breadcrumb();
+    if steps.len() < 2 {
+        return;
+    }
+    let room = area.width.saturating_sub(4) as usize;
+    let mut text = format!(" {} ", steps.join(" ▸ "));
+    while Line::raw(text.as_str()).width() > room && steps.len() > 1 {
+        steps.remove(0);
+        text = format!(" … ▸ {} ", steps.join(" ▸ "));
+    }
+    let width = (Line::raw(text.as_str()).width() as u16).min(area.width.saturating_sub(2));
+    f.buffer_mut().set_stringn(
+        area.right().saturating_sub(width + 1),
+        area.y,
+        text,
+        width as usize,
+        Style::default().fg(Color::Gray),
+    );
+}
+
+// Deck mode: each column's top view side by side, the focused one outlined in cyan
+fn draw_deck<C: BlueskyClient>(f: &mut Frame, app: &mut App<C>, area: Rect) {
+    let compact = app.settings.density == Density::Compact;
+    let count = app.deck.len() + 1;
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, count as u32); count])
+        .split(area);
+    for (i, &column_area) in columns.iter().enumerate() {
+        let stack = match i.cmp(&app.deck_focus) {
+            std::cmp::Ordering::Equal => &mut app.view_stack,
+            std::cmp::Ordering::Less => &mut app.deck[i],
+            std::cmp::Ordering::Greater => &mut app.deck[i - 1],
+        };
+        draw_view(f, stack.current_view(), column_area, compact);
+        draw_breadcrumb(f, stack, column_area);
+        if i == app.deck_focus {
+            draw_loading(f, &app.task_manager, app.view_stack.current_view(), column_area);
+        }
+    }
+
+    // Recolour the focused column's border, keeping its title
+    let focused = columns[app.deck_focus];
+    let edges = [
+        Rect { height: 1, ..focused },
+        Rect { y: focused.bottom().saturating_sub(1), height: 1, ..focused },
+        Rect { width: 1, ..focused },
+        Rect { x: focused.right().saturating_sub(1), width: 1, ..focused },
+    ];
+    for edge in edges {
+        f.buffer_mut().set_style(edge, Style::default().fg(Color::Cyan));
+    }
+}
+
+// The stats box in the top right corner, over whatever is there
+fn draw_debug_overlay<C: BlueskyClient>(f: &mut Frame, app: &App<C>, area: Rect) {
+    let lines: Vec<Line> = app.debug_lines().into_iter().map(Line::from).collect();
+    let width = lines.iter().map(|line| line.width() as u16).max().unwrap_or(0) + 2;
+    let overlay = Rect {
+        x: area.right().saturating_sub(width + 1),
+        y: area.y + 1,
+        width: width.min(area.width),
+        height: (lines.len() as u16 + 2).min(area.height.saturating_sub(1)),
+    };
+    f.render_widget(Clear, overlay);
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Magenta))
+                .title("Debug (F12)"),
+        ),
+        overlay,
+    );
+}
+
+fn centered(area: Rect, width_percent: u16, height_percent: u16) -> Rect {
+    let width = area.width * width_percent / 100;
+    let height = area.height * height_percent / 100;
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+// Highlighted for a moment when a reply or mention comes in
+fn status_style<C: BlueskyClient>(app: &App<C>) -> Style {
+    match app.status_flash_until {
+        Some(until) if Instant::now() < until => Style::default().fg(Color::Black).bg(Color::Yellow),
+        _ => Style::default(),
+    }
+}
+
+// Status line with a dot for the realtime stream, when it's on, and a badge
+// while we're showing saved content
+fn status_text<C: BlueskyClient>(app: &App<C>) -> Line<'static> {
+    let mut spans = Vec::new();
+    if let Some(status) = app.realtime_status {
+        let (dot, color) = match status {
+            ConnectionStatus::Connected => ("●".to_string(), Color::Green),
+            ConnectionStatus::Reconnecting { attempt } => (format!("◌{}", attempt), Color::Yellow),
+            ConnectionStatus::Disconnected => ("○".to_string(), Color::Red),
+        };
+        spans.push(Span::styled(dot, Style::default().fg(color)));
+        spans.push(Span::raw(" "));
+    }
+    if app.api.is_offline() {
+        spans.push(Span::raw("📴 Offline · "));
+    }
+    if let Some(activity) = app.task_manager.status() {
+        spans.push(Span::styled(activity, Style::default().fg(Color::Cyan)));
+        spans.push(Span::raw(" · "));
     }
+    spans.push(Span::raw(app.status_line.clone()));
+    Line::from(spans)
 }
@@ -2,6 +2,13 @@ pub mod app;
 pub mod components;
 pub mod views;
 pub mod layout;
+pub mod clipboard;
+pub mod emoji;
+pub mod filters;
+pub mod recovery;
+pub mod session;
+pub mod help;
+pub mod task_manager;
 
 // Re-export commonly used items
 pub use app::App;
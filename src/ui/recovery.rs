@@ -0,0 +1,146 @@
+use std::{path::{Path, PathBuf}, sync::Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use super::{components::{feed::FeedSource, profile_list::ProfileListSource}, views::View};
+
+// Written by the panic hook, offered back with :restore on the next launch
+pub const RECOVERY_PATH: &str = "recovery.json";
+
+// Enough of a view to rebuild it from the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ViewSummary {
+    Timeline { selected_uri: Option<String> },
+    Likes,
+    // The Timeline view showing a feed generator picked with :feed
+    Generator { uri: String, name: String },
+    Hashtag { tag: String },
+    Search { query: String },
+    Trending,
+    Thread { anchor_uri: String },
+    PostDetail { uri: String },
+    AuthorFeed { did: String },
+    Notifications,
+    Info { title: String },
+    ProfileList { source: ProfileListSource },
+}
+
+impl ViewSummary {
+    pub fn from_view(view: &View) -> Self {
+        match view {
+            View::Timeline(feed) => match &feed.source {
+                FeedSource::Timeline => ViewSummary::Timeline {
+                    selected_uri: view.get_selected_post().map(|post| post.uri.clone()),
+                },
+                FeedSource::Likes { .. } => ViewSummary::Likes,
+                FeedSource::Generator { uri, name } => ViewSummary::Generator {
+                    uri: uri.clone(),
+                    name: name.clone(),
+                },
+                FeedSource::Hashtag { tag } => ViewSummary::Hashtag { tag: tag.clone() },
+                FeedSource::Search { query } => ViewSummary::Search { query: query.clone() },
+            },
+            View::Thread(thread) => ViewSummary::Thread {
+                anchor_uri: thread.anchor_uri.clone(),
+            },
+            View::AuthorFeed(author_feed) => ViewSummary::AuthorFeed {
+                did: author_feed.profile.profile.did.as_str().to_string(),
+            },
+            View::Notifications(_) => ViewSummary::Notifications,
+            View::Info(info_view) => ViewSummary::Info {
+                title: info_view.title.clone(),
+            },
+            View::ProfileList(profile_list) => ViewSummary::ProfileList {
+                source: profile_list.source.clone(),
+            },
+            View::Trending(_) => ViewSummary::Trending,
+            View::PostDetail(detail) => ViewSummary::PostDetail {
+                uri: detail.post.data.uri.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecoverySnapshot {
+    pub views: Vec<ViewSummary>,
+    pub draft: Option<String>,
+    pub draft_reply_to: Option<String>,
+    pub command_history: Vec<String>,
+    // Only for crash reports: the focused view and what's selected in it
+    #[serde(skip)]
+    pub focus: String,
+}
+
+// The panic hook can't reach the App, so the event loop keeps a copy here
+static LATEST_SNAPSHOT: Mutex<Option<RecoverySnapshot>> = Mutex::new(None);
+
+pub fn record(snapshot: RecoverySnapshot) {
+    if let Ok(mut latest) = LATEST_SNAPSHOT.lock() {
+        *latest = Some(snapshot);
+    }
+}
+
+// Called from the panic hook. Never blocks: if the lock is held or poisoned
+// we give up rather than risk hanging the crash.
+pub fn write_on_panic() {
+    let Ok(latest) = LATEST_SNAPSHOT.try_lock() else {
+        return;
+    };
+    if let Some(snapshot) = latest.as_ref() {
+        if let Ok(contents) = serde_json::to_string_pretty(snapshot) {
+            let _ = std::fs::write(RECOVERY_PATH, contents);
+        }
+    }
+}
+
+// Called from the panic hook with the panic's message and location. Writes
+// crash-<time>.txt into `dir` and returns its path. Like write_on_panic it
+// skips anything it can't lock
+pub fn write_crash_report(panic: &str, dir: &Path) -> Option<PathBuf> {
+    let now = chrono::Local::now();
+    let mut report = format!(
+        "Skyline {} crashed at {}\n\n{}\n\n",
+        env!("CARGO_PKG_VERSION"),
+        now.format("%Y-%m-%d %H:%M:%S %:z"),
+        panic,
+    );
+
+    if let Ok(latest) = LATEST_SNAPSHOT.try_lock() {
+        if let Some(snapshot) = latest.as_ref() {
+            report.push_str(&format!("Focused view: {}\n", snapshot.focus));
+            report.push_str("Views in the main column, oldest first:\n");
+            for view in &snapshot.views {
+                report.push_str(&format!("  {:?}\n", view));
+            }
+            report.push('\n');
+        }
+    }
+
+    report.push_str(&format!("Backtrace:\n{}\n\n", std::backtrace::Backtrace::force_capture()));
+
+    report.push_str("Last log lines:\n");
+    for line in crate::logging::recent_lines() {
+        report.push_str(&line);
+        report.push('\n');
+    }
+
+    let path = dir.join(format!("crash-{}.txt", now.format("%Y%m%d-%H%M%S")));
+    std::fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+impl RecoverySnapshot {
+    // Load and remove the recovery file so it is only offered once
+    pub fn take() -> Option<Self> {
+        let contents = std::fs::read_to_string(RECOVERY_PATH).ok()?;
+        let _ = std::fs::remove_file(RECOVERY_PATH);
+        match serde_json::from_str(&contents) {
+            Ok(snapshot) => Some(snapshot),
+            Err(e) => {
+                log::warn!("Ignoring invalid {}: {}", RECOVERY_PATH, e);
+                None
+            }
+        }
+    }
+}
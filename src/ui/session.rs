@@ -0,0 +1,52 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use super::recovery::ViewSummary;
+
+// Written on a clean exit so the next launch can pick up where this one left off
+pub const SESSION_PATH: &str = "session.json";
+
+// Where the timeline was: the cursor of the page holding the selected post,
+// None for the first page
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimelinePosition {
+    pub cursor: Option<String>,
+    pub selected_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSession {
+    pub timeline: Option<TimelinePosition>,
+    // The view stack, bottom first, to reopen on the next launch
+    pub views: Vec<ViewSummary>,
+}
+
+impl SavedSession {
+    pub fn load() -> Self {
+        match std::fs::read_to_string(SESSION_PATH) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(session) => session,
+                Err(e) => {
+                    log::warn!("Ignoring invalid {}: {}", SESSION_PATH, e);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(SESSION_PATH, contents)?;
+        Ok(())
+    }
+
+    pub fn clear() {
+        if let Err(e) = std::fs::remove_file(SESSION_PATH) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove {}: {}", SESSION_PATH, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+const SPINNER_STEP: Duration = Duration::from_millis(100);
+
+// Given out by start and handed back to finish. Not Clone, so an operation
+// can only be finished once
+#[derive(Debug, PartialEq, Eq)]
+pub struct TaskHandle(u64);
+
+struct RunningTask {
+    id: u64,
+    label: String,
+    // Fetching a view that'll be pushed when it arrives
+    opens_view: bool,
+}
+
+// Operations in flight, loading a thread, posting and so on, by name. The
+// status line shows the newest with a spinner while any are running
+pub struct TaskManager {
+    running: Vec<RunningTask>,
+    next_id: u64,
+    started: Instant,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self {
+            running: Vec::new(),
+            next_id: 0,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl TaskManager {
+    pub fn start(&mut self, label: impl Into<String>) -> TaskHandle {
+        self.push(label.into(), false)
+    }
+
+    // Like start, for a thread or profile being fetched to go on top of the
+    // current view, which gets a placeholder until it does
+    pub fn start_view(&mut self, label: impl Into<String>) -> TaskHandle {
+        self.push(label.into(), true)
+    }
+
+    fn push(&mut self, label: String, opens_view: bool) -> TaskHandle {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.running.push(RunningTask { id, label, opens_view });
+        TaskHandle(id)
+    }
+
+    pub fn finish(&mut self, handle: TaskHandle) {
+        self.running.retain(|task| task.id != handle.0);
+    }
+
+    pub fn is_busy(&self) -> bool {
+        !self.running.is_empty()
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.running.iter().map(|task| task.label.as_str())
+    }
+
+    // The newest view being opened, for its placeholder
+    pub fn opening(&self) -> Option<&str> {
+        self.running.iter().rev().find(|task| task.opens_view).map(|task| task.label.as_str())
+    }
+
+    // Moves with the clock, so it turns whenever the screen is drawn
+    pub fn spinner(&self) -> char {
+        let step = (self.started.elapsed().as_millis() / SPINNER_STEP.as_millis()) as usize;
+        SPINNER[step % SPINNER.len()]
+    }
+
+    // "⠹ Loading thread (+1 more)", or None when nothing is running
+    pub fn status(&self) -> Option<String> {
+        let newest = self.running.last()?;
+        let spinner = self.spinner();
+        Some(match self.running.len() - 1 {
+            0 => format!("{} {}...", spinner, newest.label),
+            others => format!("{} {}... (+{} more)", spinner, newest.label, others),
+        })
+    }
+}
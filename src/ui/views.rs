@@ -1,80 +1,154 @@
 // In src/ui/views/mod.rs
 use std::sync::Arc;
 use anyhow::Result;
-use atrium_api::app::bsky::feed::defs::PostViewData;
+use atrium_api::app::bsky::actor::defs::ProfileViewDetailed;
+use atrium_api::app::bsky::feed::defs::{FeedViewPost, PostViewData};
+use atrium_api::app::bsky::feed::get_post_thread::OutputThreadRefs;
 use atrium_api::types::string::AtIdentifier;
-use atrium_api::types::LimitedU16;
+use atrium_api::types::{LimitedU16, Unknown};
+use ipld_core::ipld::Ipld;
 
-use crate::client::api::API;
+use crate::client::bluesky_client::BlueskyClient;
+use crate::ui::filters::FilterPipeline;
 use crate::ui::components::author_profile::AuthorProfile;
-use crate::ui::components::post::types::PostContext;
 use crate::ui::components::post::Post;
-use crate::ui::components::{feed::Feed, images::ImageManager, thread::Thread};
+use crate::ui::components::{feed::{Feed, FeedSource}, images::ImageManager, thread::{Thread, ThreadRow}};
 
 use super::components::author_feed::AuthorFeed;
+use super::components::info_view::InfoView;
 use super::components::notifications::NotificationView;
-use super::components::post_list::PostList;
+use super::components::post_detail::PostDetailView;
+use super::components::post_list::{PostList, SharedHeightCache};
+use super::components::profile_list::{ProfileListSource, ProfileListView};
+use super::components::trending::TrendingView;
+
+// Where the selection was, so it can be found again once a view reloads
+pub struct ViewPosition {
+    key: Option<String>,
+    selected: usize,
+    // Items between the top of the view and the selection
+    rows_from_top: usize,
+}
 
 pub enum View {
     Timeline(Feed),
     Thread(Thread),
     AuthorFeed(AuthorFeed),
     Notifications(NotificationView),
+    Info(InfoView),
+    ProfileList(ProfileListView),
+    Trending(TrendingView),
+    PostDetail(PostDetailView),
 }
 
 impl View {
+    // A few words naming the view, for tab labels
+    pub fn short_title(&self) -> String {
+        match self {
+            View::Timeline(feed) => match &feed.source {
+                FeedSource::Timeline => "Timeline".to_string(),
+                FeedSource::Likes { .. } => "Likes".to_string(),
+                FeedSource::Generator { name, .. } => name.clone(),
+                FeedSource::Hashtag { tag } => format!("#{}", tag),
+                FeedSource::Search { query } => format!("\"{}\"", query),
+            },
+            View::Thread(_) => "Thread".to_string(),
+            View::AuthorFeed(author_feed) => format!("@{}", author_feed.profile.profile.handle.as_str()),
+            View::Notifications(_) => "Notifications".to_string(),
+            View::Info(info_view) => info_view.title.clone(),
+            View::ProfileList(profile_list) => match profile_list.source {
+                ProfileListSource::Likers { .. } => "Liked by".to_string(),
+                ProfileListSource::Reposters { .. } => "Reposted by".to_string(),
+            },
+            View::Trending(_) => "Trending".to_string(),
+            View::PostDetail(_) => "Post".to_string(),
+        }
+    }
+
     pub fn update_post(&mut self, updated_post: atrium_api::app::bsky::feed::defs::PostView) {
         let uri = updated_post.data.uri.clone();
+        // The list and its rendered post share one copy
+        let shared = Arc::new(updated_post);
         match self {
             View::Timeline(feed) => {
                 if let Some(index) = feed.posts.iter().position(|p| p.data.uri == uri) {
                     log::info!("Updating timeline post at index {}", index);
-                    feed.posts[index] = updated_post.clone();
-                    // Recreate the rendered post with existing context
+                    feed.posts[index] = Arc::clone(&shared);
                     if let Some(rendered) = feed.rendered_posts.get_mut(index) {
-                        feed.rendered_posts[index] = Post::new(
-                            updated_post,
-                            PostContext {
-                                image_manager: feed.image_manager.clone(),
-                                indent_level: 0,  // Timeline posts have no indent
-                            }
-                        );
+                        rendered.update(shared);
                     }
                 }
             }
             View::Thread(thread) => {
                 if let Some(index) = thread.posts.iter().position(|p| p.uri == uri) {
                     log::info!("Updating thread post at index {}", index);
-                    thread.posts[index] = updated_post.data.clone();
-                    // Get indent level from relationships
-                    let indent_level = thread.cached_relationships
-                        .as_ref()
-                        .map(|rels| rels.get_indent_level(&uri))
-                        .unwrap_or(0);
-                    
-                    thread.rendered_posts[index] = Post::new(
-                        updated_post,
-                        PostContext {
-                            image_manager: thread.image_manager.clone(),
-                            indent_level,
-                        }
-                    );
+                    thread.posts[index] = Arc::clone(&shared);
+                    thread.rendered_posts[index].update(shared);
                 }
             }
             View::AuthorFeed(author_feed) => {
                 if let Some(index) = author_feed.posts.iter().position(|p| p.data.uri == uri) {
                     log::info!("Updating author_feed post at index {}", index);
-                    author_feed.posts[index] = updated_post.clone();
-                    author_feed.rendered_posts[index] = Post::new(
-                        updated_post,
-                        PostContext {
-                            image_manager: author_feed.image_manager.clone(),
-                            indent_level: 0,  // Author feed posts have no indent
-                        }
-                    );
+                    author_feed.posts[index] = Arc::clone(&shared);
+                    author_feed.rendered_posts[index].update(shared);
                 }
             },
+            View::PostDetail(detail) => {
+                if detail.post.data.uri == uri {
+                    detail.set_post((*shared).clone());
+                }
+            }
             View::Notifications(_notification_view) => {},
+            View::Info(_) | View::ProfileList(_) | View::Trending(_) => {},
+        }
+    }
+
+    // Take fresh counts from the server but keep our own like and repost,
+    // which the appview may not have caught up with yet
+    pub fn refresh_post(&mut self, mut fetched: atrium_api::app::bsky::feed::defs::PostView) {
+        if let Some(current) = self.find_post(&fetched.data.uri) {
+            if let (Some(fetched_viewer), Some(current_viewer)) = (fetched.data.viewer.as_mut(), current.viewer.as_ref()) {
+                fetched_viewer.data.like = current_viewer.data.like.clone();
+                fetched_viewer.data.repost = current_viewer.data.repost.clone();
+            }
+        }
+        self.update_post(fetched);
+    }
+
+    // Our follow record for `did` on every post of theirs here, or none
+    pub fn set_author_following(&mut self, did: &str, record: Option<&str>) {
+        let by_author = |post: &&atrium_api::app::bsky::feed::defs::PostView| post.author.did.as_str() == did;
+        let posts: Vec<atrium_api::app::bsky::feed::defs::PostView> = match self {
+            View::Timeline(feed) => feed.posts.iter().map(|post| &**post).filter(by_author).cloned().collect(),
+            View::Thread(thread) => thread.posts.iter().map(|post| &**post).filter(by_author).cloned().collect(),
+            View::AuthorFeed(author_feed) => author_feed.posts.iter().map(|post| &**post).filter(by_author).cloned().collect(),
+            View::PostDetail(detail) => std::iter::once(&detail.post).filter(by_author).cloned().collect(),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) => Vec::new(),
+        };
+        // The profile at the top of their feed too
+        if let View::AuthorFeed(author_feed) = self {
+            let profile = &mut author_feed.profile.profile.data;
+            if profile.did.as_str() == did {
+                if let Some(viewer) = profile.viewer.as_mut() {
+                    viewer.data.following = record.map(str::to_string);
+                }
+            }
+        }
+        for mut post in posts {
+            if let Some(viewer) = post.data.author.data.viewer.as_mut() {
+                viewer.data.following = record.map(str::to_string);
+            }
+            self.update_post(post);
+        }
+    }
+
+    fn find_post(&self, uri: &str) -> Option<&PostViewData> {
+        match self {
+            View::Timeline(feed) => feed.posts.iter().map(|post| &post.data).find(|post| post.uri == uri),
+            View::Thread(thread) => thread.posts.iter().map(|post| &post.data).find(|post| post.uri == uri),
+            View::AuthorFeed(author_feed) => author_feed.posts.iter().map(|post| &post.data).find(|post| post.uri == uri),
+            View::PostDetail(detail) => Some(&detail.post.data).filter(|post| post.uri == uri),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) => None,
         }
     }
 
@@ -95,7 +169,9 @@ impl View {
                 .map(|post| post.data.uri.to_string())
                 .collect()
             },
+            View::PostDetail(detail) => vec![detail.post.data.uri.clone()],
             View::Notifications(_notification_view) => {Vec::new()},
+            View::Info(_) | View::ProfileList(_) | View::Trending(_) => Vec::new(),
         }
     }
     
@@ -105,6 +181,10 @@ impl View {
             View::Thread(thread) => thread.scroll_down(),
             View::AuthorFeed(author_feed) => author_feed.scroll_down(),
             View::Notifications(notification_view) => notification_view.scroll_down(),
+            View::Info(info_view) => info_view.scroll_down(),
+            View::ProfileList(profile_list) => profile_list.scroll_down(),
+            View::Trending(trending) => trending.scroll_down(),
+            View::PostDetail(detail) => detail.scroll_down(),
         }
     }
 
@@ -114,6 +194,179 @@ impl View {
             View::Thread(thread) => thread.scroll_up(),
             View::AuthorFeed(author_feed) => author_feed.scroll_up(),
             View::Notifications(notification_view) => notification_view.scroll_up(),
+            View::Info(info_view) => info_view.scroll_up(),
+            View::ProfileList(profile_list) => profile_list.scroll_up(),
+            View::Trending(trending) => trending.scroll_up(),
+            View::PostDetail(detail) => detail.scroll_up(),
+        }
+    }
+
+    pub fn page_size(&self) -> usize {
+        match self {
+            View::Timeline(feed) => feed.page_size(),
+            View::Thread(thread) => thread.page_size(),
+            View::AuthorFeed(author_feed) => author_feed.page_size(),
+            View::Notifications(notification_view) => notification_view.page_size(),
+            View::Info(info_view) => info_view.page_size(),
+            View::ProfileList(profile_list) => profile_list.page_size(),
+            View::Trending(trending) => trending.page_size(),
+            View::PostDetail(detail) => detail.page_size(),
+        }
+    }
+
+    // Follow the density setting. Lists of other things keep their layout
+    pub fn set_compact(&mut self, compact: bool) {
+        match self {
+            View::Timeline(feed) => feed.set_compact(compact),
+            View::Thread(thread) => thread.set_compact(compact),
+            View::AuthorFeed(author_feed) => author_feed.set_compact(compact),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => {}
+        }
+    }
+
+    // The item under a mouse click. Info and post detail views are plain
+    // text, so never anything
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        match self {
+            View::Timeline(feed) => feed.item_at(column, row),
+            View::Thread(thread) => thread.item_at(column, row),
+            View::AuthorFeed(author_feed) => author_feed.item_at(column, row),
+            View::Notifications(notification_view) => notification_view.item_at(column, row),
+            View::Info(_) | View::PostDetail(_) => None,
+            View::ProfileList(profile_list) => profile_list.item_at(column, row),
+            View::Trending(trending) => trending.item_at(column, row),
+        }
+    }
+
+    pub fn scroll_down_by(&mut self, steps: usize) {
+        match self {
+            View::Timeline(feed) => feed.scroll_down_by(steps),
+            View::Thread(thread) => thread.scroll_down_by(steps),
+            View::AuthorFeed(author_feed) => author_feed.scroll_down_by(steps),
+            View::Notifications(notification_view) => notification_view.scroll_down_by(steps),
+            View::Info(info_view) => (0..steps).for_each(|_| info_view.scroll_down()),
+            View::ProfileList(profile_list) => profile_list.scroll_down_by(steps),
+            View::Trending(trending) => trending.scroll_down_by(steps),
+            View::PostDetail(detail) => (0..steps).for_each(|_| detail.scroll_down()),
+        }
+    }
+
+    pub fn scroll_up_by(&mut self, steps: usize) {
+        match self {
+            View::Timeline(feed) => feed.scroll_up_by(steps),
+            View::Thread(thread) => thread.scroll_up_by(steps),
+            View::AuthorFeed(author_feed) => author_feed.scroll_up_by(steps),
+            View::Notifications(notification_view) => notification_view.scroll_up_by(steps),
+            View::Info(info_view) => (0..steps).for_each(|_| info_view.scroll_up()),
+            View::ProfileList(profile_list) => profile_list.scroll_up_by(steps),
+            View::Trending(trending) => trending.scroll_up_by(steps),
+            View::PostDetail(detail) => (0..steps).for_each(|_| detail.scroll_up()),
+        }
+    }
+
+    pub fn select_index(&mut self, index: usize) {
+        match self {
+            View::Timeline(feed) => feed.select_index(index),
+            View::Thread(thread) => thread.select_index(index),
+            View::AuthorFeed(author_feed) => author_feed.select_index(index),
+            View::Notifications(notification_view) => notification_view.select_index(index),
+            View::Info(info_view) => info_view.scroll_offset = index.min(info_view.lines.len().saturating_sub(1)),
+            View::ProfileList(profile_list) => profile_list.select_index(index),
+            View::Trending(trending) => trending.select_index(index),
+            View::PostDetail(detail) => detail.scroll_offset = index.min(detail.line_count().saturating_sub(1)),
+        }
+    }
+
+    pub fn item_count(&self) -> usize {
+        match self {
+            View::Timeline(feed) => feed.posts.len(),
+            View::Thread(thread) => thread.rows.len(),
+            View::AuthorFeed(author_feed) => author_feed.posts.len(),
+            View::Notifications(notification_view) => notification_view.row_count(),
+            View::Info(info_view) => info_view.lines.len(),
+            View::ProfileList(profile_list) => profile_list.profiles.len(),
+            View::Trending(trending) => trending.topics.len(),
+            View::PostDetail(detail) => detail.line_count(),
+        }
+    }
+
+    // `key` is whatever selection_key returns for this kind of view
+    pub fn position_of(&self, key: &str) -> Option<usize> {
+        match self {
+            View::Thread(thread) => thread.row_of(key),
+            View::Notifications(notification_view) => notification_view.row_of(key),
+            View::ProfileList(profile_list) => profile_list.profiles
+                .iter()
+                .position(|profile| profile.did.as_str() == key),
+            View::Trending(trending) => trending.topics
+                .iter()
+                .position(|topic| topic.topic == key),
+            _ => self.get_all_post_uris().iter().position(|u| u == key),
+        }
+    }
+
+    // Identifies the selected item independently of its index: a post or
+    // notification URI, an account DID or a trending topic
+    pub fn selection_key(&self) -> Option<String> {
+        match self {
+            View::Notifications(notification_view) => notification_view
+                .selected_notification()
+                .map(|notification| notification.uri.clone()),
+            View::ProfileList(profile_list) => profile_list.get_selected_profile().map(|profile| profile.did.as_str().to_string()),
+            View::Trending(trending) => trending.get_selected_topic().map(|topic| topic.topic.clone()),
+            View::Info(_) => None,
+            _ => self.get_selected_post().map(|post| post.uri),
+        }
+    }
+
+    pub fn selected_index(&self) -> usize {
+        match self {
+            View::Timeline(feed) => feed.selected_index(),
+            View::Thread(thread) => thread.selected_index(),
+            View::AuthorFeed(author_feed) => author_feed.selected_index(),
+            View::Notifications(notification_view) => notification_view.selected_index(),
+            View::Info(info_view) => info_view.selected_index(),
+            View::ProfileList(profile_list) => profile_list.selected_index(),
+            View::Trending(trending) => trending.selected_index(),
+            View::PostDetail(detail) => detail.scroll_offset,
+        }
+    }
+
+    pub fn position(&self) -> ViewPosition {
+        let selected = self.selected_index();
+        let rows_from_top = match self {
+            View::Timeline(feed) => selected.saturating_sub(feed.scroll_offset()),
+            View::Thread(thread) => selected.saturating_sub(thread.scroll_offset()),
+            View::AuthorFeed(author_feed) => selected.saturating_sub(author_feed.scroll_offset()),
+            View::Notifications(notification_view) => selected.saturating_sub(notification_view.scroll_offset()),
+            View::ProfileList(profile_list) => selected.saturating_sub(profile_list.scroll_offset()),
+            View::Trending(trending) => selected.saturating_sub(trending.scroll_offset()),
+            View::Info(_) | View::PostDetail(_) => 0,
+        };
+        ViewPosition { key: self.selection_key(), selected, rows_from_top }
+    }
+
+    // Go back to the item `position` recorded, or as near its old index as
+    // the view now allows if it's gone
+    pub fn restore_position(&mut self, position: ViewPosition) {
+        let count = self.item_count();
+        if count == 0 {
+            return;
+        }
+        let ViewPosition { key, selected, rows_from_top } = position;
+        let index = key
+            .and_then(|key| self.position_of(&key))
+            .unwrap_or(selected.min(count - 1));
+        match self {
+            View::Timeline(feed) => feed.restore_position(index, rows_from_top),
+            View::Thread(thread) => thread.restore_position(index, rows_from_top),
+            View::AuthorFeed(author_feed) => author_feed.restore_position(index, rows_from_top),
+            View::Notifications(notification_view) => notification_view.restore_position(index, rows_from_top),
+            View::ProfileList(profile_list) => profile_list.restore_position(index, rows_from_top),
+            View::Trending(trending) => trending.restore_position(index, rows_from_top),
+            View::Info(info_view) => info_view.scroll_offset = index,
+            // There's only the one post, so stay where the reader scrolled to
+            View::PostDetail(_) => {}
         }
     }
 
@@ -122,7 +375,139 @@ impl View {
             View::Timeline(feed) => feed.get_selected_post(),
             View::Thread(thread) => thread.get_selected_post(),
             View::AuthorFeed(author_feed) => author_feed.get_selected_post(),
+            View::PostDetail(detail) => Some(detail.post.data.clone()),
             View::Notifications(_notification_view) => {None},
+            View::Info(_) | View::ProfileList(_) | View::Trending(_) => None,
+        }
+    }
+
+    pub fn scroll_selected_horizontal(&mut self, delta: isize) {
+        let index = match self {
+            View::Timeline(feed) => feed.selected_index(),
+            View::Thread(thread) => match thread.selected_post_index() {
+                Some(index) => index,
+                None => return,
+            },
+            View::AuthorFeed(author_feed) => author_feed.selected_index(),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => return,
+        };
+        if let Some(post) = self.rendered_posts_mut().and_then(|posts| posts.get_mut(index)) {
+            post.scroll_content_horizontal(delta);
+        }
+    }
+
+    pub fn filters(&self) -> Option<&FilterPipeline> {
+        match self {
+            View::Timeline(feed) => Some(&feed.filters),
+            View::Thread(thread) => Some(&thread.filters),
+            View::AuthorFeed(author_feed) => Some(&author_feed.filters),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => None,
+        }
+    }
+
+    fn rendered_posts_mut(&mut self) -> Option<&mut Vec<Post>> {
+        match self {
+            View::Timeline(feed) => Some(&mut feed.rendered_posts),
+            View::Thread(thread) => Some(&mut thread.rendered_posts),
+            View::AuthorFeed(author_feed) => Some(&mut author_feed.rendered_posts),
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => None,
+        }
+    }
+
+    // Parent author DIDs for replies still showing without a handle
+    pub fn unresolved_reply_dids(&mut self) -> Vec<String> {
+        self.rendered_posts_mut()
+            .map(|posts| posts
+                .iter()
+                .filter_map(|post| post.unresolved_reply_did().map(str::to_string))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    pub fn set_reply_handle(&mut self, did: &str, handle: &str) {
+        if let Some(posts) = self.rendered_posts_mut() {
+            for post in posts.iter_mut() {
+                if post.unresolved_reply_did() == Some(did) {
+                    post.set_reply_to(handle.to_string());
+                }
+            }
+        }
+    }
+
+    // Lowercased text of each item, in order, for an in-view search
+    pub fn search_texts(&self) -> Vec<String> {
+        match self {
+            View::Timeline(feed) => feed.posts.iter().map(|post| Self::post_search_text(&post.data)).collect(),
+            View::AuthorFeed(author_feed) => author_feed.posts.iter().map(|post| Self::post_search_text(&post.data)).collect(),
+            View::Thread(thread) => thread.rows
+                .iter()
+                .map(|row| match row {
+                    ThreadRow::Post(index) => thread.posts.get(*index).map(|post| Self::post_search_text(post)).unwrap_or_default(),
+                    _ => String::new(),
+                })
+                .collect(),
+            View::Notifications(notification_view) => notification_view.row_search_texts(),
+            View::Info(info_view) => info_view.lines
+                .iter()
+                .map(|line| line.spans.iter().map(|span| span.content.as_ref()).collect::<String>().to_lowercase())
+                .collect(),
+            View::ProfileList(profile_list) => profile_list.profiles
+                .iter()
+                .map(|profile| format!(
+                    "{} {} {}",
+                    profile.handle.as_str(),
+                    profile.display_name.as_deref().unwrap_or_default(),
+                    profile.description.as_deref().unwrap_or_default(),
+                ).to_lowercase())
+                .collect(),
+            View::Trending(trending) => trending.topics
+                .iter()
+                .map(|topic| format!("{} {}", topic.name(), topic.description.as_deref().unwrap_or_default()).to_lowercase())
+                .collect(),
+            View::PostDetail(_) => Vec::new(),
+        }
+    }
+
+    fn post_search_text(post: &PostViewData) -> String {
+        let text = match &post.record {
+            Unknown::Object(map) => match map.get("text").map(|text| &**text) {
+                Some(Ipld::String(text)) => text.as_str(),
+                _ => "",
+            },
+            _ => "",
+        };
+        format!(
+            "{} {} {}",
+            post.author.handle.as_str(),
+            post.author.display_name.as_deref().unwrap_or_default(),
+            text,
+        ).to_lowercase()
+    }
+
+    // Outline the posts that match `query` (already lowercased), or clear
+    // the outlines with None. Views without rendered posts have nothing to mark
+    pub fn set_search_highlight(&mut self, query: Option<&str>) {
+        let mark = |post: &PostViewData, rendered: &mut Post| {
+            let matched = query.filter(|query| Self::post_search_text(post).contains(*query));
+            rendered.set_highlight(matched.map(str::to_string));
+        };
+        match self {
+            View::Timeline(feed) => {
+                for (post, rendered) in feed.posts.iter().zip(feed.rendered_posts.iter_mut()) {
+                    mark(&post.data, rendered);
+                }
+            }
+            View::AuthorFeed(author_feed) => {
+                for (post, rendered) in author_feed.posts.iter().zip(author_feed.rendered_posts.iter_mut()) {
+                    mark(&post.data, rendered);
+                }
+            }
+            View::Thread(thread) => {
+                for (post, rendered) in thread.posts.iter().zip(thread.rendered_posts.iter_mut()) {
+                    mark(&post.data, rendered);
+                }
+            }
+            View::Notifications(_) | View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => {}
         }
     }
 
@@ -136,10 +521,7 @@ impl View {
     pub fn remove_post(&mut self, uri: &str) {
         match self {
             View::Timeline(feed) => {
-                if let Some(index) = feed.posts.iter().position(|p| p.data.uri == uri) {
-                    feed.posts.remove(index);
-                    feed.rendered_posts.remove(index);
-                }
+                feed.remove_post(uri);
             }
             View::Thread(thread) => {
                 if let Some(index) = thread.posts.iter().position(|p| p.uri == uri) {
@@ -155,6 +537,7 @@ impl View {
                 }
             }
             View::Notifications(_) => {},
+            View::Info(_) | View::ProfileList(_) | View::Trending(_) | View::PostDetail(_) => {},
         }
     }
 }
@@ -162,14 +545,17 @@ impl View {
 pub struct ViewStack {
     pub views: Vec<View>,
     pub image_manager: Arc<ImageManager>,
+    // One for the whole app, so every column and tab can use it
+    pub height_cache: SharedHeightCache,
 }
 
 impl ViewStack {
-    pub fn new(image_manager: Arc<ImageManager>) -> Self {
-        let initial_feed = Feed::new(Arc::clone(&image_manager));
+    pub fn new(image_manager: Arc<ImageManager>, height_cache: SharedHeightCache) -> Self {
+        let initial_feed = Feed::new(Arc::clone(&image_manager), Arc::clone(&height_cache));
         Self {
             views: vec![View::Timeline(initial_feed)],
             image_manager,
+            height_cache,
         }
     }
 
@@ -178,70 +564,111 @@ impl ViewStack {
     }
     
 
-    pub async fn push_thread_view(&mut self, uri: String, api: &API) -> Result<()> {
+    pub async fn push_thread_view(&mut self, uri: String, api: &impl BlueskyClient) -> Result<()> {
+        let thread_refs = Self::fetch_thread(uri, api).await?;
+        self.push_thread(thread_refs);
+        Ok(())
+    }
+
+    // The network half of push_thread_view, which needs no ViewStack so it
+    // can run on a spawned task
+    pub async fn fetch_thread(uri: String, api: &impl BlueskyClient) -> Result<OutputThreadRefs> {
         log::info!("Attempting to create thread view for URI: {}", uri);
         
-        let params = atrium_api::app::bsky::feed::get_post_thread::Parameters {
-            data: atrium_api::app::bsky::feed::get_post_thread::ParametersData {
-                uri: uri.into(),
-                depth: Some(LimitedU16::MAX),
-                parent_height: Some(LimitedU16::MAX),
-            },
-            extra_data: ipld_core::ipld::Ipld::Null,
+        let params = atrium_api::app::bsky::feed::get_post_thread::ParametersData {
+            uri,
+            depth: Some(LimitedU16::MAX),
+            parent_height: Some(LimitedU16::MAX),
         };
         
-        match api.agent.api.app.bsky.feed.get_post_thread(params).await {
-            Ok(response) => {
-                let thread_refs = match response.data.thread {
-                    atrium_api::types::Union::Refs(refs) => refs,
-                    atrium_api::types::Union::Unknown(unknown) => {
-                        return Err(anyhow::anyhow!(
-                            "Unknown thread data type: {}, data: {:?}", 
-                            unknown.r#type, 
-                            unknown.data
-                        ))
-                    }
-                };
-    
-                let thread_view = Thread::new(thread_refs, Arc::clone(&self.image_manager));
-                self.views.push(View::Thread(thread_view));
-                Ok(())
-            }
-            Err(e) => Err(e.into())
+        match api.get_post_thread(params).await {
+            Ok(response) => match response.data.thread {
+                atrium_api::types::Union::Refs(refs) => Ok(refs),
+                atrium_api::types::Union::Unknown(unknown) => {
+                    Err(anyhow::anyhow!(
+                        "Unknown thread data type: {}, data: {:?}", 
+                        unknown.r#type, 
+                        unknown.data
+                    ))
+                }
+            },
+            Err(e) => Err(e)
         }
     }
 
-    pub async fn push_author_feed_view(&mut self, actor: AtIdentifier, api: &API) -> Result<()> {
+    pub fn push_thread(&mut self, thread_refs: OutputThreadRefs) {
+        let thread_view = Thread::new(thread_refs, Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
+        self.views.push(View::Thread(thread_view));
+    }
+
+    pub async fn push_author_feed_view(&mut self, actor: AtIdentifier, api: &impl BlueskyClient) -> Result<()> {
+        let (profile, feed) = Self::fetch_author_feed(actor, api).await?;
+        self.push_author_feed(profile, feed);
+        Ok(())
+    }
+
+    // Same as fetch_thread: the profile and first page of posts
+    pub async fn fetch_author_feed(actor: AtIdentifier, api: &impl BlueskyClient) -> Result<(ProfileViewDetailed, Vec<FeedViewPost>)> {
         log::info!("Attempting to create author feed view from AtIdentifier: {:?}", actor);
-        let get_author_feed_params = atrium_api::app::bsky::feed::get_author_feed::Parameters {
-            data: atrium_api::app::bsky::feed::get_author_feed::ParametersData{
-                actor: actor.clone(),
-                cursor: None,
-                filter: None, // TODO: Examine this field better
-                include_pins: None,
-                limit: None,
-            },
-            extra_data: ipld_core::ipld::Ipld::Null,
+        let get_author_feed_params = atrium_api::app::bsky::feed::get_author_feed::ParametersData {
+            actor: actor.clone(),
+            cursor: None,
+            filter: None, // TODO: Examine this field better
+            include_pins: Some(true),
+            limit: None,
         };
 
-        match api.agent.api.app.bsky.feed.get_author_feed(get_author_feed_params).await {
-            Ok(response) => {
-                let author_feed_data = response.feed.iter().map(|p| p.post.clone()).collect();
-                let author_profile_data = api.agent.api.app.bsky.actor.get_profile(
-                    atrium_api::app::bsky::actor::get_profile::ParametersData {
-                        actor
-                    }.into()
-                ).await?;
-                let author_profile = AuthorProfile::new(author_profile_data, self.image_manager.clone());
-                let author_feed_view = AuthorFeed::new(author_profile, author_feed_data, self.image_manager.clone());
-                self.views.push(View::AuthorFeed(author_feed_view));
+        let response = api.get_author_feed(get_author_feed_params).await?;
+        let author_profile_data = api.get_profile(
+            atrium_api::app::bsky::actor::get_profile::ParametersData {
+                actor
             }
-            Err(e) => {return Err(e.into())}
-        }
-        Ok(())
+        ).await?;
+        Ok((author_profile_data, response.feed.clone()))
+    }
+
+    pub fn push_author_feed(&mut self, profile: ProfileViewDetailed, feed: Vec<FeedViewPost>) {
+        let author_profile = AuthorProfile::new(profile, self.image_manager.clone());
+        let author_feed_view = AuthorFeed::new(author_profile, feed, self.image_manager.clone(), self.height_cache.clone());
+        self.views.push(View::AuthorFeed(author_feed_view));
     }
     
 
+    pub async fn push_feed_view(&mut self, source: FeedSource, api: &mut impl BlueskyClient) -> Result<()> {
+        let mut feed = Feed::with_source(source, Arc::clone(&self.image_manager), Arc::clone(&self.height_cache));
+        feed.load_initial_posts(api).await?;
+        self.views.push(View::Timeline(feed));
+        Ok(())
+    }
+
+    pub async fn push_profile_list_view(&mut self, source: ProfileListSource, api: &impl BlueskyClient) -> Result<()> {
+        let mut profile_list = ProfileListView::new(source);
+        profile_list.load_initial(api).await?;
+        self.views.push(View::ProfileList(profile_list));
+        Ok(())
+    }
+
+    pub async fn push_trending_view(&mut self, api: &impl BlueskyClient) -> Result<()> {
+        let mut trending = TrendingView::new();
+        trending.load(api).await?;
+        self.views.push(View::Trending(trending));
+        Ok(())
+    }
+
+    pub fn push_info_view(&mut self, info_view: InfoView) {
+        self.views.push(View::Info(info_view));
+    }
+
+    pub fn push_post_detail_view(&mut self, post: atrium_api::app::bsky::feed::defs::PostView) {
+        let detail = PostDetailView::new(post, Arc::clone(&self.image_manager));
+        self.views.push(View::PostDetail(detail));
+    }
+
+    // Where Esc leads back through, e.g. ["Timeline", "@alice", "Thread"]
+    pub fn breadcrumb(&self) -> Vec<String> {
+        self.views.iter().map(View::short_title).collect()
+    }
+
     pub fn pop_view(&mut self) -> Option<View> {
         if self.views.len() > 1 {
             self.views.pop()